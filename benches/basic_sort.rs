@@ -1,13 +1,23 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use block_pseudorand::block_rand;
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput,
+};
+use rdst::primitives::{get_counts, in_place_sort, out_of_place_sort, regions_sort};
+use rdst::tuner::{Algorithm, TileParams, Tuner, TuningParams};
+use rdst::tuners::{LowMemoryTuner, StandardTuner};
 use rdst::utils::bench_utils::bench_single;
 use rdst::utils::test_utils::NumericTest;
-use rdst::RadixSort;
+use rdst::{radix_sort_batch, RadixSort};
+use std::time::Duration;
+
+// A named sort to benchmark against the others
+type SortFn<T> = Box<dyn Fn(Vec<T>)>;
 
 fn basic_sort_set<T>(c: &mut Criterion, suffix: &str, shift: T, count: usize)
 where
     T: NumericTest<T>,
 {
-    let tests: Vec<(&str, Box<dyn Fn(Vec<T>)>)> = vec![
+    let tests: Vec<(&str, SortFn<T>)> = vec![
         (
             "rdst",
             Box::new(|mut input| {
@@ -41,7 +51,356 @@ where
 
 fn basic_sort(c: &mut Criterion) {
     basic_sort_set(c, "u32", 0u32, 10_000_000);
+    basic_sort_set(c, "u64", 0u64, 10_000_000);
+}
+
+// Forces `Algorithm::MtLsb`, splitting each bucket into `tiles_per_thread` tiles per thread
+struct MtLsbTuner(usize);
+
+impl Tuner for MtLsbTuner {
+    fn pick_algorithm(&self, _p: &TuningParams, _counts: &[usize]) -> Algorithm {
+        Algorithm::MtLsb
+    }
+
+    fn tile_params(&self) -> TileParams {
+        TileParams {
+            tiles_per_thread: self.0,
+            ..Default::default()
+        }
+    }
+}
+
+// Compares tile sizes for the multi-threaded LSB sort, from one tile per thread (the default) to
+// many smaller tiles per thread
+fn mt_lsb_sort(c: &mut Criterion) {
+    let mut tests: Vec<(&str, SortFn<u32>)> = vec![(
+        "rdst_mt_lsb",
+        Box::new(|mut input| {
+            input
+                .radix_sort_builder()
+                .with_algorithm(Algorithm::MtLsb)
+                .sort();
+
+            black_box(input);
+        }),
+    )];
+
+    for (name, tiles_per_thread) in [("rdst_mt_lsb_x4_tiles", 4), ("rdst_mt_lsb_x16_tiles", 16)] {
+        tests.push((
+            name,
+            Box::new(move |mut input| {
+                input
+                    .radix_sort_builder()
+                    .with_tuner(&MtLsbTuner(tiles_per_thread))
+                    .sort();
+
+                black_box(input);
+            }),
+        ));
+    }
+
+    bench_single(c, "mt_lsb_sort_u32", tests, 0u32, 50_000_000);
+}
+
+// Forces the algorithm used for the top level only, leaving the rest to `StandardTuner`
+struct TopLevelTuner(Algorithm);
+
+impl Tuner for TopLevelTuner {
+    fn pick_algorithm(&self, p: &TuningParams, counts: &[usize]) -> Algorithm {
+        if p.level == p.total_levels - 1 {
+            self.0
+        } else {
+            StandardTuner.pick_algorithm(p, counts)
+        }
+    }
+}
+
+// Compares the serial and parallel top level distributions in the range where the
+// `StandardTuner` switches between them.
+fn top_level_sort(c: &mut Criterion) {
+    let algorithms = [
+        ("ska", Algorithm::Ska),
+        ("mt_lsb", Algorithm::MtLsb),
+        ("recombinating", Algorithm::Recombinating),
+        ("regions", Algorithm::Regions),
+    ];
+
+    for count in [500_000, 1_000_000] {
+        let mut tests: Vec<(&str, SortFn<u32>)> = vec![(
+            "rdst",
+            Box::new(|mut input| {
+                input.radix_sort_unstable();
+                black_box(input);
+            }),
+        )];
+
+        for (name, algorithm) in algorithms {
+            tests.push((
+                name,
+                Box::new(move |mut input| {
+                    input
+                        .radix_sort_builder()
+                        .with_tuner(&TopLevelTuner(algorithm))
+                        .sort();
+
+                    black_box(input);
+                }),
+            ));
+        }
+
+        bench_single(c, "top_level_sort_u32", tests, 0u32, count);
+    }
+}
+
+// Keys clustered around a few values, so that many buckets deep in the tree share the same byte
+// on the levels below them
+fn clustered_sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clustered_sort_u64");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.warm_up_time(Duration::from_secs(1));
+
+    for count in [10_000, 100_000, 1_000_000] {
+        let input: Vec<u64> = block_rand::<u64>(count)
+            .into_iter()
+            .map(|v| ((v & 0xF) << 56) | (v & 0xFFFF_0000) | 0x0000_1234_0000_0000)
+            .collect();
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::new("rdst", count), &input, |bench, input| {
+            bench.iter_batched(
+                || input.clone(),
+                |mut input| {
+                    input.radix_sort_unstable();
+                    black_box(input);
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+// Many small, independent sorts, comparing sorting each in turn with sorting them as a batch
+fn batch_sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch_sort_u32");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.warm_up_time(Duration::from_secs(1));
+
+    let input: Vec<Vec<u32>> = (0..10_000).map(|_| block_rand::<u32>(500)).collect();
+    group.throughput(Throughput::Elements(10_000 * 500));
+
+    group.bench_function("rdst_each", |bench| {
+        bench.iter_batched(
+            || input.clone(),
+            |mut input| {
+                input.iter_mut().for_each(|v| v.radix_sort_unstable());
+                black_box(input);
+            },
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.bench_function("rdst_batch", |bench| {
+        bench.iter_batched(
+            || input.clone(),
+            |mut input| {
+                let mut slices: Vec<&mut [u32]> = input.iter_mut().map(|v| &mut v[..]).collect();
+                radix_sort_batch(&mut slices);
+                black_box(input);
+            },
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.finish();
+}
+
+// A single level placed in-place with swaps, against scattering out-of-place into a new buffer
+// the size of the input, which is the extra memory the in-place placement saves
+fn single_level_placement(c: &mut Criterion) {
+    let mut group = c.benchmark_group("single_level_placement_u64");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.warm_up_time(Duration::from_secs(1));
+
+    for count in [100_000, 1_000_000, 10_000_000] {
+        let input = block_rand::<u64>(count);
+        let (counts, _) = get_counts(&input, 0);
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(
+            BenchmarkId::new("out_of_place", count),
+            &input,
+            |bench, input| {
+                bench.iter_batched(
+                    || input.clone(),
+                    |input| {
+                        let mut dst = vec![0u64; input.len()];
+                        out_of_place_sort(&input, &mut dst, &counts, 0);
+                        black_box(dst);
+                    },
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("in_place", count),
+            &input,
+            |bench, input| {
+                bench.iter_batched(
+                    || input.clone(),
+                    |mut input| {
+                        in_place_sort(&mut input, &counts, 0);
+                        black_box(input);
+                    },
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// The top level placed in-place across every thread by the regions sort, against scattering
+// out-of-place into a new buffer the size of the input
+fn regions_placement(c: &mut Criterion) {
+    let mut group = c.benchmark_group("regions_placement_u64");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.warm_up_time(Duration::from_secs(1));
+
+    for count in [1_000_000, 10_000_000, 50_000_000] {
+        let input = block_rand::<u64>(count);
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(
+            BenchmarkId::new("out_of_place", count),
+            &input,
+            |bench, input| {
+                bench.iter_batched(
+                    || input.clone(),
+                    |input| {
+                        let (counts, _) = get_counts(&input, 7);
+                        let mut dst = vec![0u64; input.len()];
+                        out_of_place_sort(&input, &mut dst, &counts, 7);
+                        black_box(dst);
+                    },
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("regions", count),
+            &input,
+            |bench, input| {
+                bench.iter_batched(
+                    || input.clone(),
+                    |mut input| {
+                        regions_sort(&mut input, 7, &LowMemoryTuner);
+                        black_box(input);
+                    },
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// Keys padded with zero bytes between the significant ones, which an LSB sort can skip rather
+// than scattering without moving anything
+fn padded_lsb_sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("padded_lsb_sort_u64");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.warm_up_time(Duration::from_secs(1));
+
+    for count in [100_000, 1_000_000, 10_000_000] {
+        let input: Vec<u64> = block_rand::<u64>(count)
+            .into_iter()
+            .map(|v| v & 0xFF00_0000_0000_FFFF)
+            .collect();
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(
+            BenchmarkId::new("rdst_lsb", count),
+            &input,
+            |bench, input| {
+                bench.iter_batched(
+                    || input.clone(),
+                    |mut input| {
+                        input
+                            .radix_sort_builder()
+                            .with_algorithm(Algorithm::Lsb)
+                            .sort();
+                        black_box(input);
+                    },
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// 99% sorted data, with every 100th item backfilled with a random value, comparing a sort with the
+// presorted fast path enabled, which only sorts the items out of place, against the default full
+// radix sort
+fn presorted_sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("presorted_sort_u64");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.warm_up_time(Duration::from_secs(1));
+
+    for count in [1_000_000, 10_000_000] {
+        let noise = block_rand::<u64>(count);
+        let input: Vec<u64> = (0..count)
+            .map(|i| {
+                if i % 100 == 0 {
+                    noise[i]
+                } else {
+                    (i as u64) << 20
+                }
+            })
+            .collect();
+
+        group.throughput(Throughput::Elements(count as u64));
+        for (name, threshold) in [("rdst_presorted_0.05", 0.05), ("rdst", 0.0)] {
+            group.bench_with_input(BenchmarkId::new(name, count), &input, |bench, input| {
+                bench.iter_batched(
+                    || input.clone(),
+                    |mut input| {
+                        input
+                            .radix_sort_builder()
+                            .with_presorted_threshold(threshold)
+                            .sort();
+                        black_box(input);
+                    },
+                    BatchSize::LargeInput,
+                );
+            });
+        }
+    }
+
+    group.finish();
 }
 
-criterion_group!(benches, basic_sort,);
+criterion_group!(
+    benches,
+    basic_sort,
+    mt_lsb_sort,
+    top_level_sort,
+    clustered_sort,
+    batch_sort,
+    single_level_placement,
+    regions_placement,
+    padded_lsb_sort,
+    presorted_sort,
+);
 criterion_main!(benches);
@@ -4,11 +4,14 @@ use rdst::utils::test_utils::NumericTest;
 use rdst::RadixSort;
 use voracious_radix_sort::{RadixKey as VorKey, RadixSort as Vor, Radixable};
 
+// A named sort to benchmark against the others
+type SortFn<T> = Box<dyn Fn(Vec<T>)>;
+
 fn full_sort_common<T>(c: &mut Criterion, shift: T, name_suffix: &str)
 where
     T: NumericTest<T> + Radixable<T> + VorKey,
 {
-    let tests: Vec<(&str, Box<dyn Fn(Vec<_>)>)> = vec![
+    let tests: Vec<(&str, SortFn<_>)> = vec![
         (
             "rdst",
             Box::new(|mut input| {
@@ -39,7 +42,7 @@ fn full_sort_medley_set<T>(c: &mut Criterion, suffix: &str, shift: T)
 where
     T: NumericTest<T> + Radixable<T> + VorKey,
 {
-    let tests: Vec<(&str, Box<dyn Fn(Vec<T>)>)> = vec![
+    let tests: Vec<(&str, SortFn<T>)> = vec![
         (
             "rdst",
             Box::new(|mut input| {
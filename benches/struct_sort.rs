@@ -3,11 +3,14 @@ use criterion::{
     black_box, criterion_group, criterion_main, AxisScale, BatchSize, BenchmarkId, Criterion,
     PlotConfiguration, Throughput,
 };
-use rdst::{RadixKey, RadixSort};
+use rdst::{radix_sort_by_cached_key, RadixKey, RadixSort};
 use std::cmp::Ordering;
 use std::time::Duration;
 use voracious_radix_sort::{RadixSort as Vor, Radixable};
 
+// A named sort to benchmark against the others
+type SortFn<T> = Box<dyn Fn(Vec<T>)>;
+
 #[derive(Debug, Clone, Copy)]
 pub struct LargeStruct {
     pub sort_key: f32,
@@ -90,7 +93,7 @@ fn full_sort_struct(c: &mut Criterion) {
         ("409k already sorted", gen_input_sorted(409_600)),
     ];
 
-    let tests: Vec<(&str, Box<dyn Fn(Vec<LargeStruct>)>)> = vec![
+    let tests: Vec<(&str, SortFn<LargeStruct>)> = vec![
         (
             "rdst",
             Box::new(|mut input| {
@@ -147,5 +150,155 @@ fn full_sort_struct(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(struct_sort, full_sort_struct,);
+// 256 bytes, sorted by a small key at the start
+#[derive(Debug, Clone, Copy)]
+pub struct HugeStruct {
+    pub sort_key: u32,
+    pub payload: [u32; 63],
+}
+
+impl RadixKey for HugeStruct {
+    const LEVELS: usize = 4;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        self.sort_key.get_level(level)
+    }
+}
+
+// Compares sorting the structs directly against sorting a compact buffer of extracted keys, and
+// moving the structs into place once at the end.
+fn huge_struct_sort(c: &mut Criterion) {
+    let input_sets: Vec<Vec<HugeStruct>> = [100_000, 500_000]
+        .iter()
+        .map(|n| {
+            block_rand::<u32>(*n)
+                .into_iter()
+                .map(|sort_key| HugeStruct {
+                    sort_key,
+                    payload: [0; 63],
+                })
+                .collect()
+        })
+        .collect();
+
+    let tests: Vec<(&str, SortFn<HugeStruct>)> = vec![
+        (
+            "rdst",
+            Box::new(|mut input| {
+                input.radix_sort_unstable();
+                black_box(input);
+            }),
+        ),
+        (
+            "rdst_cached_key",
+            Box::new(|mut input| {
+                radix_sort_by_cached_key(&mut input, |v| v.sort_key);
+                black_box(input);
+            }),
+        ),
+    ];
+
+    let mut group = c.benchmark_group("huge_struct_sort");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.warm_up_time(Duration::from_secs(1));
+
+    for set in input_sets.iter() {
+        let l = set.len();
+        group.throughput(Throughput::Elements(l as u64));
+
+        for t in tests.iter() {
+            group.bench_with_input(BenchmarkId::new(t.0, l), set, |bench, set| {
+                bench.iter_batched(|| set.clone(), &*t.1, BatchSize::LargeInput);
+            });
+        }
+    }
+
+    group.finish();
+}
+
+// A record partitioned by a boolean-like flag, which only ever takes two values
+#[derive(Debug, Clone, Copy)]
+pub struct FlaggedStruct {
+    pub flag: u8,
+    pub id: u64,
+}
+
+impl RadixKey for FlaggedStruct {
+    const LEVELS: usize = 1;
+
+    #[inline]
+    fn get_level(&self, _level: usize) -> u8 {
+        self.flag
+    }
+}
+
+fn two_value_sort(c: &mut Criterion) {
+    let input_sets: Vec<Vec<FlaggedStruct>> = [1_000_000, 10_000_000]
+        .iter()
+        .map(|n| {
+            block_rand::<u64>(*n)
+                .into_iter()
+                .map(|id| FlaggedStruct {
+                    flag: (id & 1) as u8,
+                    id,
+                })
+                .collect()
+        })
+        .collect();
+
+    let tests: Vec<(&str, SortFn<FlaggedStruct>)> = vec![
+        (
+            "rdst",
+            Box::new(|mut input| {
+                input.radix_sort_unstable();
+                black_box(input);
+            }),
+        ),
+        (
+            "rdst_single_threaded",
+            Box::new(|mut input| {
+                input
+                    .radix_sort_builder()
+                    .with_single_threaded_tuner()
+                    .with_parallel(false)
+                    .sort();
+                black_box(input);
+            }),
+        ),
+        (
+            "sort",
+            Box::new(|mut input| {
+                input.sort_unstable_by_key(|v| v.flag);
+                black_box(input);
+            }),
+        ),
+    ];
+
+    let mut group = c.benchmark_group("two_value_sort");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.warm_up_time(Duration::from_secs(1));
+
+    for set in input_sets.iter() {
+        let l = set.len();
+        group.throughput(Throughput::Elements(l as u64));
+
+        for t in tests.iter() {
+            group.bench_with_input(BenchmarkId::new(t.0, l), set, |bench, set| {
+                bench.iter_batched(|| set.clone(), &*t.1, BatchSize::LargeInput);
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    struct_sort,
+    full_sort_struct,
+    huge_struct_sort,
+    two_value_sort,
+);
 criterion_main!(struct_sort);
@@ -1,11 +1,27 @@
+use block_pseudorand::block_rand;
 use criterion::*;
 use rayon::current_num_threads;
+use rdst::tuner::{Algorithm, CountingParams, Tuner, TuningParams};
+use rdst::tuners::StandardTuner;
 use rdst::utils::bench_utils::bench_common;
 use rdst::utils::*;
+use rdst::{RadixKey, RadixSort};
 use std::cmp::max;
+use std::time::Duration;
 
+// A named sort to benchmark against the others
+type SortFn<T> = Box<dyn Fn(Vec<T>)>;
+
+// Compare `get_counts` against the always-parallel `par_get_counts_div_*` variants to find where
+// parallel counting starts to win on your machine. That crossover is a good value for
+// `CountingParams::par_count_threshold`, and the fastest divisor for `par_count_chunk_divisor`.
 fn tune_counts(c: &mut Criterion) {
-    let tests: Vec<(&str, Box<dyn Fn(Vec<_>)>)> = vec![
+    let always_par = |par_count_chunk_divisor| CountingParams {
+        par_count_threshold: 0,
+        par_count_chunk_divisor,
+    };
+
+    let tests: Vec<(&str, SortFn<_>)> = vec![
         (
             "get_counts",
             Box::new(|input: Vec<_>| {
@@ -16,7 +32,28 @@ fn tune_counts(c: &mut Criterion) {
         (
             "par_get_counts",
             Box::new(|input: Vec<_>| {
-                let (c, _) = par_get_counts(&input, 0);
+                let (c, _) = par_get_counts(&input, 0, &CountingParams::default());
+                black_box(c);
+            }),
+        ),
+        (
+            "par_get_counts_div_4",
+            Box::new(move |input: Vec<_>| {
+                let (c, _) = par_get_counts(&input, 0, &always_par(4));
+                black_box(c);
+            }),
+        ),
+        (
+            "par_get_counts_div_8",
+            Box::new(move |input: Vec<_>| {
+                let (c, _) = par_get_counts(&input, 0, &always_par(8));
+                black_box(c);
+            }),
+        ),
+        (
+            "par_get_counts_div_16",
+            Box::new(move |input: Vec<_>| {
+                let (c, _) = par_get_counts(&input, 0, &always_par(16));
                 black_box(c);
             }),
         ),
@@ -24,7 +61,7 @@ fn tune_counts(c: &mut Criterion) {
             "get_tile_counts",
             Box::new(|input: Vec<_>| {
                 let tile_size = max(30_000, cdiv(input.len(), current_num_threads()));
-                let (c, _) = get_tile_counts(&input, tile_size, 0);
+                let (c, _) = get_tile_counts(&input, tile_size, 0, &CountingParams::default());
                 black_box(c);
             }),
         ),
@@ -32,7 +69,7 @@ fn tune_counts(c: &mut Criterion) {
             "get_tile_counts_and_aggregate",
             Box::new(|input: Vec<_>| {
                 let tile_size = max(30_000, cdiv(input.len(), current_num_threads()));
-                let (c, _) = get_tile_counts(&input, tile_size, 0);
+                let (c, _) = get_tile_counts(&input, tile_size, 0, &CountingParams::default());
                 let a = aggregate_tile_counts(&c);
                 black_box(a);
             }),
@@ -42,5 +79,294 @@ fn tune_counts(c: &mut Criterion) {
     bench_common(c, 0u32, "tune_counts", tests);
 }
 
-criterion_group!(tuning_parameters, tune_counts,);
+// Compares counting every level one level at a time against counting every level in a single
+// pass via `RadixKey::get_all_levels`.
+fn tune_all_counts(c: &mut Criterion) {
+    let tests: Vec<(&str, SortFn<u64>)> = vec![
+        (
+            "get_counts_per_level",
+            Box::new(|input: Vec<u64>| {
+                for level in 0..u64::LEVELS {
+                    let (c, _) = get_counts(&input, level);
+                    black_box(c);
+                }
+            }),
+        ),
+        (
+            "get_all_counts",
+            Box::new(|input: Vec<u64>| {
+                let c = get_all_counts(&input);
+                black_box(c);
+            }),
+        ),
+    ];
+
+    bench_common(c, 0u64, "tune_all_counts", tests);
+}
+
+fn byte_array_counts_set<const N: usize>(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("tune_byte_array_counts_{}", N));
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.warm_up_time(Duration::from_secs(1));
+
+    for len in [100_000, 1_000_000, 10_000_000] {
+        let input = block_rand::<[u8; N]>(len);
+        group.throughput(Throughput::Elements(len as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("get_counts_per_level", len),
+            &input,
+            |bench, input| {
+                bench.iter(|| {
+                    for level in 0..N {
+                        let (c, _) = get_counts(input, level);
+                        black_box(c);
+                    }
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("get_all_counts", len),
+            &input,
+            |bench, input| bench.iter(|| black_box(get_all_counts(input))),
+        );
+    }
+
+    group.finish();
+}
+
+// Counting a level of a `[u8; N]` key only reads one byte per item, but still pulls in the
+// whole item's cache line(s), so this is memory-bound rather than limited by byte extraction.
+fn tune_byte_array_counts(c: &mut Criterion) {
+    byte_array_counts_set::<16>(c);
+    byte_array_counts_set::<36>(c);
+}
+
+fn counts_by_size_set<T>(c: &mut Criterion, name: &str, input: Vec<T>)
+where
+    T: RadixKey + Send + Sync,
+{
+    let mut group = c.benchmark_group(format!("tune_counts_by_size_{}", name));
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.warm_up_time(Duration::from_secs(1));
+
+    let always_par = CountingParams {
+        par_count_threshold: 0,
+        ..Default::default()
+    };
+
+    for len in [10_000, 25_000, 100_000, 400_000, 1_000_000] {
+        let input = &input[..len];
+        let level = T::LEVELS - 1;
+        group.throughput(Throughput::Elements(len as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("get_counts", len),
+            input,
+            |bench, input| bench.iter(|| black_box(get_counts(input, level))),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("par_get_counts", len),
+            input,
+            |bench, input| {
+                bench.iter(|| black_box(par_get_counts(input, level, &CountingParams::default())))
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("par_get_counts_always", len),
+            input,
+            |bench, input| bench.iter(|| black_box(par_get_counts(input, level, &always_par))),
+        );
+    }
+
+    group.finish();
+}
+
+// Compares single-threaded and parallel counting for small and large items, around the crossover
+// given by `CountingParams::par_count_threshold_for()`, which is 400_000 items for a `u32` but
+// 25_000 for a 256-byte item. `par_get_counts` should track whichever of the other two is faster
+// for both.
+fn tune_counts_by_size(c: &mut Criterion) {
+    counts_by_size_set(c, "u32", block_rand::<u32>(1_000_000));
+    counts_by_size_set(c, "256_bytes", block_rand::<[u8; 256]>(1_000_000));
+}
+
+struct TaskSizeTuner(usize);
+
+impl Tuner for TaskSizeTuner {
+    fn pick_algorithm(&self, _p: &TuningParams, _counts: &[usize]) -> Algorithm {
+        Algorithm::Ska
+    }
+
+    fn min_task_size(&self) -> usize {
+        self.0
+    }
+}
+
+// A 64-byte key with long runs of levels shared by every item in a bucket, which an MSB sort has
+// to descend one level at a time. Below `Tuner::min_task_size()`, each of those buckets is sorted
+// serially, rather than spawned as a new task at every level. To count the tasks spawned, run with
+// `--features work_profiles` and count the `SPAWN` lines.
+fn tune_min_task_size(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tune_min_task_size");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+    group.warm_up_time(Duration::from_secs(1));
+
+    let input: Vec<[u8; 64]> = block_rand::<u32>(3_000_000)
+        .into_iter()
+        .map(|v| {
+            let mut key = [0u8; 64];
+            key[63] = v as u8;
+            key[40] = (v >> 8) as u8 % 4;
+            key[20] = (v >> 16) as u8;
+            key
+        })
+        .collect();
+    group.throughput(Throughput::Elements(input.len() as u64));
+
+    for min_task_size in [128, 4_096, 65_536] {
+        group.bench_with_input(
+            BenchmarkId::new("ska", min_task_size),
+            &input,
+            |bench, input| {
+                bench.iter_batched(
+                    || input.clone(),
+                    |mut input| {
+                        input
+                            .radix_sort_builder()
+                            .with_tuner(&TaskSizeTuner(min_task_size))
+                            .sort();
+                        black_box(input);
+                    },
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+
+    group.finish();
+}
+
+struct InsertionSortTuner(usize);
+
+impl Tuner for InsertionSortTuner {
+    fn pick_algorithm(&self, _p: &TuningParams, _counts: &[usize]) -> Algorithm {
+        Algorithm::Ska
+    }
+
+    fn insertion_sort_threshold(&self) -> usize {
+        self.0
+    }
+}
+
+// An MSB sort of random keys leaves a huge number of buckets with a few dozen items or fewer,
+// which are finished by comparison. The fastest threshold is a good value for
+// `Tuner::insertion_sort_threshold()`, with `0` never using the insertion sort.
+fn tune_insertion_sort_threshold(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tune_insertion_sort_threshold");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.warm_up_time(Duration::from_secs(1));
+
+    for len in [1_000_000, 3_000_000] {
+        let input = block_rand::<u64>(len);
+        group.throughput(Throughput::Elements(len as u64));
+
+        for threshold in [0, 8, 16, 20, 32, 64] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}_items", len), threshold),
+                &input,
+                |bench, input| {
+                    bench.iter_batched(
+                        || input.clone(),
+                        |mut input| {
+                            input
+                                .radix_sort_builder()
+                                .with_tuner(&InsertionSortTuner(threshold))
+                                .sort();
+                            black_box(input);
+                        },
+                        BatchSize::LargeInput,
+                    );
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+struct CountingSortTuner(usize);
+
+impl Tuner for CountingSortTuner {
+    fn pick_algorithm(&self, p: &TuningParams, counts: &[usize]) -> Algorithm {
+        StandardTuner.pick_algorithm(p, counts)
+    }
+
+    fn counting_sort_threshold(&self) -> usize {
+        self.0
+    }
+}
+
+// Compares sorting `u16` by counting every key into 65536 buckets against a pass per level, both
+// single-threaded, as counting is only used for single-threaded sorts. The smallest length where
+// counting wins is a good value for `Tuner::counting_sort_threshold()`. The multi-threaded sort is
+// included for reference.
+fn tune_counting_sort_threshold(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tune_counting_sort_threshold");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.warm_up_time(Duration::from_secs(1));
+
+    for len in [
+        100_000, 300_000, 1_000_000, 3_000_000, 10_000_000, 50_000_000,
+    ] {
+        let input = block_rand::<u16>(len);
+        group.throughput(Throughput::Elements(len as u64));
+
+        for (name, threshold, parallel) in [
+            ("counting", 0, false),
+            ("per_level", usize::MAX, false),
+            ("multi_threaded", usize::MAX, true),
+        ] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}_items", len), name),
+                &input,
+                |bench, input| {
+                    bench.iter_batched(
+                        || input.clone(),
+                        |mut input| {
+                            input
+                                .radix_sort_builder()
+                                .with_parallel(parallel)
+                                .with_tuner(&CountingSortTuner(threshold))
+                                .sort();
+                            black_box(input);
+                        },
+                        BatchSize::LargeInput,
+                    );
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    tuning_parameters,
+    tune_counts,
+    tune_all_counts,
+    tune_byte_array_counts,
+    tune_counts_by_size,
+    tune_min_task_size,
+    tune_insertion_sort_threshold,
+    tune_counting_sort_threshold,
+);
 criterion_main!(tuning_parameters);
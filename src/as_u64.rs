@@ -0,0 +1,115 @@
+use crate::RadixKey;
+
+/// `AsU64` sorts any `Copy` type which converts into a `u64`, such as small enums or id types,
+/// without implementing `RadixKey` for it. Items are sorted by the `u64` they convert into.
+///
+/// By default all 8 bytes of the `u64` are sorted. If the values are known to fit into fewer
+/// bits, set `BITS` so that only the bytes needed are sorted. In debug builds, a value which
+/// doesn't fit into `BITS` panics; release builds skip the check and the extra bits are ignored.
+///
+/// ```
+/// use rdst::{AsU64, RadixSort};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Priority {
+///     Low,
+///     High,
+/// }
+///
+/// impl From<Priority> for u64 {
+///     fn from(p: Priority) -> u64 {
+///         p as u64
+///     }
+/// }
+///
+/// // Only one byte needs to be sorted
+/// let mut tasks: Vec<AsU64<Priority, 8>> = vec![AsU64(Priority::High), AsU64(Priority::Low)];
+/// tasks.radix_sort_unstable();
+///
+/// assert_eq!(tasks, [AsU64(Priority::Low), AsU64(Priority::High)]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct AsU64<T, const BITS: u32 = 64>(pub T);
+
+impl<T, const BITS: u32> RadixKey for AsU64<T, BITS>
+where
+    T: Into<u64> + Copy,
+{
+    const LEVELS: usize = BITS.div_ceil(8) as usize;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        let v: u64 = self.0.into();
+        debug_assert!(
+            BITS >= 64 || v >> BITS == 0,
+            "AsU64: {} does not fit into {} bits",
+            v,
+            BITS
+        );
+
+        (v >> (level * 8)) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AsU64, RadixKey, RadixSort};
+    use block_pseudorand::block_rand;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Status {
+        Pending = 3,
+        Active = 1,
+        Closed = 300,
+    }
+
+    impl From<Status> for u64 {
+        fn from(s: Status) -> u64 {
+            s as u64
+        }
+    }
+
+    #[test]
+    pub fn test_levels() {
+        assert_eq!(AsU64::<u32>::LEVELS, 8);
+        assert_eq!(AsU64::<u32, 32>::LEVELS, 4);
+        assert_eq!(AsU64::<u16, 9>::LEVELS, 2);
+        assert_eq!(AsU64::<u8, 1>::LEVELS, 1);
+    }
+
+    #[test]
+    pub fn test_enum() {
+        let statuses = [Status::Closed, Status::Pending, Status::Active];
+        let mut inputs: Vec<AsU64<Status, 16>> = block_rand::<u8>(10_000)
+            .into_iter()
+            .map(|v| AsU64(statuses[v as usize % statuses.len()]))
+            .collect();
+
+        inputs.radix_sort_unstable();
+
+        let actual: Vec<u64> = inputs.iter().map(|v| v.0.into()).collect();
+        assert!(actual.windows(2).all(|w| w[0] <= w[1]));
+        assert!(actual.contains(&300));
+    }
+
+    #[test]
+    pub fn test_full_width() {
+        let mut inputs: Vec<AsU64<u64>> =
+            block_rand::<u64>(100_000).into_iter().map(AsU64).collect();
+        let mut expected = inputs.clone();
+        expected.sort();
+
+        inputs.radix_sort_unstable();
+
+        assert_eq!(inputs, expected);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "AsU64: 300 does not fit into 8 bits")]
+    pub fn test_too_wide() {
+        let mut inputs: Vec<AsU64<Status, 8>> = vec![AsU64(Status::Closed), AsU64(Status::Active)];
+        inputs.radix_sort_unstable();
+    }
+}
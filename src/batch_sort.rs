@@ -0,0 +1,78 @@
+use crate::radix_key::check_levels;
+use crate::sorter::Sorter;
+use crate::tuners::SingleThreadedTuner;
+use crate::RadixKey;
+#[cfg(feature = "multi-threaded")]
+use rayon::prelude::*;
+
+/// radix_sort_batch sorts every slice in `slices`, for workloads with many small sorts such as
+/// thousands of `Vec`s of a few hundred items each.
+///
+/// Each slice is small enough that sorting it on a single thread is fastest, so rather than
+/// parallelizing within each slice, the slices themselves are sorted in parallel, one rayon task
+/// per slice, using all cores. A single single-threaded sorter is shared by every slice, rather
+/// than being set up for each sort as with calling `radix_sort_unstable()` on each in turn.
+///
+/// Without the `multi-threaded` feature, the slices are sorted one after another.
+///
+/// ```
+/// use rdst::radix_sort_batch;
+///
+/// let mut a = vec![3u32, 1, 2];
+/// let mut b = vec![9u32, 7, 8, 6];
+/// radix_sort_batch(&mut [&mut a[..], &mut b[..]]);
+///
+/// assert_eq!(a, [1, 2, 3]);
+/// assert_eq!(b, [6, 7, 8, 9]);
+/// ```
+pub fn radix_sort_batch<T>(slices: &mut [&mut [T]])
+where
+    T: RadixKey + Copy + Send + Sync,
+{
+    assert_ne!(T::LEVELS, 0, "RadixKey must have at least 1 level");
+    check_levels::<T>();
+
+    let sorter = Sorter::new(false, &SingleThreadedTuner);
+
+    #[cfg(feature = "multi-threaded")]
+    slices
+        .par_iter_mut()
+        .for_each(|slice| sorter.top_level_director(slice, None));
+
+    #[cfg(not(feature = "multi-threaded"))]
+    slices
+        .iter_mut()
+        .for_each(|slice| sorter.top_level_director(slice, None));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::radix_sort_batch;
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_radix_sort_batch() {
+        let lens = block_rand::<u16>(2_000);
+        let mut inputs: Vec<Vec<u32>> = lens
+            .iter()
+            .map(|len| block_rand((*len % 1_000) as usize))
+            .collect();
+        let mut expected = inputs.clone();
+        expected.iter_mut().for_each(|v| v.sort_unstable());
+
+        let mut slices: Vec<&mut [u32]> = inputs.iter_mut().map(|v| &mut v[..]).collect();
+        radix_sort_batch(&mut slices);
+
+        assert_eq!(inputs, expected);
+    }
+
+    #[test]
+    pub fn test_radix_sort_batch_empty() {
+        radix_sort_batch::<u64>(&mut []);
+
+        let mut empty: [u64; 0] = [];
+        let mut one = [5u64];
+        radix_sort_batch(&mut [&mut empty[..], &mut one[..]]);
+        assert_eq!(one, [5]);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::RadixKey;
+
+/// `BeU32` wraps a `u32` stored as 4 big-endian bytes, as in many network protocols and file
+/// formats, and sorts it in the order of the decoded value without decoding it first.
+///
+/// Note that a plain `[u8; 4]` sorts with the last byte as the most significant, which is the
+/// order of a little-endian value, so it doesn't suit big-endian data.
+///
+/// ```
+/// use rdst::{BeU32, RadixSort};
+///
+/// let mut values = vec![BeU32([0, 0, 1, 0]), BeU32([0, 0, 0, 255]), BeU32([1, 0, 0, 0])];
+/// values.radix_sort_unstable();
+///
+/// let decoded: Vec<u32> = values.iter().map(|v| v.value()).collect();
+/// assert_eq!(decoded, [255, 256, 16_777_216]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct BeU32(pub [u8; 4]);
+
+impl BeU32 {
+    /// `value` decodes the stored bytes into a `u32`.
+    #[inline]
+    pub fn value(&self) -> u32 {
+        u32::from_be_bytes(self.0)
+    }
+}
+
+impl From<u32> for BeU32 {
+    #[inline]
+    fn from(value: u32) -> Self {
+        Self(value.to_be_bytes())
+    }
+}
+
+impl RadixKey for BeU32 {
+    const LEVELS: usize = 4;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        self.0[3 - level]
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&self.value().to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BeU32, RadixSort};
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_decoded_order() {
+        let inputs = block_rand::<u32>(100_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        let mut values: Vec<BeU32> = inputs.into_iter().map(BeU32::from).collect();
+        values.radix_sort_unstable();
+
+        let decoded: Vec<u32> = values.iter().map(|v| v.value()).collect();
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    pub fn test_value() {
+        assert_eq!(BeU32([0x12, 0x34, 0x56, 0x78]).value(), 0x1234_5678);
+        assert_eq!(BeU32::from(0x1234_5678), BeU32([0x12, 0x34, 0x56, 0x78]));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::utils::cmp_by_levels;
+use crate::RadixKey;
+
+/// `radix_binary_search` searches `sorted` for an item with the same key as `key`, comparing items
+/// level by level from the most significant level down, exactly as the radix sorts order them.
+/// `sorted` must already be in that order, such as after `radix_sort_unstable()`.
+///
+/// This matters for keys which are only part of an item, or whose `Ord` disagrees with their
+/// levels, where `slice::binary_search` would compare by `Ord` and can miss items which are there.
+/// As with `slice::binary_search`, this returns `Ok` with the index of a matching item, which may
+/// be any of them if several items share the key, or `Err` with the index at which an item with
+/// that key could be inserted while keeping `sorted` in order.
+///
+/// Keys whose number of levels is only known at runtime, such as `DynRadixKey`, have no levels to
+/// compare here, so every item matches.
+///
+/// ```
+/// use rdst::{radix_binary_search, RadixKey, RadixSort};
+///
+/// // Sorted by `id` only, while the derived `Ord` compares `name` first
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// struct User {
+///     name: [u8; 4],
+///     id: u16,
+/// }
+///
+/// impl RadixKey for User {
+///     const LEVELS: usize = 2;
+///
+///     fn get_level(&self, level: usize) -> u8 {
+///         self.id.get_level(level)
+///     }
+/// }
+///
+/// let mut users = vec![
+///     User { name: *b"carl", id: 3 },
+///     User { name: *b"anne", id: 7 },
+///     User { name: *b"bill", id: 5 },
+/// ];
+/// users.radix_sort_unstable();
+///
+/// let probe = User { name: [0; 4], id: 5 };
+/// assert_eq!(radix_binary_search(&users, &probe), Ok(1));
+/// ```
+pub fn radix_binary_search<T>(sorted: &[T], key: &T) -> Result<usize, usize>
+where
+    T: RadixKey,
+{
+    sorted.binary_search_by(|v| cmp_by_levels(v, key, T::LEVELS))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{radix_binary_search, RadixKey, RadixSort};
+    use block_pseudorand::block_rand;
+
+    // Only `key` is part of the radix key, and the derived `Ord` compares `payload` first, so it
+    // disagrees with the radix order
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Partial {
+        payload: u32,
+        key: u16,
+    }
+
+    impl RadixKey for Partial {
+        const LEVELS: usize = 2;
+
+        #[inline]
+        fn get_level(&self, level: usize) -> u8 {
+            self.key.get_level(level)
+        }
+    }
+
+    #[test]
+    pub fn test_partial_key() {
+        let mut data: Vec<Partial> = block_rand::<u32>(10_000)
+            .into_iter()
+            .map(|v| Partial {
+                payload: v,
+                key: (v >> 16) as u16 & !1,
+            })
+            .collect();
+        data.radix_sort_unstable();
+
+        let mut missed_by_ord = 0;
+        for (i, item) in data.iter().enumerate() {
+            let probe = Partial {
+                payload: u32::MAX - item.payload,
+                key: item.key,
+            };
+
+            let found = radix_binary_search(&data, &probe).unwrap();
+            assert_eq!(data[found].key, item.key);
+
+            if data.binary_search(item) != Ok(i) {
+                missed_by_ord += 1;
+            }
+        }
+
+        // Odd keys are never present, and belong just after the even key below them
+        for key in [1u16, 4_097, u16::MAX] {
+            let probe = Partial { payload: 0, key };
+            let at = radix_binary_search(&data, &probe).unwrap_err();
+
+            assert!(data[..at].iter().all(|v| v.key < key));
+            assert!(data[at..].iter().all(|v| v.key > key));
+        }
+
+        // Otherwise this test wouldn't show anything `slice::binary_search` can't do
+        assert!(missed_by_ord > 0);
+    }
+
+    #[test]
+    pub fn test_matches_binary_search() {
+        let mut data = block_rand::<u32>(10_000);
+        data.radix_sort_unstable();
+        data.dedup();
+
+        for probe in block_rand::<u32>(1_000)
+            .into_iter()
+            .chain(data[..100].to_vec())
+        {
+            assert_eq!(
+                radix_binary_search(&data, &probe),
+                data.binary_search(&probe)
+            );
+        }
+
+        assert_eq!(radix_binary_search(&[], &1u32), Err(0));
+        assert_eq!(radix_binary_search(&[1i32], &-1), Err(0));
+    }
+}
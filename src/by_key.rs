@@ -0,0 +1,570 @@
+//! Sorting records by a key which is only part of each item, without requiring the whole item to
+//! implement `RadixKey`.
+
+use crate::radix_sort_builder::RadixSortBuilder;
+use crate::sorter::Sorter;
+use crate::tuners::SingleThreadedTuner;
+use crate::utils::get_counts;
+use crate::RadixKey;
+use std::time::Instant;
+
+/// `PairKey` sorts a `(K, V)` pair by `K` only. `V` is simply moved along with its key.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+struct PairKey<K, V>((K, V));
+
+impl<K, V> RadixKey for PairKey<K, V>
+where
+    K: RadixKey,
+{
+    const LEVELS: usize = K::LEVELS;
+    const BATCHED_LEVELS: bool = K::BATCHED_LEVELS;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        (self.0).0.get_level(level)
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        (self.0).0.get_all_levels(out)
+    }
+}
+
+/// radix_sort_by_pair_key sorts a slice of `(key, value)` pairs by the key only, using the
+/// `RadixKey` implementation of `K`. `V` does not need to implement `RadixKey`.
+///
+/// ```
+/// use rdst::radix_sort_by_pair_key;
+///
+/// let mut records = vec![(3u64, "c"), (1u64, "a"), (2u64, "b")];
+/// radix_sort_by_pair_key(&mut records);
+///
+/// assert_eq!(records, [(1, "a"), (2, "b"), (3, "c")]);
+/// ```
+pub fn radix_sort_by_pair_key<K, V>(data: &mut [(K, V)])
+where
+    K: RadixKey + Copy + Send + Sync,
+    V: Copy + Send + Sync,
+{
+    RadixSortBuilder::new(as_pair_keys(data)).sort();
+}
+
+/// radix_sort_by_pair_key_stable sorts a slice of `(key, value)` pairs by the key only, like
+/// `radix_sort_by_pair_key`, but guarantees that pairs with equal keys keep their original relative
+/// order.
+///
+/// Unlike the other sorts, this never consults a tuner. It always runs a single-threaded,
+/// out-of-place LSB sort, which is stable.
+///
+/// ```
+/// use rdst::radix_sort_by_pair_key_stable;
+///
+/// let mut records = vec![(2u32, "x"), (1u32, "a"), (2u32, "y"), (1u32, "b")];
+/// radix_sort_by_pair_key_stable(&mut records);
+///
+/// assert_eq!(records, [(1, "a"), (1, "b"), (2, "x"), (2, "y")]);
+/// ```
+pub fn radix_sort_by_pair_key_stable<K, V>(data: &mut [(K, V)])
+where
+    K: RadixKey + Copy + Send + Sync,
+    V: Copy + Send + Sync,
+{
+    if data.len() < 2 {
+        return;
+    }
+
+    let data = as_pair_keys(data);
+    let level = K::LEVELS - 1;
+    let (counts, _) = get_counts(data, level);
+
+    Sorter::new(false, &SingleThreadedTuner).lsb_sort_adapter(false, data, &counts, 0, level);
+}
+
+#[inline]
+#[allow(unsafe_code)]
+fn as_pair_keys<K, V>(data: &mut [(K, V)]) -> &mut [PairKey<K, V>] {
+    unsafe {
+        // Safety: PairKey<K, V> is repr(transparent) over (K, V), so the layout is identical
+        std::slice::from_raw_parts_mut(data.as_mut_ptr() as *mut PairKey<K, V>, data.len())
+    }
+}
+
+/// radix_sort_by_u64_key sorts a slice by a `u64` extracted from each item, which covers the most
+/// common case of sorting records by a numeric field without implementing `RadixKey`.
+///
+/// Keys are extracted once into a `(key, index)` buffer, which is sorted with the `u64` radix sort.
+/// The resulting permutation is then applied to `data` in-place, so `T` only needs to be movable
+/// and large records are moved exactly once.
+///
+/// ```
+/// use rdst::radix_sort_by_u64_key;
+///
+/// struct Order {
+///     id: u64,
+///     customer: String,
+/// }
+///
+/// let mut orders = vec![
+///     Order { id: 3, customer: "c".to_string() },
+///     Order { id: 1, customer: "a".to_string() },
+///     Order { id: 2, customer: "b".to_string() },
+/// ];
+/// radix_sort_by_u64_key(&mut orders, |o| o.id);
+///
+/// let customers: Vec<&str> = orders.iter().map(|o| o.customer.as_str()).collect();
+/// assert_eq!(customers, ["a", "b", "c"]);
+/// ```
+pub fn radix_sort_by_u64_key<T, F>(data: &mut [T], f: F)
+where
+    F: Fn(&T) -> u64,
+{
+    radix_sort_by_cached_key(data, f);
+}
+
+/// radix_sort_by_cached_key sorts a slice by a key extracted from each item, where the key can be
+/// any `RadixKey`, like `slice::sort_by_cached_key`.
+///
+/// Keys are extracted once into a compact `(key, index)` buffer, which is then sorted instead of
+/// the items themselves. The resulting permutation is applied to `data` in-place, so each item is
+/// moved exactly once. For large items with a small key, this is much friendlier to the cache than
+/// sorting the items directly, as the sort never reads whole items while counting or scattering.
+///
+/// ```
+/// use rdst::radix_sort_by_cached_key;
+///
+/// #[derive(Clone)]
+/// struct Reading {
+///     sensor: u16,
+///     samples: [f64; 32],
+/// }
+///
+/// let mut readings = vec![
+///     Reading { sensor: 9, samples: [0.0; 32] },
+///     Reading { sensor: 2, samples: [1.0; 32] },
+/// ];
+/// radix_sort_by_cached_key(&mut readings, |r| r.sensor);
+///
+/// assert_eq!(readings[0].sensor, 2);
+/// ```
+pub fn radix_sort_by_cached_key<T, K, F>(data: &mut [T], f: F)
+where
+    K: RadixKey + Copy + Send + Sync,
+    F: Fn(&T) -> K,
+{
+    if data.len() < 2 {
+        return;
+    }
+
+    let mut keys: Vec<(K, usize)> = data.iter().enumerate().map(|(i, v)| (f(v), i)).collect();
+    radix_sort_by_pair_key(&mut keys);
+
+    let mut permutation: Vec<usize> = keys.into_iter().map(|(_, i)| i).collect();
+    apply_permutation(data, &mut permutation);
+}
+
+/// radix_sort_by_key_buffer sorts `data` according to a parallel buffer of precomputed `keys`, where
+/// `keys[i]` is the key for `data[i]`. Both slices are reordered in tandem, so the same key buffer
+/// can be reused without re-extracting the keys.
+///
+/// Panics if `data` and `keys` have different lengths.
+///
+/// ```
+/// use rdst::radix_sort_by_key_buffer;
+///
+/// let mut names = vec!["c", "a", "b"];
+/// let mut keys: Vec<u64> = vec![30, 10, 20];
+/// radix_sort_by_key_buffer(&mut names, &mut keys);
+///
+/// assert_eq!(names, ["a", "b", "c"]);
+/// assert_eq!(keys, [10, 20, 30]);
+/// ```
+pub fn radix_sort_by_key_buffer<T, K>(data: &mut [T], keys: &mut [K])
+where
+    K: RadixKey + Copy + Send + Sync,
+{
+    assert_eq!(
+        data.len(),
+        keys.len(),
+        "data and keys must have the same length"
+    );
+
+    if data.len() < 2 {
+        return;
+    }
+
+    let mut pairs: Vec<(K, usize)> = keys.iter().copied().zip(0..).collect();
+    radix_sort_by_pair_key(&mut pairs);
+
+    let mut permutation = Vec::with_capacity(pairs.len());
+    for (key, (k, i)) in keys.iter_mut().zip(pairs) {
+        *key = k;
+        permutation.push(i);
+    }
+
+    apply_permutation(data, &mut permutation);
+}
+
+/// radix_argsort_column sorts `indices` by the key each one points to in `keys`, so that afterwards
+/// `keys[indices[0]] <= keys[indices[1]] <= ...`. Neither `keys` nor any other column is moved, so
+/// the same index buffer can be sorted by one column, and then by another, as in a columnar engine.
+///
+/// `indices` doesn't need to cover all of `keys`, and may be any subset of the rows, such as those
+/// left after a filter. Items with equal keys may be reordered, so to break ties by a second column,
+/// combine both into a single key, such as with `multi_key_sort()`.
+///
+/// Panics if any index is out of bounds for `keys`.
+///
+/// ```
+/// use rdst::radix_argsort_column;
+///
+/// let prices: Vec<u32> = vec![30, 10, 20, 40];
+/// let mut rows: Vec<u32> = vec![0, 1, 2];
+/// radix_argsort_column(&prices, &mut rows);
+///
+/// assert_eq!(rows, [1, 2, 0]);
+/// ```
+pub fn radix_argsort_column<K>(keys: &[K], indices: &mut [u32])
+where
+    K: RadixKey + Copy + Send + Sync,
+{
+    if indices.len() < 2 {
+        return;
+    }
+
+    let mut pairs: Vec<(K, u32)> = indices.iter().map(|&i| (keys[i as usize], i)).collect();
+    radix_sort_by_pair_key(&mut pairs);
+
+    for (index, (_, i)) in indices.iter_mut().zip(pairs) {
+        *index = i;
+    }
+}
+
+/// NullOrder controls where items with a sentinel ("null") key are placed by
+/// `radix_sort_by_u64_key_with_nulls`, like SQL's `NULLS FIRST` / `NULLS LAST`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum NullOrder {
+    NullsFirst,
+    NullsLast,
+}
+
+/// radix_sort_by_u64_key_with_nulls sorts a slice by a `u64` extracted from each item, like
+/// `radix_sort_by_u64_key`, except that items whose key equals `sentinel` are grouped at the start
+/// or end of the output according to `order`, regardless of the sentinel's numeric value.
+///
+/// ```
+/// use rdst::{radix_sort_by_u64_key_with_nulls, NullOrder};
+///
+/// let mut data: Vec<u64> = vec![5, u64::MAX, 1, u64::MAX, 3];
+/// radix_sort_by_u64_key_with_nulls(&mut data, |v| *v, u64::MAX, NullOrder::NullsFirst);
+///
+/// assert_eq!(data, [u64::MAX, u64::MAX, 1, 3, 5]);
+/// ```
+pub fn radix_sort_by_u64_key_with_nulls<T, F>(data: &mut [T], f: F, sentinel: u64, order: NullOrder)
+where
+    F: Fn(&T) -> u64,
+{
+    // Remap keys so the sentinel becomes the smallest (or largest) possible key. Every other key
+    // shifts by one towards the sentinel's old position to make room, which preserves their order.
+    match order {
+        NullOrder::NullsFirst => radix_sort_by_u64_key(data, |v| {
+            let k = f(v);
+            if k == sentinel {
+                0
+            } else if k < sentinel {
+                k + 1
+            } else {
+                k
+            }
+        }),
+        NullOrder::NullsLast => radix_sort_by_u64_key(data, |v| {
+            let k = f(v);
+            if k == sentinel {
+                u64::MAX
+            } else if k > sentinel {
+                k - 1
+            } else {
+                k
+            }
+        }),
+    }
+}
+
+/// radix_sort_by_instant sorts a slice of `(Instant, value)` pairs into time order, such as events
+/// collected into a profiling buffer.
+///
+/// `Instant` has no absolute representation, so the earliest instant is found in a first pass,
+/// and each item is then sorted by the `Duration` since that instant, in nanoseconds. This uses
+/// `radix_sort_by_u64_key`, so `V` only needs to be movable. Durations too long to fit in a `u64`
+/// of nanoseconds (over 500 years) are all sorted as the longest possible duration.
+///
+/// ```
+/// use rdst::radix_sort_by_instant;
+/// use std::time::{Duration, Instant};
+///
+/// let start = Instant::now();
+/// let mut events = vec![
+///     (start + Duration::from_millis(20), "flush"),
+///     (start, "open"),
+///     (start + Duration::from_millis(5), "write"),
+/// ];
+/// radix_sort_by_instant(&mut events);
+///
+/// let names: Vec<&str> = events.iter().map(|(_, name)| *name).collect();
+/// assert_eq!(names, ["open", "write", "flush"]);
+/// ```
+pub fn radix_sort_by_instant<V>(data: &mut [(Instant, V)]) {
+    let earliest = match data.iter().map(|(instant, _)| *instant).min() {
+        Some(earliest) => earliest,
+        None => return,
+    };
+
+    radix_sort_by_u64_key(data, |(instant, _)| {
+        instant
+            .duration_since(earliest)
+            .as_nanos()
+            .min(u64::MAX as u128) as u64
+    });
+}
+
+// Moves data[permutation[i]] into data[i] for every i, by following each cycle of the
+// permutation. Visited positions are marked by setting permutation[i] = i.
+pub(crate) fn apply_permutation<T>(data: &mut [T], permutation: &mut [usize]) {
+    for start in 0..data.len() {
+        let mut current = start;
+
+        while permutation[current] != start {
+            let next = permutation[current];
+            data.swap(current, next);
+            permutation[current] = current;
+            current = next;
+        }
+
+        permutation[current] = current;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        radix_argsort_column, radix_sort_by_cached_key, radix_sort_by_instant,
+        radix_sort_by_key_buffer, radix_sort_by_pair_key, radix_sort_by_pair_key_stable,
+        radix_sort_by_u64_key, radix_sort_by_u64_key_with_nulls, NullOrder,
+    };
+    use block_pseudorand::block_rand;
+    use std::time::{Duration, Instant};
+
+    // Deliberately does not implement RadixKey
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Payload {
+        id: usize,
+    }
+
+    #[test]
+    pub fn test_radix_sort_by_pair_key() {
+        for n in [0, 1, 100, 10_000, 1_000_000] {
+            let mut inputs: Vec<(u64, Payload)> = block_rand::<u64>(n)
+                .into_iter()
+                .enumerate()
+                .map(|(id, k)| (k >> 40, Payload { id }))
+                .collect();
+            let mut expected = inputs.clone();
+
+            radix_sort_by_pair_key(&mut inputs);
+            expected.sort_by_key(|(k, _)| *k);
+
+            // The sort is unstable, so compare keys, then ensure every payload is still present
+            // alongside its original key.
+            let keys: Vec<u64> = inputs.iter().map(|(k, _)| *k).collect();
+            let expected_keys: Vec<u64> = expected.iter().map(|(k, _)| *k).collect();
+            assert_eq!(keys, expected_keys);
+
+            inputs.sort_by_key(|(k, p)| (*k, p.id));
+            expected.sort_by_key(|(k, p)| (*k, p.id));
+            assert_eq!(inputs, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_radix_sort_by_u64_key() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Record {
+            key: u64,
+            name: String,
+        }
+
+        for n in [0, 1, 2, 100, 10_000, 1_000_000] {
+            let mut inputs: Vec<Record> = block_rand::<u64>(n)
+                .into_iter()
+                .enumerate()
+                .map(|(i, k)| Record {
+                    key: k >> 44,
+                    name: i.to_string(),
+                })
+                .collect();
+            let mut expected = inputs.clone();
+
+            radix_sort_by_u64_key(&mut inputs, |r| r.key);
+            expected.sort_by_key(|r| r.key);
+
+            let keys: Vec<u64> = inputs.iter().map(|r| r.key).collect();
+            let expected_keys: Vec<u64> = expected.iter().map(|r| r.key).collect();
+            assert_eq!(keys, expected_keys);
+
+            inputs.sort_by(|a, b| (a.key, &a.name).cmp(&(b.key, &b.name)));
+            expected.sort_by(|a, b| (a.key, &a.name).cmp(&(b.key, &b.name)));
+            assert_eq!(inputs, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_radix_sort_by_u64_key_with_nulls() {
+        for sentinel in [0, 1 << 20, u64::MAX] {
+            let inputs: Vec<u64> = block_rand::<u64>(100_000)
+                .into_iter()
+                .enumerate()
+                .map(|(i, k)| if i % 7 == 0 { sentinel } else { k >> 43 })
+                .collect();
+            let nulls = inputs.iter().filter(|k| **k == sentinel).count();
+
+            let mut expected: Vec<u64> =
+                inputs.iter().copied().filter(|k| *k != sentinel).collect();
+            expected.sort_unstable();
+
+            let mut first = inputs.clone();
+            radix_sort_by_u64_key_with_nulls(&mut first, |k| *k, sentinel, NullOrder::NullsFirst);
+            assert!(first[..nulls].iter().all(|k| *k == sentinel));
+            assert_eq!(first[nulls..], expected[..]);
+
+            let mut last = inputs.clone();
+            radix_sort_by_u64_key_with_nulls(&mut last, |k| *k, sentinel, NullOrder::NullsLast);
+            assert_eq!(last[..inputs.len() - nulls], expected[..]);
+            assert!(last[inputs.len() - nulls..].iter().all(|k| *k == sentinel));
+        }
+    }
+
+    #[test]
+    pub fn test_radix_sort_by_pair_key_stable() {
+        for n in [0, 1, 100, 10_000, 1_000_000] {
+            // Few distinct keys, so there are many duplicates whose order must be kept
+            let mut inputs: Vec<(u32, Payload)> = block_rand::<u32>(n)
+                .into_iter()
+                .enumerate()
+                .map(|(id, k)| (k >> 28, Payload { id }))
+                .collect();
+            let mut expected = inputs.clone();
+
+            radix_sort_by_pair_key_stable(&mut inputs);
+            expected.sort_by_key(|(k, _)| *k);
+
+            assert_eq!(inputs, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_radix_sort_by_key_buffer() {
+        for n in [0, 1, 100, 10_000, 1_000_000] {
+            let mut keys: Vec<u32> = block_rand::<u32>(n).into_iter().map(|k| k >> 12).collect();
+            let mut data: Vec<Payload> = (0..n).map(|id| Payload { id }).collect();
+            let original_keys = keys.clone();
+
+            radix_sort_by_key_buffer(&mut data, &mut keys);
+
+            let mut expected_keys = original_keys.clone();
+            expected_keys.sort_unstable();
+            assert_eq!(keys, expected_keys);
+
+            // Each payload must have moved together with its own key
+            for (k, p) in keys.iter().zip(data.iter()) {
+                assert_eq!(*k, original_keys[p.id]);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_radix_sort_by_key_buffer_mismatched_lengths() {
+        let mut data = vec![Payload { id: 0 }, Payload { id: 1 }];
+        let mut keys: Vec<u32> = vec![1];
+
+        radix_sort_by_key_buffer(&mut data, &mut keys);
+    }
+
+    #[test]
+    pub fn test_radix_argsort_column() {
+        for n in [0, 1, 100, 10_000, 1_000_000] {
+            let a: Vec<u32> = block_rand::<u32>(n).into_iter().map(|k| k >> 12).collect();
+            let b: Vec<u64> = block_rand::<u64>(n);
+            let original_a = a.clone();
+
+            // Every other row, as if some were filtered out, sorted by one column then the other
+            let mut rows: Vec<u32> = (0..n as u32).step_by(2).collect();
+            radix_argsort_column(&a, &mut rows);
+
+            assert!(rows
+                .windows(2)
+                .all(|w| a[w[0] as usize] <= a[w[1] as usize]));
+            assert_eq!(a, original_a);
+
+            radix_argsort_column(&b, &mut rows);
+
+            assert!(rows
+                .windows(2)
+                .all(|w| b[w[0] as usize] <= b[w[1] as usize]));
+            rows.sort_unstable();
+            assert_eq!(rows, (0..n as u32).step_by(2).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    pub fn test_radix_sort_by_cached_key() {
+        #[derive(Clone, Copy)]
+        struct Big {
+            key: u32,
+            payload: [u64; 31],
+        }
+
+        for n in [0, 1, 100, 100_000] {
+            let mut inputs: Vec<Big> = block_rand::<u32>(n)
+                .into_iter()
+                .map(|key| Big {
+                    key,
+                    payload: [key as u64; 31],
+                })
+                .collect();
+            let mut expected: Vec<u32> = inputs.iter().map(|b| b.key).collect();
+            expected.sort_unstable();
+
+            radix_sort_by_cached_key(&mut inputs, |b| b.key);
+
+            let keys: Vec<u32> = inputs.iter().map(|b| b.key).collect();
+            assert_eq!(keys, expected);
+            assert!(inputs.iter().all(|b| b.payload == [b.key as u64; 31]));
+        }
+    }
+
+    #[test]
+    pub fn test_radix_sort_by_instant() {
+        // Monotonic instants, each later than the last, in collection order
+        let start = Instant::now();
+        let collected: Vec<(Instant, usize)> = (0..100_000)
+            .map(|i| (start + Duration::from_nanos(i as u64 * 1_337), i))
+            .collect();
+
+        for n in [0, 1, 2, 100, 100_000] {
+            let mut inputs: Vec<(Instant, usize)> = collected[..n].to_vec();
+            let shuffle = block_rand::<u64>(n);
+            inputs.sort_by_key(|(_, i)| shuffle[*i]);
+
+            radix_sort_by_instant(&mut inputs);
+
+            assert_eq!(inputs, &collected[..n]);
+        }
+
+        // Real instants may repeat, so only check they are in time order
+        let mut inputs: Vec<(Instant, usize)> = (0..10_000).map(|i| (Instant::now(), i)).collect();
+        inputs.reverse();
+        radix_sort_by_instant(&mut inputs);
+        assert!(inputs.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+}
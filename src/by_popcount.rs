@@ -0,0 +1,90 @@
+use crate::RadixKey;
+
+/// `ByPopcount` wraps an unsigned integer used as a set of flags, and sorts sets with more flags
+/// set first, with ties broken by the value of the flags themselves, in ascending order.
+///
+/// The most significant level is `!popcount`, so that larger counts sort first, followed by the
+/// levels of the integer itself. This adds one level to the key, but no comparisons.
+///
+/// ```
+/// use rdst::{ByPopcount, RadixSort};
+///
+/// let mut flags: Vec<ByPopcount<u8>> = vec![0b0001, 0b0111, 0b1000, 0b0011]
+///     .into_iter()
+///     .map(ByPopcount)
+///     .collect();
+/// flags.radix_sort_unstable();
+///
+/// let actual: Vec<u8> = flags.iter().map(|f| f.0).collect();
+/// assert_eq!(actual, [0b0111, 0b0011, 0b0001, 0b1000]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ByPopcount<T>(pub T);
+
+macro_rules! impl_by_popcount {
+    ($($t:ty),*) => {
+        $(
+            impl RadixKey for ByPopcount<$t> {
+                const LEVELS: usize = <$t as RadixKey>::LEVELS + 1;
+
+                #[inline]
+                fn get_level(&self, level: usize) -> u8 {
+                    if level == <$t as RadixKey>::LEVELS {
+                        !(self.0.count_ones() as u8)
+                    } else {
+                        self.0.get_level(level)
+                    }
+                }
+
+                #[inline]
+                fn get_all_levels(&self, out: &mut [u8]) {
+                    let levels = <$t as RadixKey>::LEVELS;
+
+                    self.0.get_all_levels(&mut out[..levels]);
+                    out[levels] = !(self.0.count_ones() as u8);
+                }
+            }
+        )*
+    };
+}
+
+impl_by_popcount!(u8, u16, u32, u64, u128, usize);
+
+#[cfg(test)]
+mod tests {
+    use crate::{ByPopcount, RadixSort};
+    use block_pseudorand::block_rand;
+    use std::cmp::Reverse;
+
+    #[test]
+    pub fn test_set_bits_order() {
+        let inputs = block_rand::<u32>(100_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable_by_key(|v| (Reverse(v.count_ones()), *v));
+
+        let mut flags: Vec<ByPopcount<u32>> = inputs.into_iter().map(ByPopcount).collect();
+        flags.radix_sort_unstable();
+
+        let actual: Vec<u32> = flags.into_iter().map(|f| f.0).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    pub fn test_full_width() {
+        // Every popcount from 0 to 128, including the full set, whose `!popcount` is the smallest
+        let inputs: Vec<u128> = (0..=128u32)
+            .map(|n| u128::MAX.checked_shr(128 - n).unwrap_or(0))
+            .chain(block_rand::<u128>(10_000))
+            .collect();
+        let mut expected = inputs.clone();
+        expected.sort_unstable_by_key(|v| (Reverse(v.count_ones()), *v));
+
+        let mut flags: Vec<ByPopcount<u128>> = inputs.into_iter().map(ByPopcount).collect();
+        flags.radix_sort_unstable();
+
+        let actual: Vec<u128> = flags.into_iter().map(|f| f.0).collect();
+        assert_eq!(actual, expected);
+        assert_eq!(actual[0], u128::MAX);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{RadixKey, RadixSort};
+use std::cell::Cell;
+
+/// radix_sort_cells sorts the values held in a slice of `Cell`s, such as a `&[Cell<u32>]` which
+/// is shared elsewhere and so can't be borrowed as a `&mut [u32]`.
+///
+/// The values are copied out into a temporary `Vec`, radix sorted, and written back into the
+/// cells in order, so this needs as much extra memory as the values themselves. As a `Cell` is
+/// never shared across threads, no other code can observe the cells part way through.
+///
+/// If the cells are a view of a `&mut [T]` you still have, such as one created with
+/// `Cell::from_mut(slice).as_slice_of_cells()`, sort that slice directly instead to avoid the copy.
+///
+/// ```
+/// use rdst::radix_sort_cells;
+/// use std::cell::Cell;
+///
+/// let cells = [Cell::new(3u32), Cell::new(1), Cell::new(2)];
+/// let shared = &cells;
+/// radix_sort_cells(shared);
+///
+/// let values: Vec<u32> = cells.iter().map(Cell::get).collect();
+/// assert_eq!(values, [1, 2, 3]);
+/// ```
+pub fn radix_sort_cells<T>(cells: &[Cell<T>])
+where
+    T: RadixKey + Copy + Send + Sync,
+{
+    if cells.len() < 2 {
+        return;
+    }
+
+    let mut values: Vec<T> = cells.iter().map(Cell::get).collect();
+    values.radix_sort_unstable();
+
+    for (cell, v) in cells.iter().zip(values) {
+        cell.set(v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::radix_sort_cells;
+    use block_pseudorand::block_rand;
+    use std::cell::Cell;
+
+    #[test]
+    pub fn test_radix_sort_cells() {
+        for n in [0, 1, 2, 100, 100_000] {
+            let inputs: Vec<u32> = block_rand(n);
+            let cells: Vec<Cell<u32>> = inputs.iter().copied().map(Cell::new).collect();
+
+            // The manual equivalent: extract, sort and reinsert
+            let mut expected: Vec<u32> = cells.iter().map(Cell::get).collect();
+            expected.sort_unstable();
+
+            radix_sort_cells(&cells);
+
+            let actual: Vec<u32> = cells.iter().map(Cell::get).collect();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_radix_sort_cells_signed() {
+        let inputs: Vec<i64> = block_rand(10_000);
+        let cells: Vec<Cell<i64>> = inputs.iter().copied().map(Cell::new).collect();
+        let mut expected = inputs;
+        expected.sort_unstable();
+
+        radix_sort_cells(&cells[..]);
+
+        assert!(cells.iter().map(Cell::get).eq(expected));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::utils::cmp_by_levels;
+use crate::{RadixKey, RadixSort};
+use std::cmp::Ordering;
+
+/// radix_sort_count_into sorts `data` and calls `out` once for each distinct key, in ascending
+/// order, with the first item with that key and the number of items which share it, as when
+/// counting term frequencies.
+///
+/// This is built on `radix_sort_streaming()`, so each bucket of the most significant level is
+/// sorted and counted before the next is sorted, and the first counts are written while the rest
+/// of `data` is still unsorted. As every item with the same key lands in the same bucket, no run
+/// is split across buckets. Nothing is allocated beyond what the sort itself uses, and `data` is
+/// left sorted.
+///
+/// Keys are compared by their levels, so items are counted together whenever they sort as equal,
+/// even if they differ in ways their `RadixKey` ignores.
+///
+/// ```
+/// use rdst::radix_sort_count_into;
+///
+/// let mut terms: Vec<u32> = vec![7, 3, 7, 1, 3, 7];
+/// let mut counts = Vec::new();
+/// radix_sort_count_into(&mut terms, |term, count| counts.push((*term, count)));
+///
+/// assert_eq!(counts, [(1, 1), (3, 2), (7, 3)]);
+/// ```
+pub fn radix_sort_count_into<T, W>(data: &mut [T], mut out: W)
+where
+    T: RadixKey + Copy + Send + Sync,
+    W: FnMut(&T, usize),
+{
+    for chunk in data.radix_sort_streaming() {
+        let mut start = 0;
+
+        for i in 1..chunk.len() {
+            if cmp_by_levels(&chunk[start], &chunk[i], T::LEVELS) != Ordering::Equal {
+                out(&chunk[start], i - start);
+                start = i;
+            }
+        }
+
+        out(&chunk[start], chunk.len() - start);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::radix_sort_count_into;
+    use block_pseudorand::block_rand;
+    use std::collections::BTreeMap;
+
+    #[test]
+    pub fn test_term_frequencies() {
+        for (n, terms) in [
+            (0, 1),
+            (1, 1),
+            (100, 7),
+            (100_000, 1_000),
+            (1_000_000, 50_000),
+        ] {
+            // Skewed towards the smaller terms, as with word frequencies
+            let mut data: Vec<u32> = block_rand::<u32>(n)
+                .into_iter()
+                .map(|v| (v % terms) * (v % 3) / 2)
+                .collect();
+
+            let mut expected: BTreeMap<u32, usize> = BTreeMap::new();
+            for v in data.iter() {
+                *expected.entry(*v).or_default() += 1;
+            }
+
+            let mut counts = Vec::new();
+            radix_sort_count_into(&mut data, |term, count| counts.push((*term, count)));
+
+            assert_eq!(counts, expected.into_iter().collect::<Vec<_>>());
+            assert!(data.windows(2).all(|w| w[0] <= w[1]));
+        }
+    }
+
+    #[test]
+    pub fn test_spread_keys() {
+        // Few repeats, spread over every bucket of the top level
+        let mut data: Vec<u64> = block_rand::<u64>(200_000)
+            .into_iter()
+            .map(|v| v & 0xFF00_0000_0000_0FFF)
+            .collect();
+        let mut expected = data.clone();
+        expected.sort_unstable();
+        expected.dedup();
+
+        let mut total = 0;
+        let mut keys = Vec::new();
+        radix_sort_count_into(&mut data, |key, count| {
+            assert!(count > 0);
+            total += count;
+            keys.push(*key);
+        });
+
+        assert_eq!(keys, expected);
+        assert_eq!(total, data.len());
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{RadixKey, MAX_LEVELS};
+
+/// `DynRadixKey` is an alternative to `RadixKey` for keys where the number of levels is only known
+/// at runtime, such as variable-precision integers.
+///
+/// The number of levels may vary between runs, but every item sorted together must return the
+/// same value from `levels()`. Items with differing level counts are not supported.
+pub trait DynRadixKey {
+    fn levels(&self) -> usize;
+
+    fn get_level(&self, level: usize) -> u8;
+}
+
+/// `DynKey` adapts a `DynRadixKey` so it can be passed through the regular sorting machinery.
+/// `LEVELS` has to be known at compile time, so it is the most levels any key can have,
+/// `MAX_LEVELS`, with every level past the key's own `levels()` reading as zero. These are above
+/// every real level and the same for every item, so they don't change the order, and the `Sorter`
+/// is given the real number of levels at runtime so that it never visits them.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub(crate) struct DynKey<T>(T);
+
+impl<T> RadixKey for DynKey<T>
+where
+    T: DynRadixKey,
+{
+    const LEVELS: usize = MAX_LEVELS;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        if level < self.0.levels() {
+            self.0.get_level(level)
+        } else {
+            0
+        }
+    }
+}
+
+#[inline]
+#[allow(unsafe_code)]
+pub(crate) fn as_dyn_keys<T>(data: &mut [T]) -> &mut [DynKey<T>]
+where
+    T: DynRadixKey,
+{
+    unsafe {
+        // Safety: DynKey<T> is repr(transparent) over T, so the layout of the slice is identical
+        std::slice::from_raw_parts_mut(data.as_mut_ptr() as *mut DynKey<T>, data.len())
+    }
+}
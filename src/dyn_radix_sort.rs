@@ -0,0 +1,173 @@
+use crate::dyn_radix_key::as_dyn_keys;
+use crate::radix_sort_builder::RadixSortBuilder;
+use crate::DynRadixKey;
+
+pub trait DynRadixSort<T> {
+    /// radix_sort_dyn_unstable runs a radix sort based upon the `rdst::DynRadixKey`
+    /// implementation of `T`. The number of levels is read from the first item.
+    ///
+    /// ```
+    /// use rdst::{DynRadixKey, DynRadixSort};
+    ///
+    /// #[derive(Clone, Copy, Debug, PartialEq)]
+    /// struct VarInt {
+    ///     value: u64,
+    ///     bytes: usize,
+    /// }
+    ///
+    /// impl DynRadixKey for VarInt {
+    ///     fn levels(&self) -> usize {
+    ///         self.bytes
+    ///     }
+    ///
+    ///     fn get_level(&self, level: usize) -> u8 {
+    ///         (self.value >> (level * 8)) as u8
+    ///     }
+    /// }
+    ///
+    /// let mut values: Vec<VarInt> = [300, 2, 70_000]
+    ///     .iter()
+    ///     .map(|&value| VarInt { value, bytes: 3 })
+    ///     .collect();
+    /// values.radix_sort_dyn_unstable();
+    ///
+    /// assert_eq!(values.iter().map(|v| v.value).collect::<Vec<_>>(), [2, 300, 70_000]);
+    /// ```
+    fn radix_sort_dyn_unstable(&mut self);
+
+    /// radix_sort_dyn_unstable_with_levels is the same as `radix_sort_dyn_unstable`, except that
+    /// the number of levels is provided explicitly rather than read from the first item.
+    fn radix_sort_dyn_unstable_with_levels(&mut self, levels: usize);
+}
+
+impl<T> DynRadixSort<T> for [T]
+where
+    T: DynRadixKey + Sized + Send + Copy + Sync,
+{
+    fn radix_sort_dyn_unstable(&mut self) {
+        let levels = match self.first() {
+            Some(v) => v.levels(),
+            None => return,
+        };
+
+        debug_assert!(
+            self.iter().all(|v| v.levels() == levels),
+            "DynRadixKey levels must be the same for every item"
+        );
+
+        self.radix_sort_dyn_unstable_with_levels(levels);
+    }
+
+    fn radix_sort_dyn_unstable_with_levels(&mut self, levels: usize) {
+        RadixSortBuilder::new_with_levels(as_dyn_keys(self), levels).sort();
+    }
+}
+
+impl<T> DynRadixSort<T> for Vec<T>
+where
+    T: DynRadixKey + Sized + Send + Copy + Sync,
+{
+    fn radix_sort_dyn_unstable(&mut self) {
+        self.as_mut_slice().radix_sort_dyn_unstable();
+    }
+
+    fn radix_sort_dyn_unstable_with_levels(&mut self, levels: usize) {
+        self.as_mut_slice()
+            .radix_sort_dyn_unstable_with_levels(levels);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DynRadixKey, DynRadixSort};
+    use block_pseudorand::block_rand;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct VarInt {
+        value: u128,
+        bytes: usize,
+    }
+
+    impl DynRadixKey for VarInt {
+        fn levels(&self) -> usize {
+            self.bytes
+        }
+
+        fn get_level(&self, level: usize) -> u8 {
+            (self.value >> (level * 8)) as u8
+        }
+    }
+
+    fn gen_var_ints(n: usize, bytes: usize) -> Vec<VarInt> {
+        let mask = if bytes == 16 {
+            u128::MAX
+        } else {
+            (1u128 << (bytes * 8)) - 1
+        };
+
+        block_rand::<u128>(n)
+            .into_iter()
+            .map(|v| VarInt {
+                value: v & mask,
+                bytes,
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_dyn_levels() {
+        for bytes in [1, 3, 5, 11, 16] {
+            for n in [0, 1, 100, 10_000, 500_000] {
+                let mut inputs = gen_var_ints(n, bytes);
+                let mut expected = inputs.clone();
+                expected.sort_unstable_by_key(|v| v.value);
+
+                inputs.radix_sort_dyn_unstable();
+
+                assert_eq!(inputs, expected);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_dyn_levels_explicit() {
+        let mut inputs = gen_var_ints(100_000, 6);
+        let mut expected = inputs.clone();
+        expected.sort_unstable_by_key(|v| v.value);
+
+        inputs.radix_sort_dyn_unstable_with_levels(6);
+
+        assert_eq!(inputs, expected);
+    }
+
+    #[test]
+    pub fn test_dyn_key_padding() {
+        use crate::dyn_radix_key::as_dyn_keys;
+        use crate::{RadixKey, MAX_LEVELS};
+
+        // Only the low 2 bytes are part of the key, so every level above them reads as zero
+        let mut inputs = [VarInt {
+            value: 0x0403_0201,
+            bytes: 2,
+        }];
+        let keys = as_dyn_keys(&mut inputs);
+        let levels: Vec<u8> = (0..4).map(|l| keys[0].get_level(l)).collect();
+
+        assert_eq!(levels, [1, 2, 0, 0]);
+        assert_eq!(keys[0].get_level(MAX_LEVELS - 1), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_dyn_zero_levels() {
+        let mut inputs = gen_var_ints(100, 1);
+        inputs.radix_sort_dyn_unstable_with_levels(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "RadixKey has 65537 levels, more than the maximum of 65536")]
+    pub fn test_too_many_levels() {
+        let mut inputs = gen_var_ints(2, 4);
+        inputs.radix_sort_dyn_unstable_with_levels(crate::MAX_LEVELS + 1);
+    }
+}
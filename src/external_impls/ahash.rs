@@ -0,0 +1,41 @@
+use crate::HashKey;
+
+/// AHashKey sorts values by their `ahash` hash, a fast non-cryptographic hash suited to grouping.
+/// Every key in a sort must be created with the same `ahash::RandomState`, as each is randomly
+/// seeded.
+///
+/// ```
+/// use ahash::RandomState;
+/// use rdst::{AHashKey, RadixSort};
+///
+/// let state = RandomState::new();
+/// let mut keys: Vec<AHashKey<&str>> =
+///     ["b", "a", "b"].iter().map(|v| AHashKey::new(*v, &state)).collect();
+/// keys.radix_sort_unstable();
+/// ```
+pub type AHashKey<T> = HashKey<T, ahash::RandomState>;
+
+#[cfg(test)]
+mod tests {
+    use crate::{AHashKey, RadixSort};
+    use ahash::RandomState;
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_ahash_key() {
+        let state = RandomState::new();
+        let values: Vec<u32> = block_rand::<u32>(100_000)
+            .into_iter()
+            .map(|v| v % 1_000)
+            .collect();
+
+        let mut keys: Vec<AHashKey<u32>> =
+            values.iter().map(|v| AHashKey::new(*v, &state)).collect();
+        assert!(keys
+            .iter()
+            .all(|k| k.hash() == AHashKey::new(*k.value(), &state).hash()));
+
+        keys.radix_sort_unstable();
+        assert!(keys.windows(2).all(|w| w[0].hash() <= w[1].hash()));
+    }
+}
@@ -0,0 +1,82 @@
+use crate::multi_key_sort::sort_by_byte_strings;
+use ascii::{AsciiChar, AsciiStr};
+
+/// radix_sort_ascii sorts ASCII strings, such as `AsciiString`, `&AsciiStr` or `&[AsciiChar]`,
+/// in byte order, where a string sorts before any longer string it is a prefix of. As every ASCII
+/// character is a single byte below 128, this is the same case-sensitive order as `Ord` for the
+/// equivalent `str`, with all uppercase letters before all lowercase letters.
+///
+/// Each string is copied once into a fixed-width key of its bytes, padded with zeros up to the
+/// length of the longest string, followed by its length to order strings which only differ by
+/// trailing NUL characters. These keys are radix sorted, and the resulting order applied to
+/// `data` by moving each string exactly once.
+///
+/// Every key is as wide as the longest string, so a single very long string makes the sort
+/// slower and use more memory for all of the others. Strings longer than about `MAX_LEVELS`
+/// bytes panic.
+///
+/// ```
+/// use ascii::AsciiString;
+/// use rdst::radix_sort_ascii;
+///
+/// let mut words: Vec<AsciiString> = ["beta", "Beta", "alpha", "al"]
+///     .iter()
+///     .map(|w| AsciiString::from_ascii(*w).unwrap())
+///     .collect();
+/// radix_sort_ascii(&mut words);
+///
+/// assert_eq!(words, ["Beta", "al", "alpha", "beta"]);
+/// ```
+pub fn radix_sort_ascii<S>(data: &mut [S])
+where
+    S: AsRef<[AsciiChar]>,
+{
+    sort_by_byte_strings(data, |s| <&AsciiStr>::from(s.as_ref()).as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::radix_sort_ascii;
+    use ascii::{AsciiChar, AsciiStr, AsciiString};
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_ascii() {
+        let lens = block_rand::<u8>(100_000);
+        let bytes = block_rand::<u8>(100_000);
+        let alphabet = b"aAbBzZ09 \0";
+
+        for n in [0, 1, 2, 100, 100_000] {
+            // A small alphabet of mixed case, so that there are plenty of shared prefixes
+            let inputs: Vec<String> = (0..n)
+                .map(|i| {
+                    (0..lens[i] % 12)
+                        .map(|j| alphabet[bytes[(i + j as usize * 7) % n] as usize % 10] as char)
+                        .collect()
+                })
+                .collect();
+
+            let mut expected = inputs.clone();
+            expected.sort_unstable();
+
+            let mut data: Vec<AsciiString> = inputs
+                .iter()
+                .map(|s| AsciiString::from_ascii(s.as_str()).unwrap())
+                .collect();
+            radix_sort_ascii(&mut data);
+            assert_eq!(data, expected);
+
+            // Borrowed slices of characters sort the same way
+            let mut slices: Vec<&[AsciiChar]> = inputs
+                .iter()
+                .map(|s| AsciiStr::from_ascii(s.as_str()).unwrap().as_slice())
+                .collect();
+            radix_sort_ascii(&mut slices);
+            let slices: Vec<&str> = slices
+                .into_iter()
+                .map(|s| <&AsciiStr>::from(s).as_str())
+                .collect();
+            assert_eq!(slices, expected);
+        }
+    }
+}
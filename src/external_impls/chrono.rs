@@ -0,0 +1,120 @@
+use crate::RadixKey;
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+// Sorted by `timestamp_nanos_opt()`, which matches `Ord` for every time it can represent, between
+// 1677-09-21T00:12:43.145224192 and 2262-04-11T23:47:16.854775807. Times outside that range have
+// no such timestamp, so they're clamped to `i64::MIN` or `i64::MAX`, sorting before or after every
+// time within the range, but in no particular order among themselves.
+#[inline]
+fn clamped_nanos(t: &NaiveDateTime) -> i64 {
+    match t.and_utc().timestamp_nanos_opt() {
+        Some(nanos) => nanos,
+        None if t.and_utc().timestamp() < 0 => i64::MIN,
+        None => i64::MAX,
+    }
+}
+
+impl RadixKey for NaiveDateTime {
+    const LEVELS: usize = 8;
+    const BATCHED_LEVELS: bool = true;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        clamped_nanos(self).get_level(level)
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        clamped_nanos(self).get_all_levels(out)
+    }
+}
+
+impl RadixKey for DateTime<Utc> {
+    const LEVELS: usize = 8;
+    const BATCHED_LEVELS: bool = true;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        clamped_nanos(&self.naive_utc()).get_level(level)
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        clamped_nanos(&self.naive_utc()).get_all_levels(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RadixSort;
+    use block_pseudorand::block_rand;
+    use chrono::{DateTime, NaiveDateTime, Utc};
+
+    // Times spread roughly +/- 290 years either side of the unix epoch, within the range of
+    // `timestamp_nanos_opt()`
+    fn gen_date_times(n: usize) -> Vec<DateTime<Utc>> {
+        block_rand::<i64>(n)
+            .into_iter()
+            .map(|v| {
+                let secs = v % (290 * 365 * 86_400);
+                let nanos = (v as u64 % 1_000_000_000) as u32;
+
+                DateTime::from_timestamp(secs, nanos).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_date_time_utc() {
+        for n in [0, 1, 100, 100_000] {
+            let mut inputs = gen_date_times(n);
+            assert!(n < 100 || inputs.iter().any(|d| d.timestamp() < 0));
+
+            let mut expected = inputs.clone();
+            expected.sort();
+            inputs.radix_sort_unstable();
+
+            assert_eq!(inputs, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_naive_date_time() {
+        let mut inputs: Vec<NaiveDateTime> = gen_date_times(100_000)
+            .into_iter()
+            .map(|d| d.naive_utc())
+            .collect();
+
+        let mut expected = inputs.clone();
+        expected.sort();
+        inputs.radix_sort_unstable();
+
+        assert_eq!(inputs, expected);
+    }
+
+    #[test]
+    pub fn test_out_of_range() {
+        let far_past = DateTime::from_timestamp(-400 * 365 * 86_400, 0).unwrap();
+        let far_future = DateTime::from_timestamp(400 * 365 * 86_400, 0).unwrap();
+        assert!(far_past.timestamp_nanos_opt().is_none());
+        assert!(far_future.timestamp_nanos_opt().is_none());
+
+        let mut inputs = gen_date_times(1_000);
+        inputs.extend([
+            far_future,
+            far_past,
+            DateTime::<Utc>::MAX_UTC,
+            DateTime::<Utc>::MIN_UTC,
+        ]);
+        inputs.radix_sort_unstable();
+
+        // Clamped to either end, in no particular order among themselves
+        assert!(inputs[..2]
+            .iter()
+            .all(|d| d.timestamp() < -300 * 365 * 86_400));
+        assert!(inputs[inputs.len() - 2..]
+            .iter()
+            .all(|d| d.timestamp() > 300 * 365 * 86_400));
+        assert!(inputs[2..inputs.len() - 2].windows(2).all(|w| w[0] <= w[1]));
+    }
+}
@@ -0,0 +1,42 @@
+use crate::HashKey;
+
+/// FxHashKey sorts values by their `fxhash` hash, a very fast non-cryptographic hash suited to
+/// grouping. It isn't seeded, so the same value has the same hash in every run.
+///
+/// ```
+/// use fxhash::FxBuildHasher;
+/// use rdst::{FxHashKey, RadixSort};
+///
+/// let state = FxBuildHasher::default();
+/// let mut keys: Vec<FxHashKey<&str>> =
+///     ["b", "a", "b"].iter().map(|v| FxHashKey::new(*v, &state)).collect();
+/// keys.radix_sort_unstable();
+/// ```
+pub type FxHashKey<T> = HashKey<T, fxhash::FxBuildHasher>;
+
+#[cfg(test)]
+mod tests {
+    use crate::{FxHashKey, RadixSort};
+    use block_pseudorand::block_rand;
+    use fxhash::FxBuildHasher;
+
+    #[test]
+    pub fn test_fxhash_key() {
+        let values: Vec<u32> = block_rand::<u32>(100_000)
+            .into_iter()
+            .map(|v| v % 1_000)
+            .collect();
+
+        // Separate instances give the same hashes, as fxhash isn't seeded
+        let mut keys: Vec<FxHashKey<u32>> = values
+            .iter()
+            .map(|v| FxHashKey::new(*v, &FxBuildHasher::default()))
+            .collect();
+        assert!(keys
+            .iter()
+            .all(|k| k.hash() == FxHashKey::new(*k.value(), &FxBuildHasher::default()).hash()));
+
+        keys.radix_sort_unstable();
+        assert!(keys.windows(2).all(|w| w[0].hash() <= w[1].hash()));
+    }
+}
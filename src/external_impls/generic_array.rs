@@ -0,0 +1,43 @@
+use crate::RadixKey;
+use generic_array::{ArrayLength, GenericArray};
+
+impl<N> RadixKey for GenericArray<u8, N>
+where
+    N: ArrayLength,
+{
+    const LEVELS: usize = N::USIZE;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        self[level]
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RadixSort;
+    use block_pseudorand::block_rand;
+    use generic_array::typenum::U32;
+    use generic_array::GenericArray;
+
+    #[test]
+    pub fn test_generic_array() {
+        let mut inputs: Vec<GenericArray<u8, U32>> = block_rand::<u8>(32 * 100_000)
+            .chunks_exact(32)
+            .map(|c| *GenericArray::from_slice(c))
+            .collect();
+
+        // Level 0 is the least significant byte, mirroring the [u8; N] implementation
+        let mut expected = inputs.clone();
+        expected.sort_by(|a, b| a.iter().rev().cmp(b.iter().rev()));
+
+        inputs.radix_sort_unstable();
+
+        assert_eq!(inputs, expected);
+    }
+}
@@ -0,0 +1,32 @@
+//! `RadixKey` implementations for types from other crates. Each is behind a feature flag of the
+//! same name as the crate, so none of these dependencies are pulled in unless requested.
+
+#[cfg(feature = "ahash")]
+mod ahash;
+#[cfg(feature = "ascii")]
+mod ascii;
+#[cfg(feature = "chrono")]
+mod chrono;
+#[cfg(feature = "fxhash")]
+mod fxhash;
+#[cfg(feature = "generic-array")]
+mod generic_array;
+#[cfg(feature = "num-complex")]
+mod num_complex;
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal;
+#[cfg(feature = "smallvec")]
+mod smallvec;
+#[cfg(feature = "time")]
+mod time;
+
+#[cfg(feature = "ahash")]
+pub use self::ahash::AHashKey;
+#[cfg(feature = "ascii")]
+pub use self::ascii::radix_sort_ascii;
+#[cfg(feature = "fxhash")]
+pub use self::fxhash::FxHashKey;
+#[cfg(feature = "num-complex")]
+pub use self::num_complex::{ByMagnitude, ByParts};
+#[cfg(feature = "smallvec")]
+pub use self::smallvec::radix_sort_smallvecs;
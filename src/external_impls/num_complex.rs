@@ -0,0 +1,107 @@
+use crate::RadixKey;
+use num_complex::Complex;
+
+/// ByMagnitude sorts complex numbers by their magnitude, so values closest to the origin come
+/// first, regardless of phase.
+///
+/// The key is `norm_sqr()`, which orders the same as the magnitude itself without needing a
+/// square root. Values with equal magnitude, such as `1` and `i`, sort as equal. Any value with a
+/// NaN part sorts after all others.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ByMagnitude(pub Complex<f64>);
+
+impl RadixKey for ByMagnitude {
+    const LEVELS: usize = 8;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        self.0.norm_sqr().get_level(level)
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        self.0.norm_sqr().get_all_levels(out);
+    }
+}
+
+/// ByParts sorts complex numbers lexicographically, by the real part and then by the imaginary
+/// part, each ordered the same way as an `f64`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ByParts(pub Complex<f64>);
+
+impl RadixKey for ByParts {
+    const LEVELS: usize = 16;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        if level < 8 {
+            self.0.im.get_level(level)
+        } else {
+            self.0.re.get_level(level - 8)
+        }
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        let (im, re) = out.split_at_mut(8);
+        self.0.im.get_all_levels(im);
+        self.0.re.get_all_levels(re);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ByMagnitude, ByParts};
+    use crate::RadixSort;
+    use block_pseudorand::block_rand;
+    use num_complex::Complex;
+
+    fn inputs(count: usize) -> Vec<Complex<f64>> {
+        block_rand::<i32>(count * 2)
+            .chunks_exact(2)
+            .map(|c| Complex::new(c[0] as f64 / 1000.0, (c[1] % 64) as f64))
+            .collect()
+    }
+
+    #[test]
+    pub fn test_by_magnitude() {
+        let mut data: Vec<ByMagnitude> = inputs(100_000).into_iter().map(ByMagnitude).collect();
+        data.push(ByMagnitude(Complex::new(0.0, -0.0)));
+        data.radix_sort_unstable();
+
+        assert_eq!(data[0].0.norm_sqr(), 0.0);
+        assert!(data
+            .windows(2)
+            .all(|w| w[0].0.norm_sqr() <= w[1].0.norm_sqr()));
+
+        let mut small = [
+            ByMagnitude(Complex::new(3.0, 4.0)),
+            ByMagnitude(Complex::new(-1.0, 0.0)),
+            ByMagnitude(Complex::new(0.0, -2.0)),
+        ];
+        small.radix_sort_unstable();
+
+        assert_eq!(
+            small.map(|c| c.0),
+            [
+                Complex::new(-1.0, 0.0),
+                Complex::new(0.0, -2.0),
+                Complex::new(3.0, 4.0),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_by_parts() {
+        let input = inputs(100_000);
+
+        let mut expected = input.clone();
+        expected.sort_unstable_by(|a, b| a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im)));
+
+        let mut data: Vec<ByParts> = input.into_iter().map(ByParts).collect();
+        data.radix_sort_unstable();
+        let data: Vec<Complex<f64>> = data.into_iter().map(|c| c.0).collect();
+
+        assert_eq!(data, expected);
+    }
+}
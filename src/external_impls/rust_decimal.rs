@@ -0,0 +1,133 @@
+use crate::RadixKey;
+use rust_decimal::Decimal;
+
+const MAX_SCALE: u32 = 28;
+
+// Decimals with different scales can be numerically equal (1.5 == 1.50), so every value is first
+// normalized to the maximum scale of 28. The 96 bit mantissa multiplied by up to 10^28 needs 190
+// bits, so the key is a 192 bit signed fixed-point integer, stored as little-endian u64 limbs and
+// offset so that it sorts as unsigned.
+#[inline]
+fn decimal_key(d: &Decimal) -> [u64; 3] {
+    let mantissa = d.mantissa();
+    let magnitude = mantissa.unsigned_abs();
+    let multiplier = 10u128.pow(MAX_SCALE - d.scale());
+
+    let a = [magnitude as u64, (magnitude >> 64) as u64];
+    let b = [multiplier as u64, (multiplier >> 64) as u64];
+    let mut key = [0u64; 3];
+
+    for (i, a) in a.iter().enumerate() {
+        let mut carry = 0u128;
+
+        for (j, b) in b.iter().enumerate() {
+            let t = key[i + j] as u128 + (*a as u128) * (*b as u128) + carry;
+            key[i + j] = t as u64;
+            carry = t >> 64;
+        }
+
+        if i + 2 < key.len() {
+            key[i + 2] = carry as u64;
+        }
+    }
+
+    // Two's complement negation, so -0 and 0 end up with the same key
+    if mantissa < 0 {
+        let mut carry = 1u64;
+
+        for limb in key.iter_mut() {
+            let (v, c) = (!*limb).overflowing_add(carry);
+            *limb = v;
+            carry = c as u64;
+        }
+    }
+
+    key[2] ^= 1 << 63;
+
+    key
+}
+
+// Sorted by numeric value, matching `Ord` for `Decimal` regardless of scale or sign.
+impl RadixKey for Decimal {
+    const LEVELS: usize = 24;
+    const BATCHED_LEVELS: bool = true;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        (decimal_key(self)[level / 8] >> ((level % 8) * 8)) as u8
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        for (out, limb) in out.chunks_exact_mut(8).zip(decimal_key(self).iter()) {
+            out.copy_from_slice(&limb.to_le_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RadixKey, RadixSort};
+    use block_pseudorand::block_rand;
+    use rust_decimal::Decimal;
+    use std::cmp::Ordering;
+
+    fn key(d: &Decimal) -> Vec<u8> {
+        let mut out = vec![0u8; Decimal::LEVELS];
+        d.get_all_levels(&mut out);
+
+        let levels: Vec<u8> = (0..Decimal::LEVELS).map(|l| d.get_level(l)).collect();
+        assert_eq!(out, levels);
+
+        out.reverse();
+        out
+    }
+
+    #[test]
+    pub fn test_key_order() {
+        let values = [
+            Decimal::MIN,
+            Decimal::new(-15, 0),
+            Decimal::new(-150, 1),
+            Decimal::new(-1, 28),
+            Decimal::new(0, 0),
+            Decimal::new(1, 28),
+            Decimal::new(15, 1),
+            Decimal::new(150, 2),
+            Decimal::new(1_500_000, 6),
+            Decimal::new(2, 0),
+            Decimal::MAX,
+        ];
+
+        for a in values.iter() {
+            for b in values.iter() {
+                assert_eq!(key(a).cmp(&key(b)), a.cmp(b), "{} vs {}", a, b);
+            }
+        }
+
+        let mut negative_zero = Decimal::new(0, 5);
+        negative_zero.set_sign_negative(true);
+        assert_eq!(key(&negative_zero), key(&Decimal::ZERO));
+    }
+
+    #[test]
+    pub fn test_sort() {
+        for n in [0, 1, 100, 100_000] {
+            let mut inputs: Vec<Decimal> = block_rand::<i64>(n)
+                .into_iter()
+                .map(|v| Decimal::new(v, (v as u64 % 29) as u32))
+                .collect();
+
+            let mut expected = inputs.clone();
+            expected.sort();
+
+            inputs.radix_sort_unstable();
+
+            // Equal values may have differing scales, so compare numerically
+            assert!(inputs
+                .iter()
+                .zip(expected.iter())
+                .all(|(a, b)| a.cmp(b) == Ordering::Equal));
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::multi_key_sort::sort_by_byte_strings;
+use smallvec::{Array, SmallVec};
+
+/// radix_sort_smallvecs sorts variable-length byte strings stored as `SmallVec`s, such as
+/// `SmallVec<[u8; 16]>`, in lexicographic byte order, where a string sorts before any longer
+/// string it is a prefix of. This is the same order as `Ord` for `SmallVec`.
+///
+/// As `SmallVec` isn't `Copy`, it can't implement `RadixKey` directly. Instead, each string is
+/// copied once into a fixed-width key of its bytes, padded with zeros up to the length of the
+/// longest string, followed by its length to order strings which only differ by trailing zeros.
+/// These keys are radix sorted, and the resulting order applied to `data` by moving each
+/// `SmallVec` exactly once, so spilled strings are never reallocated.
+///
+/// Every key is as wide as the longest string, so a single very long string makes the sort
+/// slower and use more memory for all of the others. Strings longer than about `MAX_LEVELS`
+/// bytes panic.
+///
+/// ```
+/// use rdst::radix_sort_smallvecs;
+/// use smallvec::{smallvec, SmallVec};
+///
+/// // The last key is too long to fit inline, so it is spilled to the heap
+/// let mut keys: Vec<SmallVec<[u8; 4]>> =
+///     vec![smallvec![2, 1], smallvec![2], smallvec![1, 9, 9, 9, 9]];
+/// radix_sort_smallvecs(&mut keys);
+///
+/// let sorted: Vec<&[u8]> = keys.iter().map(|k| k.as_slice()).collect();
+/// assert_eq!(sorted, [&[1, 9, 9, 9, 9][..], &[2], &[2, 1]]);
+/// ```
+pub fn radix_sort_smallvecs<A>(data: &mut [SmallVec<A>])
+where
+    A: Array<Item = u8>,
+{
+    sort_by_byte_strings(data, |v| v.as_slice());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::radix_sort_smallvecs;
+    use block_pseudorand::block_rand;
+    use smallvec::SmallVec;
+
+    #[test]
+    pub fn test_smallvec() {
+        let lens = block_rand::<u8>(100_000);
+        let bytes = block_rand::<u8>(100_000);
+
+        for n in [0, 1, 2, 100, 100_000] {
+            // Mostly inline, with some spilled to the heap. Bytes are limited to a few values so
+            // that there are plenty of shared prefixes and trailing zeros.
+            let mut inputs: Vec<SmallVec<[u8; 16]>> = (0..n)
+                .map(|i| {
+                    let len = if lens[i] < 16 {
+                        20 + lens[i] as usize
+                    } else {
+                        lens[i] as usize % 17
+                    };
+                    (0..len).map(|j| bytes[(i + j * 7) % n] % 3).collect()
+                })
+                .collect();
+            assert!(n < 100 || inputs.iter().any(|v| v.spilled()));
+
+            let mut expected = inputs.clone();
+            expected.sort_unstable();
+
+            radix_sort_smallvecs(&mut inputs);
+
+            assert_eq!(inputs, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_smallvec_prefixes() {
+        let mut inputs: Vec<SmallVec<[u8; 2]>> = vec![
+            SmallVec::from_slice(&[1, 0, 0]),
+            SmallVec::from_slice(&[1, 0]),
+            SmallVec::new(),
+            SmallVec::from_slice(&[0]),
+            SmallVec::from_slice(&[1]),
+            SmallVec::from_slice(&[0, 255]),
+        ];
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        radix_sort_smallvecs(&mut inputs);
+
+        assert_eq!(inputs, expected);
+    }
+}
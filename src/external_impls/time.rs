@@ -0,0 +1,89 @@
+use crate::RadixKey;
+use time::{Date, OffsetDateTime};
+
+// Sorted by the instant in time, matching `Ord` for `OffsetDateTime` regardless of the offset.
+// Computing the timestamp isn't free, so all levels are extracted at once where possible.
+impl RadixKey for OffsetDateTime {
+    const LEVELS: usize = 16;
+    const BATCHED_LEVELS: bool = true;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        ((self.unix_timestamp_nanos() ^ i128::MIN) >> (level * 8)) as u8
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&(self.unix_timestamp_nanos() ^ i128::MIN).to_le_bytes());
+    }
+}
+
+impl RadixKey for Date {
+    const LEVELS: usize = 4;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        ((self.to_julian_day() ^ i32::MIN) >> (level * 8)) as u8
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&(self.to_julian_day() ^ i32::MIN).to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RadixSort;
+    use block_pseudorand::block_rand;
+    use time::{Date, Duration, OffsetDateTime, UtcOffset};
+
+    // Dates spread roughly +/- 500 years either side of the unix epoch
+    fn gen_date_times(n: usize) -> Vec<OffsetDateTime> {
+        block_rand::<i64>(n)
+            .into_iter()
+            .map(|v| {
+                let secs = v % (500 * 365 * 86_400);
+                let nanos = (v as u64 % 1_000_000_000) as i64;
+                let offset = UtcOffset::from_hms((v % 13) as i8, 0, 0).unwrap();
+
+                (OffsetDateTime::UNIX_EPOCH
+                    + Duration::seconds(secs)
+                    + Duration::nanoseconds(nanos))
+                .to_offset(offset)
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_offset_date_time() {
+        for n in [0, 1, 100, 100_000] {
+            let mut inputs = gen_date_times(n);
+            assert!(n < 100 || inputs.iter().any(|d| d.year() < 1970));
+
+            let mut expected = inputs.clone();
+            expected.sort();
+
+            inputs.radix_sort_unstable();
+
+            // Equal instants may have differing offsets, so compare the instants themselves
+            let actual: Vec<i128> = inputs.iter().map(|d| d.unix_timestamp_nanos()).collect();
+            let expected: Vec<i128> = expected.iter().map(|d| d.unix_timestamp_nanos()).collect();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_date() {
+        let mut inputs: Vec<Date> = gen_date_times(100_000)
+            .into_iter()
+            .map(|d| d.date())
+            .collect();
+        let mut expected = inputs.clone();
+        expected.sort();
+
+        inputs.radix_sort_unstable();
+
+        assert_eq!(inputs, expected);
+    }
+}
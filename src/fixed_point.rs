@@ -0,0 +1,71 @@
+use crate::RadixKey;
+
+/// `FixedPoint` wraps a fixed-point decimal stored as an `i64` scaled by `SCALE`, such as prices
+/// in cents with a `SCALE` of `100`. Every value shares the same scale, so the order of the scaled
+/// integers is the order of the decimals they represent, and it sorts in the same order as the
+/// plain `i64`, with negative values first.
+///
+/// ```
+/// use rdst::{FixedPoint, RadixSort};
+///
+/// // Prices in cents
+/// let mut prices = vec![FixedPoint::<100>(1_999), FixedPoint(-250), FixedPoint(5)];
+/// prices.radix_sort_unstable();
+///
+/// let decimals: Vec<f64> = prices.iter().map(|p| p.to_f64()).collect();
+/// assert_eq!(decimals, [-2.5, 0.05, 19.99]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct FixedPoint<const SCALE: i64>(pub i64);
+
+impl<const SCALE: i64> FixedPoint<SCALE> {
+    /// `to_f64` returns the decimal value, the scaled integer divided by `SCALE`. This is rounded
+    /// to the nearest `f64`, so isn't suitable for further exact arithmetic.
+    #[inline]
+    pub fn to_f64(&self) -> f64 {
+        self.0 as f64 / SCALE as f64
+    }
+}
+
+impl<const SCALE: i64> RadixKey for FixedPoint<SCALE> {
+    const LEVELS: usize = 8;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        self.0.get_level(level)
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        self.0.get_all_levels(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FixedPoint, RadixSort};
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_mixed_sign_order() {
+        let mut prices: Vec<FixedPoint<100>> = block_rand::<i64>(100_000)
+            .into_iter()
+            .map(|v| FixedPoint(v % 1_000_000))
+            .collect();
+        let mut expected: Vec<f64> = prices.iter().map(|p| p.to_f64()).collect();
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        prices.radix_sort_unstable();
+        let actual: Vec<f64> = prices.iter().map(|p| p.to_f64()).collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    pub fn test_to_f64() {
+        assert_eq!(FixedPoint::<100>(-1).to_f64(), -0.01);
+        assert_eq!(FixedPoint::<1_000>(12_345).to_f64(), 12.345);
+        assert_eq!(FixedPoint::<1>(-7).to_f64(), -7.0);
+    }
+}
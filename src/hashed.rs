@@ -0,0 +1,288 @@
+use crate::RadixKey;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::marker::PhantomData;
+
+/// `Hashed` wraps a value to sort it by a `u64` hash of the value, computed with the `Hasher` `H`,
+/// rather than by the value itself. This is useful for grouping values which have no `RadixKey`
+/// implementation, or whose order doesn't matter, such as strings for a group-by.
+///
+/// After sorting, equal values are adjacent. However, the order between groups is arbitrary, and
+/// two different values whose hashes collide may be mixed together in the same group, so check
+/// values for equality within each group if that matters. With a 64 bit hash, collisions are
+/// unlikely but not impossible. To keep colliding values apart, sort `(u64, K)` tuples of the hash
+/// and a `RadixKey` of the exact value instead, which are ordered by the hash first and then by
+/// the exact value.
+///
+/// The hash is computed once, by `new()`. As the sort requires `Copy` items, wrap a reference to
+/// values such as a `String` which aren't `Copy`.
+///
+/// ```
+/// use rdst::{Hashed, RadixSort};
+///
+/// let names = ["b", "a", "c", "a", "b"];
+/// let mut keys: Vec<Hashed<&str>> = names.iter().copied().map(Hashed::new).collect();
+/// keys.radix_sort_unstable();
+///
+/// let grouped: Vec<&str> = keys.iter().map(|k| *k.value()).collect();
+/// assert_eq!(grouped.iter().filter(|n| **n == "a").count(), 2);
+/// ```
+pub struct Hashed<T, H = DefaultHasher> {
+    value: T,
+    hash: u64,
+    _hasher: PhantomData<fn() -> H>,
+}
+
+impl<T, H> Hashed<T, H>
+where
+    T: Hash,
+    H: Hasher + Default,
+{
+    /// `new()` wraps `value`, computing its hash with a `Default` instance of `H`.
+    pub fn new(value: T) -> Self {
+        let mut hasher = H::default();
+        value.hash(&mut hasher);
+
+        Self {
+            hash: hasher.finish(),
+            value,
+            _hasher: PhantomData,
+        }
+    }
+}
+
+impl<T, H> Hashed<T, H> {
+    /// `value()` returns the wrapped value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// `hash()` returns the hash of the wrapped value, which is what it is sorted by.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// `into_inner()` unwraps the value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Clone, H> Clone for Hashed<T, H> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            hash: self.hash,
+            _hasher: PhantomData,
+        }
+    }
+}
+
+impl<T: Copy, H> Copy for Hashed<T, H> {}
+
+impl<T: fmt::Debug, H> fmt::Debug for Hashed<T, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hashed")
+            .field("value", &self.value)
+            .field("hash", &self.hash)
+            .finish()
+    }
+}
+
+impl<T, H> RadixKey for Hashed<T, H> {
+    const LEVELS: usize = 8;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        self.hash.get_level(level)
+    }
+}
+
+/// `HashKey` wraps a value to sort it by a `u64` hash of the value, as with `Hashed`, but computes
+/// the hash with a `BuildHasher` instance rather than a `Default` `Hasher`. This suits hashers
+/// which are seeded, such as `std::collections::hash_map::RandomState` or `ahash::RandomState`,
+/// so that a fast non-cryptographic hash can be picked for grouping.
+///
+/// The same `BuildHasher` instance must be used for every value in a sort, as differently seeded
+/// instances give the same value different hashes. With a single instance, the same value always
+/// has the same hash, so equal values are adjacent after sorting. As with `Hashed`, the order
+/// between groups is arbitrary, and values whose hashes collide may be mixed together.
+///
+/// With the `ahash` or `fxhash` features, `AHashKey` and `FxHashKey` are provided as shorthands for
+/// those hashers.
+///
+/// ```
+/// use rdst::{HashKey, RadixSort};
+/// use std::collections::hash_map::RandomState;
+///
+/// let state = RandomState::new();
+/// let names = ["b", "a", "c", "a", "b"];
+/// let mut keys: Vec<HashKey<&str, RandomState>> =
+///     names.iter().map(|n| HashKey::new(*n, &state)).collect();
+/// keys.radix_sort_unstable();
+///
+/// let grouped: Vec<&str> = keys.iter().map(|k| *k.value()).collect();
+/// assert_eq!(grouped.iter().filter(|n| **n == "a").count(), 2);
+/// ```
+pub struct HashKey<T, S> {
+    value: T,
+    hash: u64,
+    _build_hasher: PhantomData<fn() -> S>,
+}
+
+impl<T, S> HashKey<T, S>
+where
+    T: Hash,
+    S: BuildHasher,
+{
+    /// `new()` wraps `value`, computing its hash with `build_hasher`.
+    pub fn new(value: T, build_hasher: &S) -> Self {
+        Self {
+            hash: build_hasher.hash_one(&value),
+            value,
+            _build_hasher: PhantomData,
+        }
+    }
+}
+
+impl<T, S> HashKey<T, S> {
+    /// `value()` returns the wrapped value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// `hash()` returns the hash of the wrapped value, which is what it is sorted by.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// `into_inner()` unwraps the value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Clone, S> Clone for HashKey<T, S> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            hash: self.hash,
+            _build_hasher: PhantomData,
+        }
+    }
+}
+
+impl<T: Copy, S> Copy for HashKey<T, S> {}
+
+impl<T: fmt::Debug, S> fmt::Debug for HashKey<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HashKey")
+            .field("value", &self.value)
+            .field("hash", &self.hash)
+            .finish()
+    }
+}
+
+impl<T, S> RadixKey for HashKey<T, S> {
+    const LEVELS: usize = 8;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        self.hash.get_level(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HashKey, Hashed, RadixSort};
+    use block_pseudorand::block_rand;
+    use std::collections::HashSet;
+
+    #[test]
+    pub fn test_grouping() {
+        let names: Vec<String> = block_rand::<u16>(100_000)
+            .into_iter()
+            .map(|v| format!("name-{}", v % 500))
+            .collect();
+
+        let mut keys: Vec<Hashed<&String>> = names.iter().map(Hashed::new).collect();
+        keys.radix_sort_unstable();
+
+        // Each distinct value must appear in exactly one contiguous run
+        let mut seen = HashSet::new();
+        let mut prev: Option<&String> = None;
+
+        for k in keys.iter() {
+            if prev != Some(*k.value()) {
+                assert!(seen.insert(*k.value()), "{} is not grouped", k.value());
+                prev = Some(*k.value());
+            }
+        }
+
+        assert_eq!(seen.len(), 500);
+        assert!(keys.windows(2).all(|w| w[0].hash() <= w[1].hash()));
+    }
+
+    #[test]
+    pub fn test_custom_hasher() {
+        #[derive(Default)]
+        struct SumHasher(u64);
+
+        impl std::hash::Hasher for SumHasher {
+            fn finish(&self) -> u64 {
+                self.0
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                self.0 += bytes.iter().map(|b| *b as u64).sum::<u64>();
+            }
+        }
+
+        let mut keys: Vec<Hashed<[u8; 2], SumHasher>> = [[9, 9], [1, 0], [2, 3]]
+            .iter()
+            .copied()
+            .map(Hashed::new)
+            .collect();
+        keys.radix_sort_unstable();
+
+        let values: Vec<[u8; 2]> = keys.into_iter().map(Hashed::into_inner).collect();
+        assert_eq!(values, [[1, 0], [2, 3], [9, 9]]);
+    }
+
+    #[test]
+    pub fn test_hash_key() {
+        use std::collections::hash_map::RandomState;
+
+        let state = RandomState::new();
+        let values: Vec<u16> = block_rand::<u16>(100_000)
+            .into_iter()
+            .map(|v| v % 300)
+            .collect();
+
+        // The same value always maps to the same hash with the same instance
+        let first: Vec<HashKey<u16, RandomState>> =
+            values.iter().map(|v| HashKey::new(*v, &state)).collect();
+        let second: Vec<HashKey<u16, RandomState>> =
+            values.iter().map(|v| HashKey::new(*v, &state)).collect();
+        assert!(first
+            .iter()
+            .zip(second.iter())
+            .all(|(a, b)| a.hash() == b.hash()));
+
+        let mut keys = first;
+        keys.radix_sort_unstable();
+
+        let mut seen = HashSet::new();
+        let mut prev = None;
+
+        for k in keys.iter() {
+            if prev != Some(*k.value()) {
+                assert!(seen.insert(*k.value()), "{} is not grouped", k.value());
+                prev = Some(*k.value());
+            }
+        }
+
+        assert_eq!(seen.len(), 300);
+    }
+}
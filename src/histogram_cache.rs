@@ -0,0 +1,174 @@
+use crate::utils::{get_counts, get_end_offsets, get_prefix_sums};
+use crate::RadixKey;
+
+/// `HistogramCache` keeps the counts of the top level of a slice from one sort to the next, so
+/// that when only a few items change between sorts, the counts can be updated for just those
+/// items rather than recounted. Use it with `RadixSortBuilder::sort_with_cache()`.
+///
+/// The cache is tied to the slice it was last used with, by its address and length. If it is
+/// used with any other slice, including the same `Vec` after it has grown or been reallocated,
+/// everything is counted again.
+///
+/// The caller must report the index of every item changed since the last sort. In debug builds
+/// the updated counts are checked against a full recount, and a mismatch panics.
+///
+/// ```
+/// use rdst::{HistogramCache, RadixSort};
+///
+/// let mut data: Vec<u32> = vec![50, 10, 40, 30, 20];
+/// let mut cache = HistogramCache::new();
+///
+/// data.radix_sort_builder().sort_with_cache(&mut cache, &[]);
+/// assert_eq!(data, [10, 20, 30, 40, 50]);
+///
+/// data[1] = 60;
+/// data.radix_sort_builder().sort_with_cache(&mut cache, &[1]);
+/// assert_eq!(data, [10, 30, 40, 50, 60]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct HistogramCache {
+    counts: Option<[usize; 256]>,
+    ptr: usize,
+    len: usize,
+    level: usize,
+}
+
+impl HistogramCache {
+    /// `new()` creates an empty cache. The first sort using it counts everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `clear()` empties the cache, so the next sort using it counts everything.
+    pub fn clear(&mut self) {
+        self.counts = None;
+    }
+
+    /// `counts` returns the counts of `level` for `data`, where `data` is unchanged since it was
+    /// last sorted and stored in the cache, apart from the items at `changed`. Returns `None` if
+    /// `data` has nothing changed since, and so is still sorted.
+    pub(crate) fn counts<T>(
+        &self,
+        data: &[T],
+        level: usize,
+        changed: &[usize],
+    ) -> Option<[usize; 256]>
+    where
+        T: RadixKey,
+    {
+        let cached = match self.counts {
+            Some(counts)
+                if self.ptr == data.as_ptr() as usize
+                    && self.len == data.len()
+                    && self.level == level =>
+            {
+                counts
+            }
+            _ => return Some(get_counts(data, level).0),
+        };
+
+        if changed.is_empty() {
+            return None;
+        }
+
+        let mut changed = changed.to_vec();
+        changed.sort_unstable();
+        changed.dedup();
+
+        // As the data was sorted when it was cached, the old bucket of each changed item is
+        // whichever bucket its index falls in.
+        let end_offsets = get_end_offsets(&cached, &get_prefix_sums(&cached));
+        let mut counts = cached;
+
+        for i in changed {
+            let old = end_offsets.partition_point(|&end| end <= i);
+            let new = data[i].get_level(level) as usize;
+
+            counts[old] -= 1;
+            counts[new] += 1;
+        }
+
+        debug_assert_eq!(
+            counts,
+            get_counts(data, level).0,
+            "HistogramCache: not every item changed since the last sort was reported"
+        );
+
+        Some(counts)
+    }
+
+    pub(crate) fn store<T>(&mut self, data: &[T], level: usize, counts: [usize; 256]) {
+        self.counts = Some(counts);
+        self.ptr = data.as_ptr() as usize;
+        self.len = data.len();
+        self.level = level;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HistogramCache, RadixSort};
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_sort_with_cache() {
+        let mut data: Vec<u32> = block_rand(1_000_000);
+        let mut cache = HistogramCache::new();
+
+        data.radix_sort_builder().sort_with_cache(&mut cache, &[]);
+
+        let mut expected = data.clone();
+        expected.sort_unstable();
+        assert_eq!(data, expected);
+
+        for round in 0..5usize {
+            let changed: Vec<usize> = block_rand::<u32>(100)
+                .into_iter()
+                .map(|v| v as usize % data.len())
+                .collect();
+            let values: Vec<u32> = block_rand(changed.len());
+
+            for (i, v) in changed.iter().zip(values) {
+                data[*i] = v;
+            }
+
+            // Duplicate indices are allowed
+            let mut reported = changed.clone();
+            reported.push(changed[round]);
+
+            data.radix_sort_builder()
+                .sort_with_cache(&mut cache, &reported);
+
+            let mut expected = data.clone();
+            expected.sort_unstable();
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_different_input() {
+        let mut cache = HistogramCache::new();
+        let mut a: Vec<u64> = block_rand(10_000);
+        let mut b: Vec<u64> = block_rand(10_000);
+
+        a.radix_sort_builder().sort_with_cache(&mut cache, &[]);
+        b.radix_sort_builder().sort_with_cache(&mut cache, &[]);
+
+        let mut expected = b.clone();
+        expected.sort_unstable();
+        assert_eq!(b, expected);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not every item changed since the last sort was reported")]
+    pub fn test_unreported_change() {
+        let mut data: Vec<u32> = vec![0x0100_0000, 0x0200_0000, 0x0300_0000];
+        let mut cache = HistogramCache::new();
+
+        data.radix_sort_builder().sort_with_cache(&mut cache, &[]);
+        data[0] = 0x0400_0000;
+        data[1] = 0x0500_0000;
+        data.radix_sort_builder().sort_with_cache(&mut cache, &[0]);
+    }
+}
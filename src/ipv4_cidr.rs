@@ -0,0 +1,175 @@
+use crate::RadixKey;
+use std::net::Ipv4Addr;
+
+/// `Ipv4Cidr` is an IPv4 CIDR block, such as `10.0.0.0/8`, stored as the address as a `u32` in
+/// host byte order, along with the length of its prefix in bits.
+///
+/// Blocks sort by their network address, which is `addr` with every bit past the prefix cleared,
+/// in ascending order. Blocks with the same network address then sort by descending prefix length,
+/// so the most specific block comes first. Any host bits set in `addr` are ignored by the sort, so
+/// `10.1.2.3/8` and `10.0.0.0/8` are the same key.
+///
+/// Note that this only puts a more specific block first when it starts at the same address as the
+/// less specific block containing it. A block contained within another which starts at a later
+/// address, such as `10.1.0.0/16` within `10.0.0.0/8`, sorts after it, as its network address is
+/// larger. For example, these are in sorted order:
+///
+/// `0.0.0.0/0`, `10.0.0.0/16`, `10.0.0.0/8`, `10.1.0.0/16`, `10.1.2.0/24`, `192.168.0.0/16`
+///
+/// In debug builds, `new()` panics if `prefix_len` is more than 32. Release builds skip the check,
+/// and a block built with a longer prefix sorts as a `/32` which is more specific than any other.
+///
+/// ```
+/// use rdst::{Ipv4Cidr, RadixSort};
+/// use std::net::Ipv4Addr;
+///
+/// let mut blocks = vec![
+///     Ipv4Cidr::new(Ipv4Addr::new(10, 0, 0, 0), 8),
+///     Ipv4Cidr::new(Ipv4Addr::new(10, 0, 0, 0), 24),
+///     Ipv4Cidr::new(Ipv4Addr::new(0, 0, 0, 0), 0),
+/// ];
+/// blocks.radix_sort_unstable();
+///
+/// let lens: Vec<u8> = blocks.iter().map(|b| b.prefix_len).collect();
+/// assert_eq!(lens, [0, 24, 8]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Ipv4Cidr {
+    pub addr: u32,
+    pub prefix_len: u8,
+}
+
+impl Ipv4Cidr {
+    /// `new` creates the block `addr/prefix_len`.
+    #[inline]
+    pub fn new(addr: Ipv4Addr, prefix_len: u8) -> Self {
+        debug_assert!(
+            prefix_len <= 32,
+            "Ipv4Cidr: prefix length {} is more than 32",
+            prefix_len
+        );
+
+        Self {
+            addr: u32::from(addr),
+            prefix_len,
+        }
+    }
+
+    /// `network` returns the network address of the block, which is `addr` with every bit past the
+    /// prefix cleared.
+    #[inline]
+    pub fn network(&self) -> Ipv4Addr {
+        Ipv4Addr::from(self.masked())
+    }
+
+    #[inline]
+    fn masked(&self) -> u32 {
+        let host_bits = 32u32.saturating_sub(self.prefix_len as u32);
+
+        self.addr & u32::MAX.checked_shl(host_bits).unwrap_or(0)
+    }
+}
+
+impl RadixKey for Ipv4Cidr {
+    const LEVELS: usize = 5;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        if level == 0 {
+            !self.prefix_len
+        } else {
+            self.masked().get_level(level - 1)
+        }
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out[0] = !self.prefix_len;
+        self.masked().get_all_levels(&mut out[1..]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Ipv4Cidr, RadixSort};
+    use block_pseudorand::block_rand;
+    use std::cmp::Reverse;
+    use std::net::Ipv4Addr;
+
+    fn cidr(a: u8, b: u8, c: u8, d: u8, prefix_len: u8) -> Ipv4Cidr {
+        Ipv4Cidr::new(Ipv4Addr::new(a, b, c, d), prefix_len)
+    }
+
+    #[test]
+    pub fn test_overlapping_prefixes() {
+        let mut blocks = vec![
+            cidr(192, 168, 0, 0, 16),
+            cidr(10, 1, 2, 0, 24),
+            cidr(10, 0, 0, 0, 8),
+            cidr(10, 1, 0, 0, 16),
+            cidr(0, 0, 0, 0, 0),
+            cidr(10, 0, 0, 0, 16),
+            cidr(10, 1, 2, 3, 32),
+            cidr(10, 0, 0, 0, 32),
+            cidr(192, 168, 0, 0, 24),
+        ];
+        blocks.radix_sort_unstable();
+
+        assert_eq!(
+            blocks,
+            [
+                cidr(0, 0, 0, 0, 0),
+                cidr(10, 0, 0, 0, 32),
+                cidr(10, 0, 0, 0, 16),
+                cidr(10, 0, 0, 0, 8),
+                cidr(10, 1, 0, 0, 16),
+                cidr(10, 1, 2, 0, 24),
+                cidr(10, 1, 2, 3, 32),
+                cidr(192, 168, 0, 0, 24),
+                cidr(192, 168, 0, 0, 16),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_host_bits_ignored() {
+        // Both are 10.0.0.0/8 once masked, so neither moves ahead of 10.0.0.0/16
+        let mut blocks = vec![
+            cidr(10, 255, 255, 255, 8),
+            cidr(10, 0, 0, 0, 16),
+            cidr(10, 0, 0, 0, 8),
+        ];
+        blocks.radix_sort_unstable();
+
+        assert_eq!(blocks[0], cidr(10, 0, 0, 0, 16));
+        assert!(blocks[1..].contains(&cidr(10, 255, 255, 255, 8)));
+        assert!(blocks[1..].contains(&cidr(10, 0, 0, 0, 8)));
+        assert_eq!(blocks[1].network(), blocks[2].network());
+    }
+
+    #[test]
+    pub fn test_random_blocks() {
+        let key = |b: &Ipv4Cidr| (b.network(), Reverse(b.prefix_len));
+        let mut blocks: Vec<Ipv4Cidr> = block_rand::<u64>(100_000)
+            .into_iter()
+            .map(|v| Ipv4Cidr {
+                addr: v as u32,
+                prefix_len: (v >> 32) as u8 % 33,
+            })
+            .collect();
+
+        let mut expected: Vec<_> = blocks.iter().map(key).collect();
+        expected.sort_unstable();
+        blocks.radix_sort_unstable();
+
+        let actual: Vec<_> = blocks.iter().map(key).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Ipv4Cidr: prefix length 33 is more than 32")]
+    pub fn test_prefix_too_long() {
+        cidr(10, 0, 0, 0, 33);
+    }
+}
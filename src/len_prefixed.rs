@@ -0,0 +1,175 @@
+use crate::RadixKey;
+
+/// `LenPrefixed` stores a variable-length byte key of up to `MAX` bytes inline, so that it is
+/// `Copy` and fixed-size. Only the first `len` bytes are part of the key, and it sorts in
+/// byte-lexicographic order, as with `&[u8]`: by content first, and then shorter keys before
+/// longer keys which they are a prefix of. Whatever is stored past `len` is ignored.
+///
+/// There is no byte value below `0` for the positions past the end of a shorter key, so they are
+/// read as `0`, and the length is added as the two least significant levels to break the tie. Two
+/// keys can only be equal up to `MAX` where one is padded with zeros, and the other has real zeros
+/// in the same positions, such as `b"ab"` and `b"ab\0"`. In that case the shorter key is a prefix
+/// of the longer one, so sorting it first by length gives the same order as comparing the bytes.
+///
+/// Every key has `MAX + 2` levels, whatever its length, so keep `MAX` close to the longest key.
+///
+/// ```
+/// use rdst::{LenPrefixed, RadixSort};
+///
+/// let mut keys: Vec<LenPrefixed<8>> = vec![b"pear".as_ref(), b"ap", b"apple", b"a"]
+///     .into_iter()
+///     .map(LenPrefixed::new)
+///     .collect();
+/// keys.radix_sort_unstable();
+///
+/// let actual: Vec<&[u8]> = keys.iter().map(|k| k.as_bytes()).collect();
+/// assert_eq!(actual, [b"a".as_ref(), b"ap", b"apple", b"pear"]);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct LenPrefixed<const MAX: usize> {
+    pub len: u16,
+    pub bytes: [u8; MAX],
+}
+
+impl<const MAX: usize> LenPrefixed<MAX> {
+    /// `new` copies `bytes` into a new key, zeroing the rest. Panics if `bytes` is longer than
+    /// `MAX`, or than `u16::MAX`.
+    #[inline]
+    pub fn new(bytes: &[u8]) -> Self {
+        assert!(
+            bytes.len() <= MAX && bytes.len() <= u16::MAX as usize,
+            "LenPrefixed: {} bytes don't fit in {}",
+            bytes.len(),
+            MAX
+        );
+
+        let mut out = Self {
+            len: bytes.len() as u16,
+            bytes: [0; MAX],
+        };
+        out.bytes[..bytes.len()].copy_from_slice(bytes);
+
+        out
+    }
+
+    /// `as_bytes` returns the first `len` bytes, which make up the key.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len()]
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        debug_assert!(
+            self.len as usize <= MAX,
+            "LenPrefixed: len {} is larger than {}",
+            self.len,
+            MAX
+        );
+
+        (self.len as usize).min(MAX)
+    }
+}
+
+impl<const MAX: usize> PartialEq for LenPrefixed<MAX> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl<const MAX: usize> Eq for LenPrefixed<MAX> {}
+
+impl<const MAX: usize> RadixKey for LenPrefixed<MAX> {
+    const LEVELS: usize = MAX + 2;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        if level < 2 {
+            return self.len.to_le_bytes()[level];
+        }
+
+        // The first byte is the most significant level, and positions past `len` read as `0`
+        let i = MAX + 1 - level;
+        if i < self.len() {
+            self.bytes[i]
+        } else {
+            0
+        }
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        let len = self.len();
+
+        out[..2].copy_from_slice(&self.len.to_le_bytes());
+        for (i, o) in out[2..].iter_mut().rev().enumerate() {
+            *o = if i < len { self.bytes[i] } else { 0 };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LenPrefixed, RadixKey, RadixSort};
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_lexicographic_order() {
+        // A small alphabet including `0`, so there are plenty of shared prefixes and real zeros
+        // where a shorter key is padded, with random bytes left past `len`
+        let lens = block_rand::<u8>(100_000);
+        let contents = block_rand::<u8>(100_000 * 12);
+        let mut keys: Vec<LenPrefixed<12>> = lens
+            .iter()
+            .zip(contents.chunks_exact(12))
+            .map(|(len, content)| {
+                let len = (len % 13) as usize;
+                let mut bytes = [0u8; 12];
+                bytes.copy_from_slice(content);
+                for b in bytes[..len].iter_mut() {
+                    *b %= 3;
+                }
+
+                LenPrefixed {
+                    len: len as u16,
+                    bytes,
+                }
+            })
+            .collect();
+
+        let mut expected: Vec<Vec<u8>> = keys.iter().map(|k| k.as_bytes().to_vec()).collect();
+        expected.sort_unstable();
+        keys.radix_sort_unstable();
+
+        let actual: Vec<Vec<u8>> = keys.iter().map(|k| k.as_bytes().to_vec()).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    pub fn test_trailing_zeros() {
+        // Equal up to `MAX`, and only ordered by length
+        let mut keys: Vec<LenPrefixed<4>> =
+            vec![b"ab\0\0".as_ref(), b"ab", b"", b"\0", b"ab\0", b"a"]
+                .into_iter()
+                .map(LenPrefixed::new)
+                .collect();
+        keys.radix_sort_unstable();
+
+        let actual: Vec<&[u8]> = keys.iter().map(|k| k.as_bytes()).collect();
+        assert_eq!(
+            actual,
+            [b"".as_ref(), b"\0", b"a", b"ab", b"ab\0", b"ab\0\0"]
+        );
+
+        let mut garbage = LenPrefixed::<4>::new(b"ab");
+        garbage.bytes[2] = 0xFF;
+        let mut levels = [0u8; 6];
+        garbage.get_all_levels(&mut levels);
+
+        for (level, l) in levels.iter().enumerate() {
+            assert_eq!(*l, garbage.get_level(level));
+            assert_eq!(*l, LenPrefixed::<4>::new(b"ab").get_level(level));
+        }
+    }
+}
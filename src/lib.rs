@@ -21,6 +21,22 @@
 //!  * `i8`, `i16`, `i32`, `i64`, `i128`, `isize`
 //!  * `f32`, `f64`
 //!  * `[u8; N]`
+//!  * `(u8, [u8; N])`, sorted by the tag byte and then the array
+//!  * `[i8; N]`, `[i16; N]`, `[i32; N]` and `[i64; N]`, sorted lexicographically (the first element is the most significant)
+//!  * `[bool; N]`, sorted as an N bit binary number (the first element is the most significant bit)
+//!  * `(u64, K)` for any `K: RadixKey`, sorted by the `u64` first and then by `K`
+//!
+//! To treat NaN float keys as a bug, wrap them in `StrictFloat`, which panics on a NaN in debug builds. Release builds skip the check. Floats sort `-0.0` before `+0.0` by default; wrap them in `NormFloat` to sort both zeros as the same key.
+//!
+//! Timestamps stored as `i64` milliseconds since the Unix epoch can be wrapped in `Millis`, which sorts times before the epoch first, as with the plain `i64`. Fixed-point decimals stored as a scaled `i64`, such as prices in cents, can likewise be wrapped in `FixedPoint<SCALE>`, which also converts them back with `to_f64()`. Fixed-width, zero-padded ASCII numbers, such as `b"0000123"`, can be wrapped in `NumericAscii` to sort them in numeric order, with a debug check that every byte is a digit. Note that `[u8; N]` treats its last byte as the most significant, so 4-byte big-endian integers should be wrapped in `BeU32` instead, which also decodes them with `value()`. Unsigned integers used as sets of flags can be wrapped in `ByPopcount`, which sorts sets with more flags set first, and then by the flags themselves. Software versions can be encoded into a `u64` with `SemverKey::new(major, minor, patch)`, which sorts in version order and decodes them again with `parts()`. Variable-length byte keys of up to `MAX` bytes can be stored inline in `LenPrefixed<MAX>`, which sorts them in byte-lexicographic order, ignoring anything stored past their length. Firewall-style IPv4 CIDR blocks can be stored in `Ipv4Cidr`, which sorts them by network address, and then puts the most specific of the blocks starting at the same address first.
+//!
+//! Any other `Copy` type which converts into a `u64`, such as a simple enum, can be sorted by wrapping it in `AsU64`, optionally with the number of bits the values fit into.
+//!
+//! As `Range` isn't `Copy`, ranges can be converted into `RadixRange` to sort them by start, then by end.
+//!
+//! To group equal values together when their order doesn't matter, such as for a group-by on strings, wrap them in `Hashed` to sort by a hash of each value instead. Values with colliding hashes may end up mixed together. To hash with a seeded `BuildHasher` instead, such as `ahash::RandomState`, wrap them in `HashKey`. The `ahash` and `fxhash` features add `AHashKey` and `FxHashKey` as shorthands for those hashers. To keep them apart, sort `(hash, key)` tuples instead, with the `u64` hash first and the exact key second. The hash decides the order between groups, and the exact key only breaks ties within a group of colliding hashes, so this is the recommended way to group large keys cheaply.
+//!
+//! To sort 2D points so that points close together in space end up close together in the output, store them as a `morton::MortonKey`, which sorts by the point's Morton (Z-order) code.
 //!
 //! ### Implementing `RadixKey`
 //!
@@ -32,6 +48,7 @@
 //! Notes:
 //! * This allows you to implement radix keys that span multiple values, or to implement radix keys that only look at part of a value.
 //! * You should try to make this as fast as possible, so consider using branchless implementations wherever possible
+//! * `LEVELS` can be at most `MAX_LEVELS` (65,536). Sorting a key with more fails to compile, which guards against a mis-set `LEVELS` allocating huge amounts of memory for counts.
 //!
 //! ```
 //! use rdst::RadixKey;
@@ -91,6 +108,195 @@
 //! }
 //! ```
 //!
+//! The `radix_key!` macro can generate this for you from the `RadixKey` implementations of the fields, given the order to compare them in (`0` first). This is equivalent to the implementation above:
+//!
+//! ```
+//! use rdst::radix_key;
+//! struct MyStruct {
+//!     key_1: u8,
+//!     key_2: u8,
+//!     key_3: u8,
+//! }
+//!
+//! radix_key!(MyStruct { key_3: 0, key_2: 1, key_1: 2 });
+//! ```
+//!
+//! Fields of tuple structs are listed by index, and generic structs are prefixed with their type parameters. For example, a typed id `struct Id<T>(u64, PhantomData<T>)` sorts by its `u64` with `radix_key!(impl<T> Id<T> { 0: 0 })`.
+//!
+//! For small unsigned integer fields which don't fill their types, `radix_key_packed!` packs them into just the bits they need, given the width of each in order of priority, such as `radix_key_packed!(MyStruct { flag: 3, id: 20, code: 9 })`, so no passes are spent on unused bytes.
+//!
+//! For enums with an integer `repr`, such as `#[repr(u16)]`, wrap the definition in `radix_key_enum!` to sort by the discriminant.
+//!
+//! For types with a `bits()` method returning an integer, such as those generated by `bitflags!`, `radix_key_bits!(MyFlags)` sorts them by that integer.
+//!
+//! #### Computed `RadixKey`
+//!
+//! If your key is expensive to compute, such as a hash of some other data, implement `get_all_levels` to compute every byte at once, and set `BATCHED_LEVELS` to let the sort know it should prefer it over calling `get_level` once per level.
+//!
+//! ```
+//! use rdst::RadixKey;
+//! struct Hashed(u64);
+//!
+//! impl Hashed {
+//!     fn hash(&self) -> u32 {
+//!         (self.0.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 32) as u32
+//!     }
+//! }
+//!
+//! impl RadixKey for Hashed {
+//!     const LEVELS: usize = 4;
+//!     const BATCHED_LEVELS: bool = true;
+//!
+//!     #[inline]
+//!     fn get_level(&self, level: usize) -> u8 {
+//!         (self.hash() >> (level * 8)) as u8
+//!     }
+//!
+//!     #[inline]
+//!     fn get_all_levels(&self, out: &mut [u8]) {
+//!         out.copy_from_slice(&self.hash().to_le_bytes());
+//!     }
+//! }
+//! ```
+//!
+//! `get_level` only receives `&self`, but a key can also be decoded from shared data that it holds a reference to, such as the base of a block of delta-encoded keys, without decoding every key up front. Each item must decode to the same key wherever it is moved to, so keys encoded relative to their neighbours, such as running deltas, need to be re-encoded against a shared base first. If every key shares the same base, sort the deltas directly, as adding the same base to each doesn't change their order.
+//!
+//! ```
+//! use rdst::RadixKey;
+//!
+//! #[derive(Clone, Copy)]
+//! struct Delta<'a> {
+//!     bases: &'a [u64],
+//!     block: u32,
+//!     delta: u32,
+//! }
+//!
+//! impl RadixKey for Delta<'_> {
+//!     const LEVELS: usize = 8;
+//!
+//!     #[inline]
+//!     fn get_level(&self, level: usize) -> u8 {
+//!         (self.bases[self.block as usize] + self.delta as u64).get_level(level)
+//!     }
+//! }
+//! ```
+//!
+//! #### Runtime `DynRadixKey`
+//!
+//! If the number of levels in your key is only known at runtime, implement `DynRadixKey` instead and sort with `radix_sort_dyn_unstable()`. Every item sorted together must have the same number of levels.
+//!
+//! ```
+//! use rdst::{DynRadixKey, DynRadixSort};
+//!
+//! #[derive(Clone, Copy)]
+//! struct VarInt {
+//!     value: u64,
+//!     bytes: usize,
+//! }
+//!
+//! impl DynRadixKey for VarInt {
+//!     fn levels(&self) -> usize {
+//!         self.bytes
+//!     }
+//!
+//!     #[inline]
+//!     fn get_level(&self, level: usize) -> u8 {
+//!         (self.value >> (level * 8)) as u8
+//!     }
+//! }
+//!
+//! let mut my_vec = vec![VarInt { value: 300, bytes: 2 }, VarInt { value: 7, bytes: 2 }];
+//! my_vec.radix_sort_dyn_unstable();
+//! ```
+//!
+//! #### Sorting by key
+//!
+//! If you only need to sort by part of each item and don't want to implement `RadixKey`, `radix_sort_by_pair_key()` sorts `(key, value)` pairs by the key alone, `radix_sort_by_u64_key()` sorts any items by a `u64` extracted from each, and `radix_sort_by_cached_key()` does the same for a key of any `RadixKey` type. The latter two sort a compact buffer of extracted keys rather than the items themselves, which is much faster for large items with small keys. If items with equal keys must keep their original order, use `radix_sort_by_pair_key_stable()`. To put `(Instant, value)` pairs into time order, use `radix_sort_by_instant()`. For columnar data, `radix_argsort_column()` sorts a buffer of row indices by the key each points to in a column, leaving the column itself in place.
+//!
+//! ```
+//! use rdst::radix_sort_by_u64_key;
+//!
+//! struct Order {
+//!     id: u64,
+//!     customer: String,
+//! }
+//!
+//! let mut orders = vec![
+//!     Order { id: 2, customer: "b".to_string() },
+//!     Order { id: 1, customer: "a".to_string() },
+//! ];
+//! radix_sort_by_u64_key(&mut orders, |o| o.id);
+//! ```
+//!
+//! To sort by several keys, each ascending or descending, use `multi_key_sort()`. The keys are combined into a single radix key, from the most significant to the least, in the order they are added.
+//!
+//! ```
+//! use rdst::multi_key_sort;
+//!
+//! struct Event {
+//!     region: u8,
+//!     timestamp: i64,
+//!     id: u32,
+//! }
+//!
+//! let mut events = vec![Event { region: 1, timestamp: 10, id: 2 }];
+//! multi_key_sort(&mut events)
+//!     .asc(|e| e.region)
+//!     .desc(|e| e.timestamp)
+//!     .asc(|e| e.id)
+//!     .run();
+//! ```
+//!
+//! Many small slices, such as thousands of `Vec`s of a few hundred items each, can be sorted together with `radix_sort_batch()`. This sorts the slices in parallel, each on a single thread, rather than parallelizing within each small sort.
+//!
+//! Values held in a shared `&[Cell<T>]`, which can't be borrowed as a `&mut [T]`, can be sorted with `radix_sort_cells()`. This copies the values out, sorts them and writes them back.
+//!
+//! To drop some items as well as sorting the rest, such as tombstones marked with a sentinel key, use `radix_sort_retain(&mut my_vec, |v| keep(v))`, which filters the items before sorting what's left. To count how many times each key occurs, such as term frequencies, use `radix_sort_count_into(&mut my_vec, |key, count| ...)`, which writes each distinct key and its count in ascending order, counting each bucket of the top level as soon as it is sorted. To search the sorted output, use `radix_binary_search(&my_vec, &key)`, which compares items by their levels exactly as they were sorted, rather than by `Ord`, which may not match for keys that are only part of an item.
+//!
+//! Variable-length sequences of any `RadixKey` type, such as `&[u32]`, can be sorted lexicographically by element with `radix_sort_sequences()`, where a sequence sorts before any longer sequence it is a prefix of.
+//!
+//! #### Streaming
+//!
+//! If the sorted data is consumed in order, `radix_sort_streaming()` returns an iterator over sorted chunks of the data. Only the most significant level is sorted up front; each chunk is then fully sorted as the iterator reaches it, so work on the first chunks can start before the rest have been sorted.
+//!
+//! ```
+//! use rdst::RadixSort;
+//! let mut my_vec: Vec<u32> = vec![10, 15, 0, 22, 9];
+//!
+//! for chunk in my_vec.radix_sort_streaming() {
+//!     // Each chunk is sorted, and follows on from the previous one
+//!     println!("{:?}", chunk);
+//! }
+//! ```
+//!
+//! #### Fallible Allocation
+//!
+//! `try_radix_sort_unstable()` returns `RadixSortError::OutOfMemory` rather than aborting if the temporary buffer can't be allocated, leaving the data untouched. A single buffer the size of the data is allocated up-front and shared by every level, and algorithms which would allocate their own buffers are swapped for ones which don't, so this can be slower for large inputs.
+//!
+//! ```
+//! use rdst::RadixSort;
+//! let mut my_vec: Vec<u32> = vec![10, 15, 0, 22, 9];
+//!
+//! if my_vec.try_radix_sort_unstable().is_err() {
+//!     // Not enough memory, so shed the load rather than crashing
+//! }
+//! ```
+//!
+//! #### Mostly Sorted Data
+//!
+//! Data which is mostly sorted already, such as data appended to in order and occasionally backfilled, is detected from a sample of the data. Rather than a full radix sort, only the items which are out of place are pulled out and sorted, then merged back in, which is several times faster. This is off by default, and `with_presorted_threshold()` turns it on for up to the given fraction of items being out of place, such as `0.05` for 5%.
+//!
+//! ```
+//! use rdst::RadixSort;
+//! let mut my_vec: Vec<u32> = (0..100_000).collect();
+//! my_vec[500] = 7;
+//!
+//! my_vec
+//!     .radix_sort_builder()
+//!     .with_presorted_threshold(0.01)
+//!     .sort();
+//! ```
+//!
 //! ## Low-memory Variant
 //!
 //! ```
@@ -104,6 +310,8 @@
 //!
 //! This library also includes a _mostly_ in-place variant of radix sort. This is useful in cases where memory or memory bandwidth are more limited. Generally, this algorithm is slightly slower than the standard algorithm, however in specific circumstances this algorithm may even provide a speed boost. It is worth benchmarking against your use-case if you need the ultimate level of performance.
 //!
+//! The low-memory tuner still uses out-of-place sorts for buckets of up to 50,000 items. Where nothing may be allocated in proportion to the input, `.with_algorithm(Algorithm::Regions)` sorts every bucket in-place, and `rdst::primitives::regions_sort()` exposes the in-place placement of a single level, for building your own pipeline.
+//!
 //! ## Single-threaded Variant
 //!
 //! To make this library use an entirely single-threaded set of algorithms and processes, you can use the following snippet.
@@ -131,6 +339,18 @@
 //!
 //! With the `"multi-threaded"` feature disabled, even the default `my_data.radix_sort_unstable()` will use a single-threaded tuner.
 //!
+//! ## Reproducible Output
+//!
+//! The sort is unstable, so items with equal keys may end up in a different order from run to run when sorting on multiple threads. For golden-file tests and the like, `.with_deterministic(true)` only uses algorithms which split the work the same way on every run, so the output is identical for the same input and number of threads. This can be slightly slower for the largest inputs.
+//!
+//! ## Thread Pool
+//!
+//! The multi-threaded sorts run on rayon's global thread pool, which is started by the first sort. Latency-sensitive code can start it ahead of time, such as at startup, with `rdst::init()`, or `rdst::init_with_threads(n)` to also set the number of threads. Calling either is optional, and later sorts reuse the same pool.
+//!
+//! ```
+//! rdst::init();
+//! ```
+//!
 //! ## Custom Tuners
 //!
 //! Tuners are things which you can implement to control which sorting algorithms are used. There are many radix sorting algorithms implemented as part of this crate, and they all have their pros and cons. If you have a very specific use-case it may be worth your time to tune the sort yourself.
@@ -158,6 +378,48 @@
 //!     .sort();
 //! ```
 //!
+//! The built-in tuners are available in `rdst::tuners`, so a custom tuner can also delegate to `DefaultTuner` for any cases it doesn't need to handle itself.
+//!
+//! To see which algorithms the tuner picks for your data before writing your own, sort with `radix_sort_builder().sort_with_trace()`. This returns the length, level and chosen `Algorithm` of every bucket the tuner was asked about.
+//!
+//! For pipelines which need rdst's single-level scatter but orchestrate the levels themselves, such as radix partitioning for a join, `rdst::primitives` exposes the counting, prefix sum and out-of-place scatter steps directly.
+//!
+//! All of the options above can also be collected into a `SortConfig`, which is useful when the same configuration is used for many sorts, or is decided away from where the sort happens.
+//!
+//! ```
+//! use rdst::{RadixSort, SortConfig};
+//!
+//! let config = SortConfig::new().with_low_mem_tuner();
+//!
+//! let mut my_vec: Vec<usize> = vec![10, 25, 9, 22, 6];
+//! my_vec.radix_sort_unstable_with(&config);
+//! ```
+//!
+//! ## Optional Features
+//!
+//! `RadixKey` is implemented for some types from other crates behind feature flags, named after the crate:
+//!
+//! * `chrono`: `DateTime<Utc>` and `NaiveDateTime`, by `timestamp_nanos_opt()`, so in the same order as `Ord` between 1677 and 2262 (times outside that range sort before or after every time within it)
+//! * `generic-array`: `GenericArray<u8, N>`, sorted the same way as `[u8; N]`
+//! * `rust_decimal`: `Decimal`, by numeric value regardless of scale (so `1.5` and `1.50` sort as equal)
+//! * `time`: `OffsetDateTime` (by instant, including dates before 1970) and `Date`
+//!
+//! The `ahash` and `fxhash` features add `AHashKey` and `FxHashKey`, shorthands for grouping values by those hashes with `HashKey`.
+//!
+//! The `ascii` feature adds `radix_sort_ascii()`, which sorts ASCII strings such as `AsciiString` or `&[AsciiChar]` in byte order. This is the same case-sensitive order as for the equivalent `str`.
+//!
+//! The `memmap2` feature adds `radix_sort_into_mmap()`, which writes the sorted output straight into a memory-mapped file rather than a separate buffer in memory.
+//!
+//! The `num-complex` feature adds two wrappers for sorting `Complex<f64>`: `ByMagnitude` sorts by magnitude (via `norm_sqr()`), so values with the same magnitude but different phases sort as equal, and `ByParts` sorts lexicographically by the real part and then the imaginary part.
+//!
+//! The `smallvec` feature adds `radix_sort_smallvecs()`, which sorts variable-length byte strings stored as `SmallVec<[u8; N]>` in lexicographic order, where a string sorts before any longer string it is a prefix of.
+//!
+//! The `debug-checks` feature makes the out-of-place sorts panic with the level and bucket involved if more items are distributed into a bucket than were counted for it, rather than silently writing out of place. This is intended for testing custom `RadixKey` implementations, and has a small performance cost.
+//!
+//! The `safe-only` feature removes the `unsafe` trick used to allocate temporary buffers without initializing them, and copies the data into each buffer instead. This is for builds which must pass an audit for `unsafe` code, and it comes at a cost: sorts of 1M `u32` or `u64` items are up to 10% slower, and sorts of 10M items around 40% slower. The feature also denies `unsafe` code throughout the crate. The remaining exceptions are each marked with `#[allow(unsafe_code)]`: casts between `#[repr(transparent)]` wrappers and the types they wrap, the discriminant read in `radix_key_enum!`, and the `memmap2` feature.
+//!
+//! Separately, builds with `debug_assertions` (such as `cargo test` and debug builds) check that `get_level` is deterministic: a few items are sampled on each level while counting, and the sort panics if any of them returns a different byte when the scatter starts. A `get_level` which depends on interior mutability or other changing state would otherwise silently mis-sort. Release builds skip the check entirely, at no cost.
+//!
 //! ## License
 //!
 //! Licensed under either of
@@ -171,9 +433,46 @@
 //!
 //! Unless you explicitly state otherwise, any contribution intentionally submitted for inclusion in the work by you, as defined in the Apache-2.0 license, shall be dual licensed as above, without any additional terms or conditions.
 
+#![cfg_attr(feature = "safe-only", deny(unsafe_code))]
+
+mod as_u64;
+mod batch_sort;
+mod be_u32;
+mod binary_search;
+mod by_key;
+mod by_popcount;
+mod cell_sort;
+mod count_into;
+mod dyn_radix_key;
+mod dyn_radix_sort;
+mod external_impls;
+mod fixed_point;
+mod hashed;
+mod histogram_cache;
+mod ipv4_cidr;
+mod len_prefixed;
+mod millis;
+// Memory-mapping a file is inherently unsafe, as the file could be modified by another process
+#[cfg(feature = "memmap2")]
+#[allow(unsafe_code)]
+mod mmap_sort;
+mod multi_key_sort;
+mod norm_float;
+mod numeric_ascii;
 mod radix_key;
 mod radix_key_impl;
+mod radix_key_macro;
+mod radix_range;
 mod radix_sort_builder;
+mod radix_sort_error;
+mod radix_sort_stream;
+mod retain_sort;
+mod semver_key;
+mod sequence_sort;
+mod sort_config;
+mod strict_float;
+#[cfg(feature = "multi-threaded")]
+mod thread_pool;
 
 #[cfg(not(any(test, bench)))]
 mod sorts;
@@ -187,13 +486,62 @@ pub mod utils;
 
 mod radix_sort;
 mod sorter;
-mod tuners;
 
 // Public modules
+pub mod morton;
+pub mod primitives;
 pub mod tuner;
+pub mod tuners;
 
 // Public exports
-pub use radix_key::RadixKey;
+pub use as_u64::AsU64;
+pub use batch_sort::radix_sort_batch;
+pub use be_u32::BeU32;
+pub use binary_search::radix_binary_search;
+pub use by_key::{
+    radix_argsort_column, radix_sort_by_cached_key, radix_sort_by_instant,
+    radix_sort_by_key_buffer, radix_sort_by_pair_key, radix_sort_by_pair_key_stable,
+    radix_sort_by_u64_key, radix_sort_by_u64_key_with_nulls, NullOrder,
+};
+pub use by_popcount::ByPopcount;
+pub use cell_sort::radix_sort_cells;
+pub use count_into::radix_sort_count_into;
+pub use dyn_radix_key::DynRadixKey;
+pub use dyn_radix_sort::DynRadixSort;
+#[cfg(feature = "ascii")]
+pub use external_impls::radix_sort_ascii;
+#[cfg(feature = "smallvec")]
+pub use external_impls::radix_sort_smallvecs;
+#[cfg(feature = "ahash")]
+pub use external_impls::AHashKey;
+#[cfg(feature = "fxhash")]
+pub use external_impls::FxHashKey;
+#[cfg(feature = "num-complex")]
+pub use external_impls::{ByMagnitude, ByParts};
+pub use fixed_point::FixedPoint;
+pub use hashed::{HashKey, Hashed};
+pub use histogram_cache::HistogramCache;
+pub use ipv4_cidr::Ipv4Cidr;
+pub use len_prefixed::LenPrefixed;
+pub use millis::Millis;
+#[cfg(feature = "memmap2")]
+pub use mmap_sort::radix_sort_into_mmap;
+pub use multi_key_sort::{multi_key_sort, MultiKeySort};
+pub use norm_float::NormFloat;
+pub use numeric_ascii::NumericAscii;
+pub use radix_key::{RadixKey, MAX_LEVELS};
+#[doc(hidden)]
+pub use radix_key_macro::{__field_levels, __pack_field, __packed_levels, __return_levels};
+pub use radix_range::RadixRange;
 pub use radix_sort::RadixSort;
 #[doc(inline)]
 pub use radix_sort_builder::RadixSortBuilder;
+pub use radix_sort_error::RadixSortError;
+pub use radix_sort_stream::RadixSortStream;
+pub use retain_sort::radix_sort_retain;
+pub use semver_key::SemverKey;
+pub use sequence_sort::radix_sort_sequences;
+pub use sort_config::SortConfig;
+pub use strict_float::StrictFloat;
+#[cfg(feature = "multi-threaded")]
+pub use thread_pool::{init, init_with_threads};
@@ -0,0 +1,63 @@
+use crate::RadixKey;
+
+/// `Millis` wraps a timestamp stored as `i64` milliseconds since the Unix epoch, as used by many
+/// databases and wire formats. It sorts in the same order as the plain `i64`, so times before the
+/// epoch (negative values) sort before it.
+///
+/// ```
+/// use rdst::{Millis, RadixSort};
+///
+/// let mut times = vec![Millis(1_700_000_000_000), Millis(-86_400_000), Millis(0)];
+/// times.radix_sort_unstable();
+///
+/// assert_eq!(times, [Millis(-86_400_000), Millis(0), Millis(1_700_000_000_000)]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Millis(pub i64);
+
+impl RadixKey for Millis {
+    const LEVELS: usize = 8;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        self.0.get_level(level)
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        self.0.get_all_levels(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Millis, RadixSort};
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_epoch_order() {
+        let mut times: Vec<Millis> = vec![1, -1, 0, i64::MIN, i64::MAX, -1_000, 1_000]
+            .into_iter()
+            .map(Millis)
+            .collect();
+        times.radix_sort_unstable();
+
+        let actual: Vec<i64> = times.into_iter().map(|t| t.0).collect();
+        assert_eq!(actual, [i64::MIN, -1_000, -1, 0, 1, 1_000, i64::MAX]);
+    }
+
+    #[test]
+    pub fn test_around_epoch() {
+        // Within about a day either side of the epoch
+        let mut times: Vec<Millis> = block_rand::<i64>(100_000)
+            .into_iter()
+            .map(|v| Millis(v % 86_400_000))
+            .collect();
+        let mut expected = times.clone();
+        expected.sort_unstable();
+        times.radix_sort_unstable();
+
+        assert_eq!(times, expected);
+    }
+}
@@ -0,0 +1,136 @@
+//! Sorting straight into a memory-mapped file, so that very large outputs don't need to be held in
+//! memory alongside the input.
+
+use crate::radix_key::check_levels;
+use crate::sorter::Sorter;
+use crate::tuners::DefaultTuner;
+use crate::RadixKey;
+use memmap2::MmapMut;
+use std::fs::File;
+use std::io;
+use std::mem::{align_of, size_of, size_of_val};
+
+/// radix_sort_into_mmap writes a sorted copy of `data` into `file`, via a memory map. The first
+/// level is distributed straight from `data` into the mapped file, and the remaining levels are
+/// sorted within the mapping, so the sorted output is never held in memory as a separate `Vec`.
+///
+/// `file` must be opened for both reading and writing. It is resized to exactly fit the output,
+/// and is flushed to disk before returning.
+///
+/// The output is the raw in-memory representation of each `T`, so `T` should be plain data without
+/// any pointers or references.
+///
+/// ```
+/// use rdst::radix_sort_into_mmap;
+/// use std::fs::OpenOptions;
+///
+/// let path = std::env::temp_dir().join("rdst_radix_sort_into_mmap_doctest");
+/// let file = OpenOptions::new()
+///     .read(true)
+///     .write(true)
+///     .create(true)
+///     .truncate(true)
+///     .open(&path)
+///     .unwrap();
+///
+/// let data: Vec<u32> = vec![5, 22, 3, 7, 9];
+/// radix_sort_into_mmap(&data, &file).unwrap();
+///
+/// let sorted: Vec<u32> = std::fs::read(&path)
+///     .unwrap()
+///     .chunks_exact(4)
+///     .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+///     .collect();
+///
+/// assert_eq!(sorted, [3, 5, 7, 9, 22]);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn radix_sort_into_mmap<T>(data: &[T], file: &File) -> io::Result<()>
+where
+    T: RadixKey + Copy + Send + Sync,
+{
+    assert_ne!(T::LEVELS, 0, "RadixKey must have at least 1 level");
+    check_levels::<T>();
+
+    file.set_len(size_of_val(data) as u64)?;
+
+    if data.is_empty() || size_of::<T>() == 0 {
+        return Ok(());
+    }
+
+    let mut mmap = unsafe { MmapMut::map_mut(file)? };
+
+    // Memory maps are page-aligned, which is more than enough for any T
+    assert_eq!(mmap.as_ptr() as usize % align_of::<T>(), 0);
+
+    let dst = unsafe {
+        // Safety: The mapping is exactly data.len() * size_of::<T>() bytes, and every element is
+        // overwritten by the sort before it is read.
+        std::slice::from_raw_parts_mut(mmap.as_mut_ptr() as *mut T, data.len())
+    };
+
+    Sorter::new(cfg!(feature = "multi-threaded"), &DefaultTuner {}).out_of_place_sort_into_adapter(
+        data,
+        dst,
+        T::LEVELS - 1,
+    );
+
+    mmap.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::radix_sort_into_mmap;
+    use crate::utils::test_utils::{gen_inputs, NumericTest};
+    use std::fs::OpenOptions;
+    use std::mem::size_of;
+
+    fn test_radix_sort_into_mmap<T>(shift: T)
+    where
+        T: NumericTest<T>,
+    {
+        let path = std::env::temp_dir().join(format!(
+            "rdst_test_radix_sort_into_mmap_{}_{}",
+            size_of::<T>(),
+            std::process::id()
+        ));
+
+        for n in [0, 1, 100, 100_000, 2_000_000] {
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&path)
+                .unwrap();
+
+            let inputs = gen_inputs(n, shift);
+            let mut expected = inputs.clone();
+            expected.sort_unstable();
+
+            radix_sort_into_mmap(&inputs, &file).unwrap();
+
+            let bytes = std::fs::read(&path).unwrap();
+            assert_eq!(bytes.len(), n * size_of::<T>());
+
+            let actual: Vec<T> = bytes
+                .chunks_exact(size_of::<T>())
+                .map(|b| unsafe { std::ptr::read_unaligned(b.as_ptr() as *const T) })
+                .collect();
+
+            assert_eq!(actual, expected);
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    pub fn test_u32() {
+        test_radix_sort_into_mmap(16u32);
+    }
+
+    #[test]
+    pub fn test_u64() {
+        test_radix_sort_into_mmap(32u64);
+    }
+}
@@ -0,0 +1,160 @@
+//! Morton (Z-order) codes, for sorting 2D points so that points which are close together in space
+//! end up close together in the sorted output.
+//!
+//! A Morton code interleaves the bits of the two coordinates, with `x` in the even bits and `y` in
+//! the odd bits. Sorting by the code visits the points along a Z-shaped curve which recursively
+//! fills each quadrant before moving on to the next, so every aligned power-of-two square of the
+//! plane forms a single contiguous run of the sorted output.
+//!
+//! ```
+//! use rdst::morton::MortonKey;
+//! use rdst::RadixSort;
+//!
+//! let mut points = vec![
+//!     MortonKey::new(3, 3),
+//!     MortonKey::new(0, 1),
+//!     MortonKey::new(2, 0),
+//!     MortonKey::new(1, 0),
+//! ];
+//! points.radix_sort_unstable();
+//!
+//! let coords: Vec<(u32, u32)> = points.iter().map(|p| (p.x(), p.y())).collect();
+//! assert_eq!(coords, [(1, 0), (0, 1), (2, 0), (3, 3)]);
+//! ```
+
+use crate::RadixKey;
+
+/// `encode` interleaves `x` and `y` into a single Morton code, with `x` in the even bits.
+#[inline]
+pub const fn encode(x: u32, y: u32) -> u64 {
+    spread(x) | (spread(y) << 1)
+}
+
+/// `decode` splits a Morton code back into its `(x, y)` coordinates.
+#[inline]
+pub const fn decode(code: u64) -> (u32, u32) {
+    (compact(code), compact(code >> 1))
+}
+
+// Spreads the 32 bits of `v` out into the even bits of a u64
+#[inline]
+const fn spread(v: u32) -> u64 {
+    let mut v = v as u64;
+    v = (v | (v << 16)) & 0x0000_FFFF_0000_FFFF;
+    v = (v | (v << 8)) & 0x00FF_00FF_00FF_00FF;
+    v = (v | (v << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+    v = (v | (v << 1)) & 0x5555_5555_5555_5555;
+
+    v
+}
+
+// The inverse of `spread`, gathering the even bits of `v` back into a u32
+#[inline]
+const fn compact(v: u64) -> u32 {
+    let mut v = v & 0x5555_5555_5555_5555;
+    v = (v | (v >> 1)) & 0x3333_3333_3333_3333;
+    v = (v | (v >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    v = (v | (v >> 4)) & 0x00FF_00FF_00FF_00FF;
+    v = (v | (v >> 8)) & 0x0000_FFFF_0000_FFFF;
+    v = (v | (v >> 16)) & 0x0000_0000_FFFF_FFFF;
+
+    v as u32
+}
+
+/// `MortonKey` is a 2D point stored as its Morton code, which sorts in Z-order. The coordinates
+/// can be read back with `x()` and `y()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct MortonKey(u64);
+
+impl MortonKey {
+    /// `new()` creates the key for the point `(x, y)`.
+    #[inline]
+    pub const fn new(x: u32, y: u32) -> Self {
+        Self(encode(x, y))
+    }
+
+    /// `from_code()` creates a key from an existing Morton code.
+    #[inline]
+    pub const fn from_code(code: u64) -> Self {
+        Self(code)
+    }
+
+    /// `code()` returns the Morton code of the point.
+    #[inline]
+    pub const fn code(&self) -> u64 {
+        self.0
+    }
+
+    /// `x()` returns the x coordinate of the point.
+    #[inline]
+    pub const fn x(&self) -> u32 {
+        compact(self.0)
+    }
+
+    /// `y()` returns the y coordinate of the point.
+    #[inline]
+    pub const fn y(&self) -> u32 {
+        compact(self.0 >> 1)
+    }
+}
+
+impl RadixKey for MortonKey {
+    const LEVELS: usize = 8;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        self.0.get_level(level)
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        self.0.get_all_levels(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::morton::{decode, encode, MortonKey};
+    use crate::RadixSort;
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_encode() {
+        assert_eq!(encode(0, 0), 0);
+        assert_eq!(encode(1, 0), 0b01);
+        assert_eq!(encode(0, 1), 0b10);
+        assert_eq!(encode(0b11, 0b10), 0b1101);
+        assert_eq!(encode(u32::MAX, 0), 0x5555_5555_5555_5555);
+        assert_eq!(encode(u32::MAX, u32::MAX), u64::MAX);
+
+        for v in block_rand::<u64>(10_000) {
+            let (x, y) = decode(v);
+            assert_eq!(encode(x, y), v);
+            assert_eq!(decode(encode(x, y)), (x, y));
+        }
+    }
+
+    #[test]
+    pub fn test_spatial_locality() {
+        let size = 256u32;
+        let block = 16u32;
+
+        let shuffle = block_rand::<u32>((size * size) as usize);
+        let mut points: Vec<MortonKey> = (0..size * size)
+            .map(|i| MortonKey::new(i % size, i / size))
+            .collect();
+        points.sort_by_key(|p| shuffle[(p.y() * size + p.x()) as usize]);
+
+        points.radix_sort_unstable();
+
+        // Every aligned block of the grid must occupy a single contiguous run of the output
+        for run in points.chunks(block as usize * block as usize) {
+            let (bx, by) = (run[0].x() / block, run[0].y() / block);
+            assert!(run
+                .iter()
+                .all(|p| p.x() / block == bx && p.y() / block == by));
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! Sorting by several keys at once, each in its own direction, like SQL's
+//! `ORDER BY a ASC, b DESC`.
+
+use crate::by_key::apply_permutation;
+use crate::radix_sort_builder::RadixSortBuilder;
+use crate::{RadixKey, MAX_LEVELS};
+
+/// `multi_key_sort` starts a sort of `data` by several keys, each extracted from the items and
+/// sorted in its own direction. Add keys from the most significant to the least with `asc()` and
+/// `desc()`, then sort with `run()`.
+///
+/// ```
+/// use rdst::multi_key_sort;
+///
+/// struct Event {
+///     region: u8,
+///     timestamp: i64,
+///     id: u32,
+/// }
+///
+/// let mut events = vec![
+///     Event { region: 2, timestamp: 10, id: 1 },
+///     Event { region: 1, timestamp: 10, id: 2 },
+///     Event { region: 1, timestamp: 30, id: 3 },
+///     Event { region: 1, timestamp: 10, id: 0 },
+/// ];
+///
+/// multi_key_sort(&mut events)
+///     .asc(|e| e.region)
+///     .desc(|e| e.timestamp)
+///     .asc(|e| e.id)
+///     .run();
+///
+/// let ids: Vec<u32> = events.iter().map(|e| e.id).collect();
+/// assert_eq!(ids, [3, 0, 2, 1]);
+/// ```
+pub fn multi_key_sort<T>(data: &mut [T]) -> MultiKeySort<'_, T> {
+    MultiKeySort {
+        data,
+        keys: Vec::new(),
+    }
+}
+
+/// `MultiKeySort` is the builder returned by `multi_key_sort()`.
+///
+/// Every key is extracted once per item into a single buffer of combined keys, with the bytes of
+/// descending keys inverted so that they sort in reverse. That buffer is radix sorted in place of
+/// the items, and the resulting permutation applied to the data, so each item is moved exactly
+/// once as with `radix_sort_by_cached_key()`. The combined key has as many levels as all of the
+/// keys together, so only add the keys which are needed.
+///
+/// The sort is unstable, so items which are equal on every key may be reordered.
+pub struct MultiKeySort<'a, T> {
+    data: &'a mut [T],
+    keys: Vec<SortKey<'a, T>>,
+}
+
+// Writes the levels of a single key of an item into its slice of the combined key
+type WriteKey<'a, T> = Box<dyn Fn(&T, &mut [u8]) + 'a>;
+
+struct SortKey<'a, T> {
+    levels: usize,
+    write: WriteKey<'a, T>,
+}
+
+impl<'a, T> MultiKeySort<'a, T> {
+    /// `asc()` adds a key to sort by in ascending order, after any keys already added.
+    pub fn asc<K, F>(mut self, f: F) -> Self
+    where
+        K: RadixKey,
+        F: Fn(&T) -> K + 'a,
+    {
+        self.keys.push(SortKey {
+            levels: K::LEVELS,
+            write: Box::new(move |v, out| f(v).get_all_levels(out)),
+        });
+
+        self
+    }
+
+    /// `desc()` adds a key to sort by in descending order, after any keys already added.
+    pub fn desc<K, F>(mut self, f: F) -> Self
+    where
+        K: RadixKey,
+        F: Fn(&T) -> K + 'a,
+    {
+        self.keys.push(SortKey {
+            levels: K::LEVELS,
+            write: Box::new(move |v, out| {
+                f(v).get_all_levels(out);
+                out.iter_mut().for_each(|b| *b = !*b);
+            }),
+        });
+
+        self
+    }
+
+    /// `run()` sorts the data by all of the keys added.
+    pub fn run(self) {
+        let width: usize = self.keys.iter().map(|k| k.levels).sum();
+
+        if self.data.len() < 2 || width == 0 {
+            return;
+        }
+
+        // The first key added is the most significant, so it takes the highest levels
+        let keys = self.keys;
+        sort_by_rows(self.data, width, |item, row| {
+            let mut end = width;
+
+            for key in keys.iter() {
+                (key.write)(item, &mut row[end - key.levels..end]);
+                end -= key.levels;
+            }
+        });
+    }
+}
+
+// Sorts `data` by a combined key of `width` levels for each item, which `fill` writes into a
+// zeroed row with level 0 first. The rows are sorted in place of the items, and the resulting
+// permutation then applied to `data`.
+pub(crate) fn sort_by_rows<T, F>(data: &mut [T], width: usize, fill: F)
+where
+    F: Fn(&T, &mut [u8]),
+{
+    let mut buffer = vec![0u8; data.len() * width];
+    for (item, row) in data.iter().zip(buffer.chunks_exact_mut(width)) {
+        fill(item, row);
+    }
+
+    let mut rows: Vec<Row> = buffer
+        .chunks_exact(width)
+        .enumerate()
+        .map(|(index, key)| Row { key, index })
+        .collect();
+    RadixSortBuilder::new_with_levels(&mut rows, width).sort();
+
+    let mut permutation: Vec<usize> = rows.into_iter().map(|r| r.index).collect();
+    apply_permutation(data, &mut permutation);
+}
+
+// Sorts `data` by the byte string `bytes` returns for each item, in lexicographic order where a
+// string sorts before any longer string it is a prefix of. Each row is the string padded with
+// zeros up to the length of the longest, followed by its length to order strings which only
+// differ by trailing zeros.
+#[cfg(any(feature = "ascii", feature = "smallvec"))]
+pub(crate) fn sort_by_byte_strings<T, F>(data: &mut [T], bytes: F)
+where
+    F: Fn(&T) -> &[u8],
+{
+    let max_len = data.iter().map(|v| bytes(v).len()).max().unwrap_or(0);
+
+    if data.len() < 2 || max_len == 0 {
+        return;
+    }
+
+    let len_bytes = (usize::BITS - max_len.leading_zeros()).div_ceil(8) as usize;
+    let width = max_len + len_bytes;
+
+    // The first byte of the string is the most significant level, and the length the least
+    sort_by_rows(data, width, |v, row| {
+        let v = bytes(v);
+        row[..len_bytes].copy_from_slice(&v.len().to_le_bytes()[..len_bytes]);
+
+        for (b, out) in v.iter().zip(row.iter_mut().rev()) {
+            *out = *b;
+        }
+    });
+}
+
+/// `Row` is the combined key of a single item, with the index of the item it came from. As with
+/// `DynKey`, `LEVELS` is `MAX_LEVELS`, with every level past the end of the key reading as zero,
+/// and the `Sorter` is given the real number of levels at runtime.
+#[derive(Clone, Copy)]
+struct Row<'k> {
+    key: &'k [u8],
+    index: usize,
+}
+
+impl<'k> RadixKey for Row<'k> {
+    const LEVELS: usize = MAX_LEVELS;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        self.key.get(level).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::multi_key_sort;
+    use block_pseudorand::block_rand;
+    use std::cmp::Reverse;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Record {
+        region: u8,
+        timestamp: i64,
+        score: f32,
+        id: u32,
+        name: String,
+    }
+
+    fn records(n: usize) -> Vec<Record> {
+        block_rand::<u64>(n)
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| Record {
+                region: (v % 5) as u8,
+                timestamp: ((v >> 8) % 20) as i64 - 10,
+                score: ((v >> 16) % 7) as f32 - 3.5,
+                id: i as u32,
+                name: format!("record-{}", i),
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_multi_key_sort() {
+        for n in [0, 1, 2, 100, 100_000] {
+            let mut inputs = records(n);
+            let mut expected = inputs.clone();
+            expected.sort_by_key(|r| (r.region, Reverse(r.timestamp), r.id));
+
+            multi_key_sort(&mut inputs)
+                .asc(|r| r.region)
+                .desc(|r| r.timestamp)
+                .asc(|r| r.id)
+                .run();
+
+            assert_eq!(inputs, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_multi_key_sort_ties() {
+        let mut inputs = records(100_000);
+        let mut expected = inputs.clone();
+        expected.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap()
+                .then(b.region.cmp(&a.region))
+        });
+
+        // Items equal on every key may be in any order
+        multi_key_sort(&mut inputs)
+            .desc(|r| r.score)
+            .desc(|r| r.region)
+            .run();
+
+        let keys =
+            |v: &[Record]| -> Vec<(f32, u8)> { v.iter().map(|r| (r.score, r.region)).collect() };
+        assert_eq!(keys(&inputs), keys(&expected));
+
+        let mut ids: Vec<u32> = inputs.iter().map(|r| r.id).collect();
+        ids.sort_unstable();
+        assert!(ids.iter().enumerate().all(|(i, id)| i as u32 == *id));
+    }
+
+    #[test]
+    pub fn test_multi_key_sort_no_keys() {
+        let mut inputs = records(100);
+        let expected = inputs.clone();
+
+        multi_key_sort(&mut inputs).run();
+
+        assert_eq!(inputs, expected);
+    }
+}
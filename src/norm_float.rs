@@ -0,0 +1,127 @@
+use crate::RadixKey;
+
+/// `NormFloat` wraps an `f32` or `f64` to sort it in the same order as the plain float, except
+/// that `-0.0` is sorted as if it were `+0.0`, so the two zeros are treated as the same key and
+/// end up mixed together in the sorted output. This matches `==` on floats, where `-0.0 == 0.0`.
+///
+/// The plain float impls, like `total_cmp()`, sort `-0.0` before `+0.0`, so that every bit pattern
+/// has its own place in the order. Use `NormFloat` where the sign of zero is an artifact of the
+/// calculation, and equal values should be grouped together, such as before counting or
+/// deduplicating them. NaNs are unaffected, and are sorted to either end as with the plain float.
+///
+/// ```
+/// use rdst::{NormFloat, RadixSort};
+///
+/// let mut values = vec![NormFloat(0.0f64), NormFloat(-1.0), NormFloat(-0.0), NormFloat(1.0)];
+/// values.radix_sort_unstable();
+///
+/// assert_eq!(values[0].0, -1.0);
+/// assert!(values[1].0 == 0.0 && values[2].0 == 0.0);
+/// assert_eq!(values[3].0, 1.0);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct NormFloat<T>(pub T);
+
+impl RadixKey for NormFloat<f32> {
+    const LEVELS: usize = 4;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        normalize_f32(self.0).get_level(level)
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        normalize_f32(self.0).get_all_levels(out)
+    }
+}
+
+impl RadixKey for NormFloat<f64> {
+    const LEVELS: usize = 8;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        normalize_f64(self.0).get_level(level)
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        normalize_f64(self.0).get_all_levels(out)
+    }
+}
+
+// `-0.0 == 0.0`, so both zeros become `+0.0`, and everything else, including NaN, is unchanged
+#[inline]
+fn normalize_f32(v: f32) -> f32 {
+    if v == 0.0 {
+        0.0
+    } else {
+        v
+    }
+}
+
+#[inline]
+fn normalize_f64(v: f64) -> f64 {
+    if v == 0.0 {
+        0.0
+    } else {
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NormFloat, RadixKey, RadixSort};
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_zeros_equal() {
+        for level in 0..8 {
+            assert_eq!(
+                NormFloat(-0.0f64).get_level(level),
+                NormFloat(0.0f64).get_level(level)
+            );
+        }
+
+        for level in 0..4 {
+            assert_eq!(
+                NormFloat(-0.0f32).get_level(level),
+                NormFloat(0.0f32).get_level(level)
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_zeros_adjacent() {
+        // Plenty of both zeros among values either side of them
+        let mut values: Vec<NormFloat<f64>> = block_rand::<i32>(100_000)
+            .into_iter()
+            .map(|v| match v % 4 {
+                0 => NormFloat(0.0),
+                1 | -1 => NormFloat(-0.0),
+                _ => NormFloat(v as f64),
+            })
+            .collect();
+        let zeros = values.iter().filter(|v| v.0 == 0.0).count();
+        values.radix_sort_unstable();
+
+        let first = values.iter().position(|v| v.0 == 0.0).unwrap();
+        assert!(values[first..first + zeros].iter().all(|v| v.0 == 0.0));
+        assert!(values.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert!(values.iter().any(|v| v.0.is_sign_negative() && v.0 == 0.0));
+
+        let mut values: Vec<NormFloat<f32>> = vec![
+            NormFloat(0.0),
+            NormFloat(1.0),
+            NormFloat(-0.0),
+            NormFloat(-1.0),
+            NormFloat(0.0),
+        ];
+        values.radix_sort_unstable();
+
+        assert_eq!(values[0].0, -1.0);
+        assert!(values[1..4].iter().all(|v| v.0 == 0.0));
+        assert_eq!(values[4].0, 1.0);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::RadixKey;
+
+/// `NumericAscii` wraps a fixed-width, zero-padded ASCII number, such as the product ID
+/// `b"0000123"`. As every value has the same width, comparing the digits byte by byte from the
+/// left gives the same order as comparing the numbers they spell, so no parsing is needed.
+///
+/// The bytes are sorted with the first as the most significant, as in the string. In debug
+/// builds, sorting a value with any byte that isn't an ASCII digit panics, to catch malformed
+/// input such as an unpadded or signed number, which would otherwise sort out of numeric order.
+/// Release builds skip the check.
+///
+/// ```
+/// use rdst::{NumericAscii, RadixSort};
+///
+/// let mut skus = vec![NumericAscii(*b"0004500"), NumericAscii(*b"0000123"), NumericAscii(*b"0000900")];
+/// skus.radix_sort_unstable();
+///
+/// assert_eq!(skus, [NumericAscii(*b"0000123"), NumericAscii(*b"0000900"), NumericAscii(*b"0004500")]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct NumericAscii<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> RadixKey for NumericAscii<N> {
+    const LEVELS: usize = N;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        debug_assert!(
+            self.0.iter().all(u8::is_ascii_digit),
+            "NumericAscii: non-digit byte in {:?}",
+            self.0
+        );
+
+        self.0[N - 1 - level]
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        debug_assert!(
+            self.0.iter().all(u8::is_ascii_digit),
+            "NumericAscii: non-digit byte in {:?}",
+            self.0
+        );
+
+        for (o, b) in out.iter_mut().zip(self.0.iter().rev()) {
+            *o = *b;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NumericAscii, RadixSort};
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_numeric_order() {
+        let numbers: Vec<u32> = block_rand::<u32>(100_000)
+            .into_iter()
+            .map(|v| v % 10_000_000)
+            .collect();
+
+        let mut skus: Vec<NumericAscii<7>> = numbers
+            .iter()
+            .map(|v| {
+                let mut sku = [0u8; 7];
+                sku.copy_from_slice(format!("{:07}", v).as_bytes());
+                NumericAscii(sku)
+            })
+            .collect();
+        skus.radix_sort_unstable();
+
+        let mut expected = numbers;
+        expected.sort_unstable();
+
+        let actual: Vec<u32> = skus
+            .iter()
+            .map(|s| std::str::from_utf8(&s.0).unwrap().parse().unwrap())
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "NumericAscii: non-digit byte")]
+    pub fn test_non_digit_panics() {
+        let mut skus = vec![
+            NumericAscii(*b"0000123"),
+            NumericAscii(*b"  12345"),
+            NumericAscii(*b"0000001"),
+        ];
+        skus.radix_sort_unstable();
+    }
+}
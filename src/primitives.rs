@@ -0,0 +1,507 @@
+//! Low-level building blocks for composing your own radix pipelines, such as radix partitioning
+//! for a join, from the same single-level scatter used by the sorts in this crate.
+//!
+//! A single level of a radix sort is three steps:
+//!
+//!  1. `get_counts()` counts how many items have each byte value at the level
+//!  2. `get_prefix_sums()` turns the counts into the offset each byte's bucket starts at
+//!  3. `out_of_place_sort()` scatters each item from `src` into its bucket in `dst`
+//!
+//! Step 3 computes the prefix sums itself, so step 2 is only needed to find where each bucket
+//! starts and (with `get_end_offsets()`) ends after the scatter.
+//!
+//! Where there isn't memory for a second buffer, `in_place_sort()` can replace step 3. It places
+//! every item within the same slice by following cycles of swaps driven by the prefix sums,
+//! using no extra memory, but it isn't stable. For large buckets, `regions_sort()` does the same
+//! across every thread in the pool.
+//!
+//! Level `0` is the least significant byte of a key. Sorting each level in turn from `0` up, as
+//! below, is an LSB radix sort. Scattering only the most significant level and handling each
+//! bucket separately is an MSB radix partition, which `radix_partition_top()` does in-place,
+//! returning where each bucket starts and ends.
+//!
+//! ```
+//! use rdst::primitives::{get_counts, out_of_place_sort, out_of_place_sort_with_counts};
+//!
+//! let mut data: Vec<u16> = vec![0x0302, 0x0101, 0x0203, 0x0102];
+//! let mut tmp = vec![0u16; data.len()];
+//!
+//! let (counts, _) = get_counts(&data, 0);
+//! let next_counts = out_of_place_sort_with_counts(&data, &mut tmp, &counts, 0);
+//! out_of_place_sort(&tmp, &mut data, &next_counts, 1);
+//!
+//! assert_eq!(data, [0x0101, 0x0102, 0x0203, 0x0302]);
+//! ```
+
+use crate::sorts::out_of_place_sort;
+#[cfg(feature = "multi-threaded")]
+use crate::sorts::regions_sort;
+use crate::sorts::ska_sort::ska_sort;
+#[cfg(feature = "multi-threaded")]
+use crate::tuner::Tuner;
+use crate::utils;
+use crate::RadixKey;
+
+/// `get_counts` counts the number of items in `bucket` with each byte value at `level`. It also
+/// returns whether `bucket` is already in order by that level, in which case a scatter would not
+/// move anything.
+#[inline]
+pub fn get_counts<T>(bucket: &[T], level: usize) -> ([usize; 256], bool)
+where
+    T: RadixKey,
+{
+    utils::get_counts(bucket, level)
+}
+
+/// `get_prefix_sums` returns the offset at which each byte value's bucket starts, which is the
+/// sum of the counts of all smaller byte values.
+#[inline]
+pub fn get_prefix_sums(counts: &[usize; 256]) -> [usize; 256] {
+    utils::get_prefix_sums(counts)
+}
+
+/// `get_end_offsets` returns the offset just past the end of each byte value's bucket, given the
+/// `prefix_sums` from `get_prefix_sums(counts)`. Bucket `b` is `prefix_sums[b]..end_offsets[b]`.
+#[inline]
+pub fn get_end_offsets(counts: &[usize; 256], prefix_sums: &[usize; 256]) -> [usize; 256] {
+    utils::get_end_offsets(counts, prefix_sums)
+}
+
+/// `out_of_place_sort` scatters every item in `src` into `dst`, ordered by the byte at `level`.
+/// Items with the same byte keep their relative order, so this is stable.
+///
+/// The contract is:
+///
+///  * `dst.len()` must equal `src.len()`. Every slot of `dst` is overwritten, so its contents
+///    don't matter
+///  * `counts` must be exactly the counts of `src` at `level`, as returned by `get_counts(src,
+///    level)`. Counts which merely sum to the right total can't be detected, and leave `dst`
+///    partly unsorted
+///  * `level` must be less than the number of levels of `T`
+///
+/// # Panics
+///
+/// If `dst` is not the same length as `src`, or if `counts` don't sum to `src.len()`.
+#[inline]
+pub fn out_of_place_sort<T>(src: &[T], dst: &mut [T], counts: &[usize; 256], level: usize)
+where
+    T: RadixKey + Sized + Send + Copy + Sync,
+{
+    check_contract(src, dst, counts);
+    out_of_place_sort::out_of_place_sort(src, dst, counts, level);
+}
+
+/// `out_of_place_sort_with_counts` behaves like `out_of_place_sort`, but also counts the bytes at
+/// `level + 1` as it scatters, and returns those counts. These are the counts of `dst` needed to
+/// scatter the next level of an LSB sort, saving a separate counting pass.
+///
+/// The contract is the same as for `out_of_place_sort`, except that `level + 1` must also be less
+/// than the number of levels of `T`.
+///
+/// # Panics
+///
+/// If `dst` is not the same length as `src`, or if `counts` don't sum to `src.len()`.
+#[inline]
+pub fn out_of_place_sort_with_counts<T>(
+    src: &[T],
+    dst: &mut [T],
+    counts: &[usize; 256],
+    level: usize,
+) -> [usize; 256]
+where
+    T: RadixKey + Sized + Send + Copy + Sync,
+{
+    check_contract(src, dst, counts);
+    out_of_place_sort::out_of_place_sort_with_counts(src, dst, counts, level)
+}
+
+/// `in_place_sort` places every item in `bucket` into order by the byte at `level`, within
+/// `bucket` itself. Each item is swapped directly into the next free slot of its byte's bucket,
+/// following the prefix sums, until every bucket is full. This is the placement used by
+/// `Algorithm::Ska`.
+///
+/// Unlike `out_of_place_sort`, this needs no second buffer, but it is unstable: items with the
+/// same byte don't keep their relative order. So in an LSB pipeline, it can only replace the
+/// scatter of the first level sorted, as each later level relies on the order left by the levels
+/// before it. It can also be used for each level of an MSB partition, which doesn't rely on
+/// stability. Whether it is faster than `out_of_place_sort` depends on the data and the machine,
+/// as the swaps jump around the whole bucket, but there is no second buffer to allocate or write
+/// to. The `single_level_placement` bench in `benches/basic_sort.rs` compares the two.
+///
+/// The contract is:
+///
+///  * `counts` must be exactly the counts of `bucket` at `level`, as returned by
+///    `get_counts(bucket, level)`. Counts which merely sum to the right total can't be detected,
+///    and leave `bucket` partly unsorted
+///  * `level` must be less than the number of levels of `T`
+///
+/// # Panics
+///
+/// If `counts` don't sum to `bucket.len()`.
+#[inline]
+pub fn in_place_sort<T>(bucket: &mut [T], counts: &[usize; 256], level: usize)
+where
+    T: RadixKey + Sized + Send + Copy + Sync,
+{
+    check_counts(bucket.len(), counts);
+
+    if bucket.len() < 2 {
+        return;
+    }
+
+    let mut prefix_sums = utils::get_prefix_sums(counts);
+    let end_offsets = utils::get_end_offsets(counts, &prefix_sums);
+
+    ska_sort(bucket, &mut prefix_sums, &end_offsets, level);
+}
+
+/// `radix_partition_top` partitions `bucket` in-place by the byte at `level`, as a single MSB
+/// distribution pass, without sorting within each partition. It returns the 257 boundary offsets
+/// of the partitions: partition `b` holds every item whose byte at `level` is `b`, and is
+/// `bucket[boundaries[b]..boundaries[b + 1]]`. The last offset is always `bucket.len()`.
+///
+/// The items are placed as by `in_place_sort()`, so this needs no second buffer, but the order
+/// of items within each partition is not kept. Each partition can then be processed
+/// independently, such as one per thread for a radix-partitioned join. For the most significant
+/// byte, pass `T::LEVELS - 1` as `level`, which must be less than the number of levels of `T`.
+///
+/// ```
+/// use rdst::primitives::radix_partition_top;
+///
+/// let mut data: Vec<u16> = vec![0x0302, 0x0101, 0x0203, 0x0102];
+/// let boundaries = radix_partition_top(&mut data, 1);
+///
+/// assert_eq!(&boundaries[1..5], [0, 2, 3, 4]);
+/// assert!(data[boundaries[1]..boundaries[2]].iter().all(|v| v >> 8 == 1));
+/// ```
+#[inline]
+pub fn radix_partition_top<T>(bucket: &mut [T], level: usize) -> [usize; 257]
+where
+    T: RadixKey + Sized + Send + Copy + Sync,
+{
+    let (counts, already_sorted) = utils::get_counts(bucket, level);
+
+    if !already_sorted {
+        in_place_sort(bucket, &counts, level);
+    }
+
+    let mut boundaries = [0; 257];
+    let mut offset = 0;
+    for (b, count) in counts.iter().enumerate() {
+        boundaries[b] = offset;
+        offset += count;
+    }
+    boundaries[256] = offset;
+
+    boundaries
+}
+
+/// `regions_sort` places every item in `bucket` into order by the byte at `level`, within `bucket`
+/// itself, using every thread in the current pool. This is the placement used by
+/// `Algorithm::Regions`, and returns the counts of `bucket` at `level`, so the caller can find
+/// each byte's bucket to sort the levels below.
+///
+/// `bucket` is split into tiles as given by `tuner.tile_params()`, and counted as given by
+/// `tuner.counting_params()`. Each tile is placed in-place in parallel, as by `in_place_sort()`.
+/// The tiles are then treated as a graph of regions which need to move to another byte's bucket,
+/// and pairs of regions are swapped in parallel until every item is in its byte's bucket.
+///
+/// Memory use doesn't depend on the length of `bucket`, only on the number of tiles, which is at
+/// most `threads * tiles_per_thread`: a set of counts per tile, plus the list of regions left to
+/// swap, which is at most 256 per tile. That is a few tens of KB per tile, whether `bucket` holds
+/// a million items or 10 GB of them. Like `in_place_sort`, this is unstable.
+///
+/// Only the one level is placed. To fully sort in-place, call this (or `in_place_sort()` for
+/// smaller buckets) on each bucket for the next level down, or sort with
+/// `radix_sort_builder().with_algorithm(Algorithm::Regions)`, which does so for every bucket
+/// larger than 128 items, and sorts smaller ones with an in-place comparison sort. Tuners such as
+/// `LowMemoryTuner` instead pick an out-of-place sort for small buckets, needing a temporary
+/// buffer as large as the bucket. The `regions_placement` bench in `benches/basic_sort.rs`
+/// compares this against `out_of_place_sort`.
+///
+/// `level` must be less than the number of levels of `T`.
+///
+/// ```
+/// use rdst::primitives::regions_sort;
+/// use rdst::tuners::LowMemoryTuner;
+///
+/// let mut data: Vec<u16> = vec![0x0302, 0x0101, 0x0203, 0x0102];
+/// let counts = regions_sort(&mut data, 1, &LowMemoryTuner);
+///
+/// assert!(data.windows(2).all(|w| w[0] >> 8 <= w[1] >> 8));
+/// assert_eq!(counts[1], 2);
+/// ```
+#[cfg(feature = "multi-threaded")]
+#[inline]
+pub fn regions_sort<T>(
+    bucket: &mut [T],
+    level: usize,
+    tuner: &(dyn Tuner + Send + Sync),
+) -> [usize; 256]
+where
+    T: RadixKey + Sized + Send + Copy + Sync,
+{
+    if bucket.is_empty() {
+        return [0; 256];
+    }
+
+    let params = tuner.tile_params();
+    let threads = rayon::current_num_threads();
+    let tile_size = std::cmp::max(
+        params.min_tile_size.max(1),
+        utils::cdiv(bucket.len(), threads * params.tiles_per_thread.max(1)),
+    );
+
+    let (tile_counts, already_sorted) =
+        utils::get_tile_counts(bucket, tile_size, level, &tuner.counting_params());
+    let counts = utils::aggregate_tile_counts(&tile_counts);
+
+    if !already_sorted && bucket.len() >= 2 {
+        regions_sort::regions_sort(bucket, &counts, &tile_counts, tile_size, level);
+    }
+
+    counts
+}
+
+#[inline]
+fn check_contract<T>(src: &[T], dst: &[T], counts: &[usize; 256]) {
+    assert_eq!(src.len(), dst.len(), "dst must be the same length as src");
+    check_counts(src.len(), counts);
+}
+
+#[inline]
+fn check_counts(len: usize, counts: &[usize; 256]) {
+    assert_eq!(
+        counts.iter().sum::<usize>(),
+        len,
+        "counts must sum to the number of items"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "multi-threaded")]
+    use crate::primitives::regions_sort;
+    use crate::primitives::{
+        get_counts, get_end_offsets, get_prefix_sums, in_place_sort, out_of_place_sort,
+        out_of_place_sort_with_counts, radix_partition_top,
+    };
+    #[cfg(feature = "multi-threaded")]
+    use crate::tuner::{Algorithm, TileParams, Tuner, TuningParams};
+    #[cfg(feature = "multi-threaded")]
+    use crate::tuners::LowMemoryTuner;
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_lsb_pipeline() {
+        let mut data = block_rand::<u32>(100_000);
+        let mut expected = data.clone();
+        expected.sort_unstable();
+
+        let mut tmp = vec![0u32; data.len()];
+        let (mut counts, _) = get_counts(&data, 0);
+
+        for level in 0..3 {
+            let (src, dst) = if level % 2 == 0 {
+                (&data, &mut tmp)
+            } else {
+                (&tmp, &mut data)
+            };
+
+            let next_counts = out_of_place_sort_with_counts(src, dst, &counts, level);
+            assert_eq!(next_counts, get_counts(dst, level + 1).0);
+            counts = next_counts;
+        }
+
+        out_of_place_sort(&tmp, &mut data, &counts, 3);
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    pub fn test_partition_offsets() {
+        let data = block_rand::<u16>(10_000);
+        let mut dst = vec![0u16; data.len()];
+
+        let (counts, _) = get_counts(&data, 1);
+        let prefix_sums = get_prefix_sums(&counts);
+        let end_offsets = get_end_offsets(&counts, &prefix_sums);
+        out_of_place_sort(&data, &mut dst, &counts, 1);
+
+        for b in 0..256 {
+            assert_eq!(end_offsets[b] - prefix_sums[b], counts[b]);
+            assert!(dst[prefix_sums[b]..end_offsets[b]]
+                .iter()
+                .all(|v| (v >> 8) as usize == b));
+        }
+    }
+
+    #[test]
+    pub fn test_in_place_first_level() {
+        let mut data = block_rand::<u32>(100_000);
+        let mut expected = data.clone();
+        expected.sort_unstable();
+
+        // Only the first level sorted can be unstable, the rest are scattered out-of-place
+        let (counts, _) = get_counts(&data, 0);
+        in_place_sort(&mut data, &counts, 0);
+        assert!(data.windows(2).all(|w| (w[0] & 0xFF) <= (w[1] & 0xFF)));
+
+        let mut tmp = vec![0u32; data.len()];
+        let (counts, _) = get_counts(&data, 1);
+        let counts = out_of_place_sort_with_counts(&data, &mut tmp, &counts, 1);
+        let counts = out_of_place_sort_with_counts(&tmp, &mut data, &counts, 2);
+        out_of_place_sort(&data, &mut tmp, &counts, 3);
+        assert_eq!(tmp, expected);
+    }
+
+    #[test]
+    pub fn test_radix_partition_top() {
+        for len in [0, 1, 2, 100_000] {
+            let mut data = block_rand::<u32>(len);
+            let mut expected = data.clone();
+            expected.sort_unstable();
+
+            let boundaries = radix_partition_top(&mut data, 3);
+            assert_eq!(boundaries[0], 0);
+            assert_eq!(boundaries[256], len);
+
+            for b in 0..256 {
+                assert!(boundaries[b] <= boundaries[b + 1]);
+                assert!(data[boundaries[b]..boundaries[b + 1]]
+                    .iter()
+                    .all(|v| (v >> 24) as usize == b));
+            }
+
+            data.sort_unstable();
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_radix_partition_lower_level() {
+        // Every item shares its top byte, so partitioning there leaves a single partition
+        let mut data: Vec<u32> = block_rand::<u32>(10_000)
+            .into_iter()
+            .map(|v| v & 0x00FF_FFFF)
+            .collect();
+
+        let boundaries = radix_partition_top(&mut data, 3);
+        assert_eq!(boundaries[1], data.len());
+
+        let boundaries = radix_partition_top(&mut data, 1);
+        for b in 0..256 {
+            assert!(data[boundaries[b]..boundaries[b + 1]]
+                .iter()
+                .all(|v| ((v >> 8) & 0xFF) as usize == b));
+        }
+    }
+
+    #[test]
+    pub fn test_single_item_next_counts() {
+        let src = [0x0100u16];
+        let mut dst = [0u16];
+        let (counts, _) = get_counts(&src, 0);
+
+        let next_counts = out_of_place_sort_with_counts(&src, &mut dst, &counts, 0);
+        assert_eq!(next_counts[1], 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "dst must be the same length as src")]
+    pub fn test_dst_len() {
+        let src = [1u8, 2, 3];
+        let mut dst = [0u8; 2];
+        let (counts, _) = get_counts(&src, 0);
+
+        out_of_place_sort(&src, &mut dst, &counts, 0);
+    }
+
+    // Small tiles, so that even small inputs are split into many regions to swap between
+    #[cfg(feature = "multi-threaded")]
+    struct SmallTiles;
+
+    #[cfg(feature = "multi-threaded")]
+    impl Tuner for SmallTiles {
+        fn pick_algorithm(&self, _p: &TuningParams, _counts: &[usize]) -> Algorithm {
+            Algorithm::Regions
+        }
+
+        fn tile_params(&self) -> TileParams {
+            TileParams {
+                min_tile_size: 1_000,
+                tiles_per_thread: 16,
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "multi-threaded")]
+    pub fn test_regions_sort_level() {
+        for (len, tuner) in [
+            (0, &SmallTiles as &(dyn Tuner + Send + Sync)),
+            (1, &SmallTiles),
+            (100_000, &SmallTiles),
+            (100_000, &LowMemoryTuner),
+            (1_000_000, &LowMemoryTuner),
+        ] {
+            let mut data = block_rand::<u32>(len);
+            let mut expected = data.clone();
+            expected.sort_unstable();
+
+            let counts = regions_sort(&mut data, 2, tuner);
+            assert_eq!(counts, get_counts(&expected, 2).0);
+            assert!(data
+                .windows(2)
+                .all(|w| (w[0] >> 16) & 0xFF <= (w[1] >> 16) & 0xFF));
+
+            data.sort_unstable();
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "multi-threaded")]
+    pub fn test_regions_sort_msb() {
+        let mut data = block_rand::<u32>(200_000);
+        let mut expected = data.clone();
+        expected.sort_unstable();
+
+        // Placing every bucket in turn, from the top level down, fully sorts in-place
+        let mut buckets = vec![(0, data.len(), 3)];
+        while let Some((start, end, level)) = buckets.pop() {
+            let counts = regions_sort(&mut data[start..end], level, &SmallTiles);
+
+            if level > 0 {
+                let mut offset = start;
+                for count in counts {
+                    if count > 1 {
+                        buckets.push((offset, offset + count, level - 1));
+                    }
+
+                    offset += count;
+                }
+            }
+        }
+
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "multi-threaded")]
+    pub fn test_regions_sort_skewed() {
+        // Most items share a byte, so most regions are already in place
+        let mut data: Vec<u64> = block_rand::<u64>(300_000)
+            .into_iter()
+            .map(|v| if v % 10 == 0 { v } else { v & 0x00FF_FFFF })
+            .collect();
+        let mut expected = data.clone();
+        expected.sort_unstable();
+
+        let counts = regions_sort(&mut data, 7, &SmallTiles);
+        assert!(data.windows(2).all(|w| w[0] >> 56 <= w[1] >> 56));
+
+        data.sort_unstable();
+        assert_eq!(data, expected);
+        assert_eq!(counts.iter().sum::<usize>(), data.len());
+    }
+}
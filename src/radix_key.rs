@@ -1,5 +1,73 @@
+use std::marker::PhantomData;
+
+/// `MAX_LEVELS` is the largest number of levels a key can have. Every level is counted into its
+/// own `[usize; 256]`, and keys which count all of their levels at once allocate one of these per
+/// level, so this bounds that at 128 MiB rather than letting a mis-set `LEVELS` allocate many
+/// gigabytes. It is far beyond the needs of any real key.
+///
+/// Sorting a `RadixKey` with more `LEVELS` than this fails to compile, while a runtime level count,
+/// such as from `DynRadixKey`, panics instead.
+///
+/// ```compile_fail
+/// use rdst::{RadixKey, RadixSort};
+///
+/// #[derive(Clone, Copy)]
+/// struct Huge;
+///
+/// impl RadixKey for Huge {
+///     const LEVELS: usize = rdst::MAX_LEVELS + 1;
+///
+///     fn get_level(&self, _: usize) -> u8 {
+///         0
+///     }
+/// }
+///
+/// vec![Huge, Huge].radix_sort_unstable();
+/// ```
+pub const MAX_LEVELS: usize = 1 << 16;
+
 pub trait RadixKey {
     const LEVELS: usize;
 
+    /// `BATCHED_LEVELS` signals that `get_all_levels` is much cheaper than calling `get_level`
+    /// once per level, for example when every byte is derived from the same expensive computation.
+    /// When set, sorts which need the counts of many levels will extract all of the levels of
+    /// each item in a single call rather than calling `get_level` for each level separately.
+    ///
+    /// This should be paired with an efficient `get_all_levels` implementation. Default: `false`
+    const BATCHED_LEVELS: bool = false;
+
+    /// `get_level` returns the byte of the key at `level`, where level `0` is the least significant.
+    /// It is called any number of times for each item, from any thread, and as the item is moved,
+    /// so it must only depend on the item itself, and on any data it references which doesn't
+    /// change during the sort.
     fn get_level(&self, level: usize) -> u8;
+
+    /// `get_all_levels` writes every level of the key into `out`, such that
+    /// `out[level] == self.get_level(level)`. `out` will always have a length of `LEVELS`.
+    ///
+    /// The default implementation calls `get_level` for each level. Override this if your type
+    /// can produce all of its bytes at once more cheaply, for example via `to_le_bytes()`.
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        for (level, b) in out.iter_mut().enumerate() {
+            *b = self.get_level(level);
+        }
+    }
+}
+
+struct CheckLevels<T>(PhantomData<T>);
+
+impl<T: RadixKey> CheckLevels<T> {
+    const VALID: () = assert!(
+        T::LEVELS <= MAX_LEVELS,
+        "RadixKey::LEVELS is larger than rdst::MAX_LEVELS"
+    );
+}
+
+// Fails to compile for a key whose `LEVELS` is larger than `MAX_LEVELS`, as the assert is
+// evaluated as a constant whenever this is instantiated.
+#[inline]
+pub(crate) const fn check_levels<T: RadixKey>() {
+    CheckLevels::<T>::VALID
 }
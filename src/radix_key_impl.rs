@@ -7,6 +7,11 @@ impl RadixKey for u8 {
     fn get_level(&self, _: usize) -> u8 {
         *self
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out[0] = *self;
+    }
 }
 
 impl RadixKey for u16 {
@@ -16,24 +21,41 @@ impl RadixKey for u16 {
     fn get_level(&self, level: usize) -> u8 {
         (self >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&self.to_le_bytes());
+    }
 }
 
+// u32 and u64 are by far the most commonly sorted keys, so make sure their key extraction is always
+// inlined into the sorting loops, even when it crosses into rayon closures.
 impl RadixKey for u32 {
     const LEVELS: usize = 4;
 
-    #[inline]
+    #[inline(always)]
     fn get_level(&self, level: usize) -> u8 {
         (self >> (level * 8)) as u8
     }
+
+    #[inline(always)]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&self.to_le_bytes());
+    }
 }
 
 impl RadixKey for u64 {
     const LEVELS: usize = 8;
 
-    #[inline]
+    #[inline(always)]
     fn get_level(&self, level: usize) -> u8 {
         (self >> (level * 8)) as u8
     }
+
+    #[inline(always)]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&self.to_le_bytes());
+    }
 }
 
 impl RadixKey for u128 {
@@ -43,6 +65,11 @@ impl RadixKey for u128 {
     fn get_level(&self, level: usize) -> u8 {
         (self >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&self.to_le_bytes());
+    }
 }
 
 #[cfg(target_pointer_width = "16")]
@@ -53,6 +80,11 @@ impl RadixKey for usize {
     fn get_level(&self, level: usize) -> u8 {
         (self >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&self.to_le_bytes());
+    }
 }
 
 #[cfg(target_pointer_width = "32")]
@@ -63,6 +95,11 @@ impl RadixKey for usize {
     fn get_level(&self, level: usize) -> u8 {
         (self >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&self.to_le_bytes());
+    }
 }
 
 #[cfg(target_pointer_width = "64")]
@@ -73,6 +110,11 @@ impl RadixKey for usize {
     fn get_level(&self, level: usize) -> u8 {
         (self >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&self.to_le_bytes());
+    }
 }
 
 impl<const N: usize> RadixKey for [u8; N] {
@@ -82,6 +124,54 @@ impl<const N: usize> RadixKey for [u8; N] {
     fn get_level(&self, level: usize) -> u8 {
         self[level]
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(self);
+    }
+}
+
+// A tagged key, sorted by the tag first and then by the payload in the same way as `[u8; N]`.
+impl<const N: usize> RadixKey for (u8, [u8; N]) {
+    const LEVELS: usize = N + 1;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        if level < N {
+            self.1[level]
+        } else {
+            self.0
+        }
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out[..N].copy_from_slice(&self.1);
+        out[N] = self.0;
+    }
+}
+
+// A hash-major composite key, sorted by the `u64` hash first and then by the exact key, so that
+// distinct keys whose hashes collide still end up in separate groups.
+impl<K: RadixKey> RadixKey for (u64, K) {
+    const LEVELS: usize = K::LEVELS + 8;
+    const BATCHED_LEVELS: bool = K::BATCHED_LEVELS;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        if level < K::LEVELS {
+            self.1.get_level(level)
+        } else {
+            (self.0 >> ((level - K::LEVELS) * 8)) as u8
+        }
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        let (key, hash) = out.split_at_mut(K::LEVELS);
+        self.1.get_all_levels(key);
+        hash.copy_from_slice(&self.0.to_le_bytes());
+    }
 }
 
 impl RadixKey for i8 {
@@ -91,6 +181,11 @@ impl RadixKey for i8 {
     fn get_level(&self, _: usize) -> u8 {
         (*self ^ i8::MIN) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out[0] = (*self ^ i8::MIN) as u8;
+    }
 }
 
 impl RadixKey for i16 {
@@ -100,6 +195,11 @@ impl RadixKey for i16 {
     fn get_level(&self, level: usize) -> u8 {
         ((self ^ i16::MIN) >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&(self ^ i16::MIN).to_le_bytes());
+    }
 }
 
 impl RadixKey for i32 {
@@ -109,6 +209,11 @@ impl RadixKey for i32 {
     fn get_level(&self, level: usize) -> u8 {
         ((self ^ i32::MIN) >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&(self ^ i32::MIN).to_le_bytes());
+    }
 }
 
 impl RadixKey for i64 {
@@ -118,6 +223,11 @@ impl RadixKey for i64 {
     fn get_level(&self, level: usize) -> u8 {
         ((self ^ i64::MIN) >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&(self ^ i64::MIN).to_le_bytes());
+    }
 }
 
 impl RadixKey for i128 {
@@ -127,6 +237,63 @@ impl RadixKey for i128 {
     fn get_level(&self, level: usize) -> u8 {
         ((self ^ i128::MIN) >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&(self ^ i128::MIN).to_le_bytes());
+    }
+}
+
+// Arrays of signed integers sort lexicographically, so unlike `[u8; N]`, the first element is the
+// most significant. Each element is sign-flipped in the same way as the scalar impls above.
+macro_rules! impl_signed_array {
+    ($($t:ty),*) => {
+        $(
+            impl<const N: usize> RadixKey for [$t; N] {
+                const LEVELS: usize = N * std::mem::size_of::<$t>();
+
+                #[inline]
+                fn get_level(&self, level: usize) -> u8 {
+                    let size = std::mem::size_of::<$t>();
+                    let v = self[N - 1 - level / size];
+
+                    ((v ^ <$t>::MIN) >> ((level % size) * 8)) as u8
+                }
+
+                #[inline]
+                fn get_all_levels(&self, out: &mut [u8]) {
+                    let size = std::mem::size_of::<$t>();
+
+                    for (chunk, v) in out.chunks_exact_mut(size).zip(self.iter().rev()) {
+                        chunk.copy_from_slice(&(v ^ <$t>::MIN).to_le_bytes());
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_signed_array!(i8, i16, i32, i64);
+
+// Sorted as an N bit binary number, with the first element as the most significant bit. When N
+// isn't a multiple of 8, the missing bits of the most significant byte are always zero.
+impl<const N: usize> RadixKey for [bool; N] {
+    const LEVELS: usize = N.div_ceil(8);
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        let mut byte = 0u8;
+
+        for bit in 0..8 {
+            let pos = level * 8 + bit;
+
+            if pos < N && self[N - 1 - pos] {
+                byte |= 1 << bit;
+            }
+        }
+
+        byte
+    }
 }
 
 #[cfg(target_pointer_width = "16")]
@@ -137,6 +304,11 @@ impl RadixKey for isize {
     fn get_level(&self, level: usize) -> u8 {
         ((self ^ isize::MIN) >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&(self ^ isize::MIN).to_le_bytes());
+    }
 }
 
 #[cfg(target_pointer_width = "32")]
@@ -147,6 +319,11 @@ impl RadixKey for isize {
     fn get_level(&self, level: usize) -> u8 {
         ((self ^ isize::MIN) >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&(self ^ isize::MIN).to_le_bytes());
+    }
 }
 
 #[cfg(target_pointer_width = "64")]
@@ -157,6 +334,11 @@ impl RadixKey for isize {
     fn get_level(&self, level: usize) -> u8 {
         ((self ^ isize::MIN) >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        out.copy_from_slice(&(self ^ isize::MIN).to_le_bytes());
+    }
 }
 
 impl RadixKey for f32 {
@@ -170,6 +352,15 @@ impl RadixKey for f32 {
 
         ((s ^ i32::MIN) >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        let mut s = self.to_bits() as i32;
+
+        s ^= (((s >> 31) as u32) >> 1) as i32;
+
+        out.copy_from_slice(&(s ^ i32::MIN).to_le_bytes());
+    }
 }
 
 impl RadixKey for f64 {
@@ -182,4 +373,12 @@ impl RadixKey for f64 {
 
         ((s ^ i64::MIN) >> (level * 8)) as u8
     }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        let mut s = self.to_bits() as i64;
+        s ^= (((s >> 63) as u64) >> 1) as i64;
+
+        out.copy_from_slice(&(s ^ i64::MIN).to_le_bytes());
+    }
 }
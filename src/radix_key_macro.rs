@@ -0,0 +1,566 @@
+use crate::RadixKey;
+
+/// `radix_key!` implements `RadixKey` for a struct by combining the `RadixKey` implementations of
+/// some of its fields. Each field is given a priority, with `0` being compared first (the most
+/// significant), `1` next, and so on. Priorities must be unique.
+///
+/// `LEVELS` is the sum of the `LEVELS` of each listed field, and fields that aren't listed are not
+/// part of the key.
+///
+/// Fields of tuple structs are listed by index, and generic structs are prefixed with their type
+/// parameters, as in `impl<T> Id<T> { 0: 0 }`. This covers newtypes which tag an id with a
+/// `PhantomData` type for type safety, where the `PhantomData` field is simply left out.
+///
+/// ```
+/// use rdst::{radix_key, RadixSort};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Event {
+///     day: u16,
+///     minute: u16,
+///     id: u32,
+/// }
+///
+/// // Sort by day, then by minute within each day
+/// radix_key!(Event { day: 0, minute: 1 });
+///
+/// let mut events = vec![
+///     Event { day: 2, minute: 5, id: 0 },
+///     Event { day: 1, minute: 30, id: 1 },
+///     Event { day: 1, minute: 10, id: 2 },
+/// ];
+/// events.radix_sort_unstable();
+///
+/// let ids: Vec<u32> = events.iter().map(|e| e.id).collect();
+/// assert_eq!(ids, [2, 1, 0]);
+/// ```
+///
+/// ```
+/// use rdst::{radix_key, RadixSort};
+/// use std::marker::PhantomData;
+///
+/// #[derive(Clone, Copy)]
+/// struct User;
+///
+/// #[derive(Clone, Copy)]
+/// struct Id<T>(u64, PhantomData<T>);
+///
+/// // Sort by the id alone, whatever it is tagged with
+/// radix_key!(impl<T> Id<T> { 0: 0 });
+///
+/// let mut ids: Vec<Id<User>> = vec![Id(3, PhantomData), Id(1, PhantomData)];
+/// ids.radix_sort_unstable();
+///
+/// assert_eq!(ids[0].0, 1);
+/// ```
+#[macro_export]
+macro_rules! radix_key {
+    (impl<$($gen:ident),+> $ty:ty { $($field:tt : $priority:expr),+ $(,)? }) => {
+        $crate::radix_key!(@impl [$($gen),+] $ty, [$($field : $priority),+], $($field : $priority),+);
+    };
+
+    ($ty:ty { $($field:tt : $priority:expr),+ $(,)? }) => {
+        $crate::radix_key!(@impl [] $ty, [$($field : $priority),+], $($field : $priority),+);
+    };
+
+    (@impl [$($gen:ident),*] $ty:ty, $all:tt, $($field:tt : $priority:expr),+) => {
+        impl<$($gen),*> $crate::RadixKey for $ty {
+            const LEVELS: usize = 0 $(+ $crate::__field_levels(|s: &$ty| &s.$field))+;
+
+            #[inline]
+            fn get_level(&self, level: usize) -> u8 {
+                $(
+                    let offset = $crate::radix_key!(@offset $ty, $priority, $all);
+                    let levels = $crate::__field_levels(|s: &$ty| &s.$field);
+
+                    if level >= offset && level < offset + levels {
+                        return $crate::RadixKey::get_level(&self.$field, level - offset);
+                    }
+                )+
+
+                0
+            }
+        }
+    };
+
+    // A field's lowest level sits above the levels of every field with a lower priority
+    (@offset $ty:ty, $priority:expr, [$($field:tt : $other:expr),+]) => {
+        0 $(+ if $other > $priority {
+            $crate::__field_levels(|s: &$ty| &s.$field)
+        } else {
+            0
+        })+
+    };
+}
+
+/// `radix_key_packed!` implements `RadixKey` for a struct by packing some of its unsigned integer
+/// fields into as few bits as they need, given the number of bits of each. Fields are listed in
+/// the order to compare them in, with the first being the most significant.
+///
+/// `LEVELS` is just enough bytes to hold the total number of bits, which can be at most 128. This
+/// saves the passes `radix_key!` would spend on the unused high bytes of each field, such as a
+/// 3-bit flag, a 20-bit id and a 9-bit code, which pack into 4 levels rather than the 7 of a `u8`,
+/// `u32` and `u16`. Fields that aren't listed are not part of the key.
+///
+/// Each field is converted with `as u128`, so it should be an unsigned integer or `bool`. In debug
+/// builds, a value which doesn't fit in its bits panics, as it would otherwise spill into the
+/// field above it. Release builds skip the check.
+///
+/// ```
+/// use rdst::{radix_key_packed, RadixKey, RadixSort};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Record {
+///     flag: u8,
+///     id: u32,
+///     code: u16,
+/// }
+///
+/// // Sort by flag, then id, then code, in 3 + 20 + 9 = 32 bits
+/// radix_key_packed!(Record { flag: 3, id: 20, code: 9 });
+/// assert_eq!(Record::LEVELS, 4);
+///
+/// let mut records = vec![
+///     Record { flag: 1, id: 5, code: 0 },
+///     Record { flag: 0, id: 900_000, code: 7 },
+///     Record { flag: 0, id: 900_000, code: 2 },
+/// ];
+/// records.radix_sort_unstable();
+///
+/// let codes: Vec<u16> = records.iter().map(|r| r.code).collect();
+/// assert_eq!(codes, [2, 7, 0]);
+/// ```
+#[macro_export]
+macro_rules! radix_key_packed {
+    ($ty:ty { $($field:ident : $bits:expr),+ $(,)? }) => {
+        impl $crate::RadixKey for $ty {
+            const LEVELS: usize = $crate::__packed_levels(0 $(+ $bits)+);
+
+            #[inline]
+            fn get_level(&self, level: usize) -> u8 {
+                let packed = $crate::radix_key_packed!(@pack self, $($field : $bits),+);
+
+                (packed >> (level * 8)) as u8
+            }
+
+            #[inline]
+            fn get_all_levels(&self, out: &mut [u8]) {
+                let packed = $crate::radix_key_packed!(@pack self, $($field : $bits),+);
+
+                out.copy_from_slice(&packed.to_le_bytes()[..out.len()]);
+            }
+        }
+    };
+
+    (@pack $self:ident, $($field:ident : $bits:expr),+) => {{
+        let mut packed = 0u128;
+        $(
+            packed = $crate::__pack_field(packed, $self.$field as u128, $bits);
+        )+
+
+        packed
+    }};
+}
+
+/// `radix_key_enum!` wraps the definition of an enum with a primitive representation, such as
+/// `#[repr(u16)]`, and implements `RadixKey` for it to sort by its discriminant. The `repr` can
+/// be any of the integer types, and may appear anywhere among the enum's attributes.
+///
+/// The discriminant is read directly, so variants may also have fields, which are not part of the
+/// key.
+///
+/// ```
+/// use rdst::{radix_key_enum, RadixSort};
+///
+/// radix_key_enum! {
+///     #[derive(Clone, Copy, Debug, PartialEq)]
+///     #[repr(u16)]
+///     enum Level {
+///         Error = 500,
+///         Debug = 100,
+///         Info = 200,
+///     }
+/// }
+///
+/// let mut levels = vec![Level::Error, Level::Debug, Level::Info];
+/// levels.radix_sort_unstable();
+///
+/// assert_eq!(levels, [Level::Debug, Level::Info, Level::Error]);
+/// ```
+#[macro_export]
+macro_rules! radix_key_enum {
+    (@find [$($attrs:tt)*] #[repr($repr:ident)] $($rest:tt)+) => {
+        $crate::radix_key_enum!(@impl $repr, [$($attrs)* #[repr($repr)]] $($rest)+);
+    };
+
+    (@find [$($attrs:tt)*] #[$meta:meta] $($rest:tt)+) => {
+        $crate::radix_key_enum!(@find [$($attrs)* #[$meta]] $($rest)+);
+    };
+
+    (@find [$($attrs:tt)*] $($rest:tt)+) => {
+        compile_error!("radix_key_enum! requires the enum to have a #[repr] of an integer type");
+    };
+
+    (@impl $repr:ident, [$($attrs:tt)*] $(#[$meta:meta])* $vis:vis enum $name:ident $body:tt) => {
+        $($attrs)*
+        $(#[$meta])*
+        $vis enum $name $body
+
+        impl $crate::RadixKey for $name {
+            const LEVELS: usize = <$repr as $crate::RadixKey>::LEVELS;
+
+            #[inline]
+            #[allow(unsafe_code)]
+            fn get_level(&self, level: usize) -> u8 {
+                // Safety: an enum with a primitive representation stores its discriminant as
+                // that primitive at the start of every variant
+                let discriminant = unsafe { *(self as *const Self as *const $repr) };
+
+                $crate::RadixKey::get_level(&discriminant, level)
+            }
+        }
+    };
+
+    ($($def:tt)+) => {
+        $crate::radix_key_enum!(@find [] $($def)+);
+    };
+}
+
+/// `radix_key_bits!` implements `RadixKey` for a type with a `bits()` method returning its raw
+/// integer representation, such as the types generated by the `bitflags` crate, to sort by that
+/// representation.
+///
+/// ```
+/// use rdst::{radix_key_bits, RadixSort};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Permissions(u32);
+///
+/// impl Permissions {
+///     const fn bits(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// radix_key_bits!(Permissions);
+///
+/// let mut perms = vec![Permissions(0b110), Permissions(0b001), Permissions(0b011)];
+/// perms.radix_sort_unstable();
+///
+/// assert_eq!(perms, [Permissions(0b001), Permissions(0b011), Permissions(0b110)]);
+/// ```
+#[macro_export]
+macro_rules! radix_key_bits {
+    ($ty:ty) => {
+        impl $crate::RadixKey for $ty {
+            const LEVELS: usize = $crate::__return_levels(|s: &$ty| s.bits());
+
+            #[inline]
+            fn get_level(&self, level: usize) -> u8 {
+                $crate::RadixKey::get_level(&self.bits(), level)
+            }
+        }
+    };
+}
+
+/// `__field_levels` gets the `LEVELS` of a field's type via an accessor, as `radix_key!` only
+/// knows the field names. This is an implementation detail of `radix_key!`.
+#[doc(hidden)]
+pub const fn __field_levels<S, K: RadixKey>(_field: fn(&S) -> &K) -> usize {
+    K::LEVELS
+}
+
+/// `__packed_levels` gets the `LEVELS` needed to hold `bits` bits, failing to compile if that is
+/// more than fit in a `u128`. This is an implementation detail of `radix_key_packed!`.
+#[doc(hidden)]
+pub const fn __packed_levels(bits: u32) -> usize {
+    assert!(
+        bits > 0 && bits <= 128,
+        "radix_key_packed! fields must total between 1 and 128 bits"
+    );
+
+    bits.div_ceil(8) as usize
+}
+
+/// `__pack_field` shifts the fields packed so far up by `bits`, and packs `value` below them. This
+/// is an implementation detail of `radix_key_packed!`.
+#[doc(hidden)]
+#[inline]
+pub fn __pack_field(packed: u128, value: u128, bits: u32) -> u128 {
+    debug_assert!(
+        value.checked_shr(bits).unwrap_or(0) == 0,
+        "radix_key_packed!: {} doesn't fit in {} bits",
+        value,
+        bits
+    );
+
+    packed.checked_shl(bits).unwrap_or(0) | value
+}
+
+/// `__return_levels` gets the `LEVELS` of a method's return type via an accessor, as
+/// `radix_key_bits!` only knows the method name. This is an implementation detail of
+/// `radix_key_bits!`.
+#[doc(hidden)]
+pub const fn __return_levels<S, K: RadixKey>(_method: fn(&S) -> K) -> usize {
+    K::LEVELS
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RadixKey, RadixSort};
+    use block_pseudorand::block_rand;
+    use std::marker::PhantomData;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Composite {
+        a: u8,
+        b: u32,
+        c: i16,
+        ignored: u64,
+    }
+
+    radix_key!(Composite { b: 0, c: 1, a: 2 });
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct User;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Id<T>(u64, PhantomData<T>);
+
+    radix_key!(impl<T> Id<T> { 0: 0 });
+
+    // Tuple fields listed by index, in a different order to their position
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Tagged<T, U>(u8, u16, PhantomData<(T, U)>);
+
+    radix_key!(impl<T, U> Tagged<T, U> { 1: 0, 0: 1 });
+
+    radix_key_enum! {
+        /// Attributes before the `repr` are kept too
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        #[repr(u16)]
+        pub(crate) enum Status {
+            Closed = 0x0300,
+            Pending = 0x0003,
+            Active = 0x0102,
+            Archived = 0xFFFF,
+        }
+    }
+
+    radix_key_enum! {
+        #[repr(i8)]
+        #[derive(Debug, PartialEq)]
+        enum Signed {
+            Negative(u32) = -3,
+            Zero = 0,
+            Positive { value: u8 } = 7,
+        }
+    }
+
+    #[test]
+    pub fn test_levels() {
+        assert_eq!(Composite::LEVELS, 7);
+
+        let v = Composite {
+            a: 0x01,
+            b: 0x0203_0405,
+            c: 0x0607,
+            ignored: u64::MAX,
+        };
+
+        let levels: Vec<u8> = (0..Composite::LEVELS).map(|l| v.get_level(l)).collect();
+        assert_eq!(levels, [0x01, 0x07, 0x86, 0x05, 0x04, 0x03, 0x02]);
+    }
+
+    #[test]
+    pub fn test_sort() {
+        let mut inputs: Vec<Composite> = block_rand::<u64>(100_000)
+            .into_iter()
+            .map(|v| Composite {
+                a: v as u8,
+                b: (v >> 8) as u32 % 100,
+                c: (v >> 40) as i16 % 100,
+                ignored: v,
+            })
+            .collect();
+
+        let mut expected = inputs.clone();
+        expected.sort_by_key(|v| (v.b, v.c, v.a));
+        inputs.radix_sort_unstable();
+
+        let key = |v: &Composite| (v.b, v.c, v.a);
+        assert!(inputs.iter().map(key).eq(expected.iter().map(key)));
+    }
+
+    #[test]
+    pub fn test_typed_ids() {
+        assert_eq!(Id::<User>::LEVELS, 8);
+
+        let inputs = block_rand::<u64>(100_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        let mut ids: Vec<Id<User>> = inputs.into_iter().map(|v| Id(v, PhantomData)).collect();
+        ids.radix_sort_unstable();
+
+        let actual: Vec<u64> = ids.into_iter().map(|id| id.0).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    pub fn test_tuple_fields() {
+        assert_eq!(Tagged::<User, ()>::LEVELS, 3);
+
+        let mut inputs: Vec<Tagged<User, ()>> = block_rand::<u32>(100_000)
+            .into_iter()
+            .map(|v| Tagged(v as u8, (v >> 8) as u16, PhantomData))
+            .collect();
+
+        let mut expected = inputs.clone();
+        expected.sort_by_key(|v| (v.1, v.0));
+        inputs.radix_sort_unstable();
+
+        assert_eq!(inputs, expected);
+    }
+
+    #[test]
+    pub fn test_enum_levels() {
+        assert_eq!(Status::LEVELS, 2);
+        assert_eq!(Status::Active.get_level(0), 0x02);
+        assert_eq!(Status::Active.get_level(1), 0x01);
+        assert_eq!(Signed::LEVELS, 1);
+    }
+
+    #[test]
+    pub fn test_enum_sort() {
+        let statuses = [
+            Status::Closed,
+            Status::Pending,
+            Status::Active,
+            Status::Archived,
+        ];
+        let mut inputs: Vec<Status> = block_rand::<u8>(100_000)
+            .into_iter()
+            .map(|v| statuses[v as usize % statuses.len()])
+            .collect();
+
+        let mut expected = inputs.clone();
+        expected.sort_by_key(|s| *s as u16);
+        inputs.radix_sort_unstable();
+
+        assert_eq!(inputs, expected);
+    }
+
+    #[test]
+    pub fn test_enum_signed_with_fields() {
+        let values = [
+            Signed::Positive { value: 1 },
+            Signed::Zero,
+            Signed::Negative(5),
+        ];
+
+        let keys: Vec<u8> = values.iter().map(|v| v.get_level(0)).collect();
+        assert!(keys[2] < keys[1] && keys[1] < keys[0]);
+    }
+
+    bitflags::bitflags! {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Permissions: u32 {
+            const READ = 0b0001;
+            const WRITE = 0b0010;
+            const EXECUTE = 0b0100;
+            const ADMIN = 1 << 31;
+        }
+    }
+
+    radix_key_bits!(Permissions);
+
+    #[test]
+    pub fn test_bitflags() {
+        assert_eq!(Permissions::LEVELS, 4);
+
+        let mut perms = vec![
+            Permissions::ADMIN,
+            Permissions::READ | Permissions::WRITE,
+            Permissions::empty(),
+            Permissions::EXECUTE,
+            Permissions::READ,
+            Permissions::all(),
+        ];
+        perms.radix_sort_unstable();
+
+        let bits: Vec<u32> = perms.iter().map(|p| p.bits()).collect();
+        assert_eq!(
+            bits,
+            [0, 0b0001, 0b0011, 0b0100, 1 << 31, (1 << 31) | 0b0111]
+        );
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Packed {
+        flag: u8,
+        id: u32,
+        code: u16,
+        ignored: u64,
+    }
+
+    radix_key_packed!(Packed {
+        flag: 3,
+        id: 20,
+        code: 9
+    });
+
+    #[test]
+    pub fn test_packed_levels() {
+        assert_eq!(Packed::LEVELS, 4);
+
+        let v = Packed {
+            flag: 0b101,
+            id: 0xA_BCDE,
+            code: 0x1FF,
+            ignored: u64::MAX,
+        };
+
+        let packed: u32 = (0b101 << 29) | (0xA_BCDE << 9) | 0x1FF;
+        let levels: Vec<u8> = (0..Packed::LEVELS).map(|l| v.get_level(l)).collect();
+        assert_eq!(levels, packed.to_le_bytes());
+
+        let mut all = [0u8; 4];
+        v.get_all_levels(&mut all);
+        assert_eq!(all, packed.to_le_bytes());
+    }
+
+    #[test]
+    pub fn test_packed_sort() {
+        let mut inputs: Vec<Packed> = block_rand::<u64>(100_000)
+            .into_iter()
+            .map(|v| Packed {
+                flag: v as u8 & 0b111,
+                id: (v >> 8) as u32 % 50 * 20_000,
+                code: (v >> 40) as u16 & 0x1FF,
+                ignored: v,
+            })
+            .collect();
+
+        let key = |v: &Packed| (v.flag, v.id, v.code);
+        let mut expected = inputs.clone();
+        expected.sort_by_key(key);
+        inputs.radix_sort_unstable();
+
+        assert!(inputs.iter().map(key).eq(expected.iter().map(key)));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "doesn't fit in 3 bits")]
+    pub fn test_packed_overflow_panics() {
+        let v = Packed {
+            flag: 8,
+            id: 0,
+            code: 0,
+            ignored: 0,
+        };
+
+        v.get_level(0);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::RadixKey;
+use std::ops::Range;
+
+/// `RadixRange` is a `Copy` equivalent of `Range<T>`, which sorts by `start` and then by `end`.
+/// `Range` itself isn't `Copy`, so it can't be radix sorted directly.
+///
+/// `LEVELS` is twice the `LEVELS` of `T`, with the `start` bytes the most significant.
+///
+/// ```
+/// use rdst::{RadixRange, RadixSort};
+///
+/// let mut intervals: Vec<RadixRange<u32>> = vec![(5..9).into(), (1..7).into(), (1..3).into()];
+/// intervals.radix_sort_unstable();
+///
+/// assert_eq!(intervals, [(1..3).into(), (1..7).into(), (5..9).into()]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RadixRange<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T> From<Range<T>> for RadixRange<T> {
+    fn from(r: Range<T>) -> Self {
+        Self {
+            start: r.start,
+            end: r.end,
+        }
+    }
+}
+
+impl<T> From<RadixRange<T>> for Range<T> {
+    fn from(r: RadixRange<T>) -> Self {
+        r.start..r.end
+    }
+}
+
+impl<T> RadixKey for RadixRange<T>
+where
+    T: RadixKey,
+{
+    const LEVELS: usize = T::LEVELS * 2;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        if level < T::LEVELS {
+            self.end.get_level(level)
+        } else {
+            self.start.get_level(level - T::LEVELS)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RadixKey, RadixRange, RadixSort};
+    use block_pseudorand::block_rand;
+    use std::ops::Range;
+
+    #[test]
+    pub fn test_levels() {
+        assert_eq!(RadixRange::<u8>::LEVELS, 2);
+        assert_eq!(RadixRange::<u32>::LEVELS, 8);
+        assert_eq!(RadixRange::<u128>::LEVELS, 32);
+
+        let r: RadixRange<u16> = (0x0102..0x0304).into();
+        let levels: Vec<u8> = (0..4).map(|l| r.get_level(l)).collect();
+        assert_eq!(levels, [0x04, 0x03, 0x02, 0x01]);
+    }
+
+    #[test]
+    pub fn test_overlapping() {
+        let mut inputs: Vec<RadixRange<u32>> = vec![
+            (10..20).into(),
+            (5..15).into(),
+            (10..12).into(),
+            (5..30).into(),
+            (0..100).into(),
+            (10..20).into(),
+        ];
+        inputs.radix_sort_unstable();
+
+        let ranges: Vec<Range<u32>> = inputs.into_iter().map(Range::from).collect();
+        assert_eq!(ranges, [0..100, 5..15, 5..30, 10..12, 10..20, 10..20]);
+    }
+
+    #[test]
+    pub fn test_sort() {
+        let mut inputs: Vec<RadixRange<i16>> = block_rand::<u32>(100_000)
+            .into_iter()
+            .map(|v| {
+                let start = v as i16 % 200;
+                (start..start.saturating_add((v >> 16) as i16 % 50)).into()
+            })
+            .collect();
+
+        let mut expected = inputs.clone();
+        expected.sort();
+        inputs.radix_sort_unstable();
+
+        assert_eq!(inputs, expected);
+    }
+}
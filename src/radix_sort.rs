@@ -1,5 +1,10 @@
 use crate::radix_sort_builder::RadixSortBuilder;
+use crate::radix_sort_error::RadixSortError;
+use crate::radix_sort_stream::RadixSortStream;
+use crate::sort_config::SortConfig;
+use crate::utils::cmp_by_levels;
 use crate::RadixKey;
+use std::cmp::Ordering;
 
 pub trait RadixSort<T> {
     /// radix_sort_unstable runs a radix sort based upon the `rdst::RadixKey` implementation
@@ -15,7 +20,114 @@ pub trait RadixSort<T> {
     /// ```
     fn radix_sort_unstable(&mut self);
 
-    fn radix_sort_builder(&mut self) -> RadixSortBuilder<T>;
+    /// radix_sort_unstable_changed sorts your `Vec<T>` or `[T]` in the same way as
+    /// `radix_sort_unstable`, returning `false` without sorting if it was already sorted, or
+    /// `true` if it needed sorting.
+    ///
+    /// ```
+    /// use rdst::RadixSort;
+    ///
+    /// let mut values = [3, 1, 2];
+    ///
+    /// assert!(values.radix_sort_unstable_changed());
+    /// assert!(!values.radix_sort_unstable_changed());
+    /// assert_eq!(values, [1, 2, 3]);
+    /// ```
+    fn radix_sort_unstable_changed(&mut self) -> bool;
+
+    /// radix_sort_with_bounds sorts your `Vec<T>` or `[T]` in the same way as
+    /// `radix_sort_unstable`, and returns its minimum and maximum items, or `None` if it is empty.
+    ///
+    /// ```
+    /// use rdst::RadixSort;
+    ///
+    /// let mut values = [3, 1, 2];
+    ///
+    /// assert_eq!(values.radix_sort_with_bounds(), Some((1, 3)));
+    /// assert_eq!(values, [1, 2, 3]);
+    /// ```
+    fn radix_sort_with_bounds(&mut self) -> Option<(T, T)>;
+
+    /// radix_sort_and_split sorts your `Vec<T>` or `[T]` in the same way as
+    /// `radix_sort_unstable`, and returns the index of the first item whose key is greater than or
+    /// equal to the key of `threshold`. Items before the index are less than `threshold`, and
+    /// items from it onwards are not. If every item is less than `threshold`, this is the length.
+    ///
+    /// The index is found by a binary search of the sorted data, so it costs next to nothing on
+    /// top of the sort.
+    ///
+    /// ```
+    /// use rdst::RadixSort;
+    ///
+    /// let mut values = [30, 10, 20, 40];
+    /// let split = values.radix_sort_and_split(25);
+    ///
+    /// assert_eq!(split, 2);
+    /// assert_eq!(values[..split], [10, 20]);
+    /// assert_eq!(values[split..], [30, 40]);
+    /// ```
+    fn radix_sort_and_split(&mut self, threshold: T) -> usize;
+
+    /// radix_sort_unstable_with sorts your `Vec<T>` or `[T]` using all of the options in a
+    /// `SortConfig`. With `SortConfig::default()`, this is the same as `radix_sort_unstable`.
+    ///
+    /// ```
+    /// use rdst::{RadixSort, SortConfig};
+    ///
+    /// let mut values = [3, 1, 2];
+    /// values.radix_sort_unstable_with(&SortConfig::new().with_parallel(false));
+    ///
+    /// assert_eq!(values, [1, 2, 3]);
+    /// ```
+    fn radix_sort_unstable_with(&mut self, config: &SortConfig<'_>);
+
+    /// radix_sorted_copy returns a sorted copy of your `Vec<T>` or `[T]`, leaving the original
+    /// untouched.
+    ///
+    /// ```
+    /// use rdst::RadixSort;
+    ///
+    /// let values = [3, 1, 2];
+    /// let sorted = values.radix_sorted_copy();
+    ///
+    /// assert_eq!(sorted, [1, 2, 3]);
+    /// assert_eq!(values, [3, 1, 2]);
+    /// ```
+    #[must_use]
+    fn radix_sorted_copy(&self) -> Vec<T>;
+
+    /// radix_sort_streaming sorts your `Vec<T>` or `[T]` in sorted chunks, returning an iterator
+    /// which fully sorts each chunk as it is requested. The chunks are yielded in ascending
+    /// order, so consumers can start on the first chunk while the rest are still unsorted. See
+    /// `RadixSortStream` for the ordering guarantees.
+    ///
+    /// ```
+    /// use rdst::RadixSort;
+    ///
+    /// let mut values = [3, 1, 2];
+    /// let sorted: Vec<i32> = values.radix_sort_streaming().flatten().copied().collect();
+    ///
+    /// assert_eq!(sorted, [1, 2, 3]);
+    /// assert_eq!(values, [1, 2, 3]);
+    /// ```
+    fn radix_sort_streaming(&mut self) -> RadixSortStream<'_, T>;
+
+    /// try_radix_sort_unstable sorts your `Vec<T>` or `[T]` in the same way as
+    /// `radix_sort_unstable`, but returns `RadixSortError::OutOfMemory` rather than aborting if the
+    /// temporary buffer can't be allocated, leaving the data untouched. See
+    /// `RadixSortBuilder::try_sort()` for the details.
+    ///
+    /// ```
+    /// use rdst::RadixSort;
+    ///
+    /// let mut values = [3, 1, 2];
+    /// values.try_radix_sort_unstable().unwrap();
+    ///
+    /// assert_eq!(values, [1, 2, 3]);
+    /// ```
+    fn try_radix_sort_unstable(&mut self) -> Result<(), RadixSortError>;
+
+    fn radix_sort_builder(&mut self) -> RadixSortBuilder<'_, T>;
 }
 
 impl<T> RadixSort<T> for Vec<T>
@@ -26,7 +138,42 @@ where
         self.radix_sort_builder().sort();
     }
 
-    fn radix_sort_builder(&mut self) -> RadixSortBuilder<T> {
+    fn radix_sort_unstable_changed(&mut self) -> bool {
+        self.radix_sort_builder().sort_changed()
+    }
+
+    fn radix_sort_with_bounds(&mut self) -> Option<(T, T)> {
+        self.radix_sort_unstable();
+
+        Some((*self.first()?, *self.last()?))
+    }
+
+    fn radix_sort_and_split(&mut self, threshold: T) -> usize {
+        self.radix_sort_unstable();
+
+        self.partition_point(|v| cmp_by_levels(v, &threshold, T::LEVELS) == Ordering::Less)
+    }
+
+    fn radix_sort_unstable_with(&mut self, config: &SortConfig<'_>) {
+        self.radix_sort_builder().with_config(config).sort();
+    }
+
+    fn radix_sorted_copy(&self) -> Vec<T> {
+        let mut out = self.to_vec();
+        out.radix_sort_unstable();
+
+        out
+    }
+
+    fn radix_sort_streaming(&mut self) -> RadixSortStream<'_, T> {
+        RadixSortStream::new(self)
+    }
+
+    fn try_radix_sort_unstable(&mut self) -> Result<(), RadixSortError> {
+        self.radix_sort_builder().try_sort()
+    }
+
+    fn radix_sort_builder(&mut self) -> RadixSortBuilder<'_, T> {
         RadixSortBuilder::new(self)
     }
 }
@@ -39,16 +186,53 @@ where
         self.radix_sort_builder().sort();
     }
 
-    fn radix_sort_builder(&mut self) -> RadixSortBuilder<T> {
+    fn radix_sort_unstable_changed(&mut self) -> bool {
+        self.radix_sort_builder().sort_changed()
+    }
+
+    fn radix_sort_with_bounds(&mut self) -> Option<(T, T)> {
+        self.radix_sort_unstable();
+
+        Some((*self.first()?, *self.last()?))
+    }
+
+    fn radix_sort_and_split(&mut self, threshold: T) -> usize {
+        self.radix_sort_unstable();
+
+        self.partition_point(|v| cmp_by_levels(v, &threshold, T::LEVELS) == Ordering::Less)
+    }
+
+    fn radix_sort_unstable_with(&mut self, config: &SortConfig<'_>) {
+        self.radix_sort_builder().with_config(config).sort();
+    }
+
+    fn radix_sorted_copy(&self) -> Vec<T> {
+        let mut out = self.to_vec();
+        out.radix_sort_unstable();
+
+        out
+    }
+
+    fn radix_sort_streaming(&mut self) -> RadixSortStream<'_, T> {
+        RadixSortStream::new(self)
+    }
+
+    fn try_radix_sort_unstable(&mut self) -> Result<(), RadixSortError> {
+        self.radix_sort_builder().try_sort()
+    }
+
+    fn radix_sort_builder(&mut self) -> RadixSortBuilder<'_, T> {
         RadixSortBuilder::new(self)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tuner::{Algorithm, Tuner, TuningParams};
+    use crate::sorter::Sorter;
+    use crate::tuner::{Algorithm, TileParams, Tuner, TuningParams};
+    use crate::utils::get_counts;
     use crate::utils::test_utils::{sort_comparison_suite, NumericTest, SingleAlgoTuner};
-    use crate::RadixSort;
+    use crate::{RadixKey, RadixSort, SortConfig};
     use block_pseudorand::block_rand;
     use std::cmp::Ordering;
     use std::fmt::Debug;
@@ -69,6 +253,22 @@ mod tests {
         });
     }
 
+    fn test_reused_buffer_full_sort<T>(shift: T)
+    where
+        T: NumericTest<T>,
+    {
+        sort_comparison_suite(shift, |inputs| {
+            inputs.radix_sort_builder().with_reused_buffer(true).sort()
+        });
+    }
+
+    fn test_fallible_full_sort<T>(shift: T)
+    where
+        T: NumericTest<T>,
+    {
+        sort_comparison_suite(shift, |inputs| inputs.try_radix_sort_unstable().unwrap());
+    }
+
     fn test_custom_tuner_full_sort<T>(shift: T)
     where
         T: NumericTest<T>,
@@ -298,6 +498,118 @@ mod tests {
         test_custom_tuner_full_sort(32u64);
     }
 
+    #[test]
+    pub fn test_reused_buffer_u32() {
+        test_reused_buffer_full_sort(16u32);
+    }
+
+    #[test]
+    pub fn test_reused_buffer_u64() {
+        test_reused_buffer_full_sort(32u64);
+    }
+
+    #[test]
+    pub fn test_fallible_u32() {
+        test_fallible_full_sort(0u32);
+    }
+
+    #[test]
+    pub fn test_fallible_u64() {
+        test_fallible_full_sort(32u64);
+    }
+
+    #[test]
+    pub fn test_try_sort_swaps_algorithms() {
+        let inputs: Vec<u64> = block_rand(300_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        // Each of these allocates its own buffers in `sort()`
+        for algorithm in [
+            Algorithm::Scanning,
+            Algorithm::Recombinating,
+            Algorithm::Regions,
+            Algorithm::MtOop,
+            Algorithm::MtLsb,
+        ] {
+            let mut data = inputs.clone();
+            data.radix_sort_builder()
+                .with_algorithm(algorithm)
+                .try_sort()
+                .unwrap();
+            assert_eq!(data, expected, "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    pub fn test_wrapped_tuner_hooks() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Counts how often the tile size is looked up, which every multi-threaded sort of a large
+        // enough input does to count it in tiles
+        struct TiledTuner {
+            lookups: AtomicUsize,
+        }
+
+        impl Tuner for TiledTuner {
+            fn pick_algorithm(&self, _p: &TuningParams, _counts: &[usize]) -> Algorithm {
+                Algorithm::Lsb
+            }
+
+            fn tile_params(&self) -> TileParams {
+                self.lookups.fetch_add(1, Ordering::Relaxed);
+
+                TileParams::default()
+            }
+        }
+
+        let inputs: Vec<u32> = block_rand(300_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        // Each of these wraps the tuner to swap out some of its algorithms
+        for name in ["try_sort", "deterministic", "trace"] {
+            let tuner = TiledTuner {
+                lookups: AtomicUsize::new(0),
+            };
+            let mut data = inputs.clone();
+            let builder = data.radix_sort_builder().with_tuner(&tuner);
+
+            match name {
+                "try_sort" => builder.try_sort().unwrap(),
+                "deterministic" => builder.with_deterministic(true).sort(),
+                _ => {
+                    builder.sort_with_trace();
+                }
+            }
+
+            assert_eq!(data, expected, "{}", name);
+            assert!(tuner.lookups.into_inner() > 0, "{}", name);
+        }
+    }
+
+    #[test]
+    pub fn test_sort_with_trace() {
+        let inputs: Vec<u64> = block_rand(300_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        let mut data = inputs.clone();
+        let trace = data
+            .radix_sort_builder()
+            .with_algorithm(Algorithm::Ska)
+            .sort_with_trace();
+
+        assert_eq!(data, expected);
+        assert!(trace.iter().any(|c| c.input_len == 300_000 && c.level == 7));
+        assert!(trace.iter().all(|c| c.algorithm == Algorithm::Ska));
+        assert!(trace.iter().all(|c| c.input_len > 128));
+
+        // Small enough to go straight to the comparative sort, without asking the tuner
+        let mut small: Vec<u64> = block_rand(100);
+        assert!(small.radix_sort_builder().sort_with_trace().is_empty());
+    }
+
     #[test]
     pub fn test_f64_parallel_false_only() {
         let mut data = block_rand::<f64>(10_000_000);
@@ -309,4 +621,745 @@ mod tests {
             })
             .sort();
     }
+
+    #[test]
+    pub fn test_deep_levels() {
+        // Only the least significant bytes vary, so every bucket has to descend through all of the
+        // shared upper levels before it is fully sorted.
+        let inputs: Vec<[u8; 64]> = block_rand::<u16>(200_000)
+            .into_iter()
+            .map(|v| {
+                let mut key = [0u8; 64];
+                key[..2].copy_from_slice(&v.to_le_bytes());
+                key
+            })
+            .collect();
+
+        let mut expected = inputs.clone();
+        expected.sort_by(|a, b| a.iter().rev().cmp(b.iter().rev()));
+
+        for parallel in [true, false] {
+            let mut data = inputs.clone();
+            data.radix_sort_builder()
+                .with_parallel(parallel)
+                .with_tuner(&SingleAlgoTuner {
+                    algo: Algorithm::Ska,
+                })
+                .sort();
+
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_min_task_size() {
+        use std::collections::HashSet;
+        use std::sync::Mutex;
+
+        // Records the thread each bucket is sorted on
+        struct TaskTuner {
+            min_task_size: usize,
+            threads: Mutex<HashSet<Option<usize>>>,
+        }
+
+        impl Tuner for TaskTuner {
+            fn pick_algorithm(&self, _p: &TuningParams, _counts: &[usize]) -> Algorithm {
+                let thread = rayon::current_thread_index();
+                self.threads.lock().unwrap().insert(thread);
+
+                Algorithm::Ska
+            }
+
+            fn min_task_size(&self) -> usize {
+                self.min_task_size
+            }
+        }
+
+        // Every bucket descends through the shared upper levels, then splits into many small ones
+        let inputs: Vec<[u8; 64]> = block_rand::<u16>(200_000)
+            .into_iter()
+            .map(|v| {
+                let mut key = [0u8; 64];
+                key[..2].copy_from_slice(&v.to_le_bytes());
+                key
+            })
+            .collect();
+
+        let mut expected = inputs.clone();
+        expected.sort_by(|a, b| a.iter().rev().cmp(b.iter().rev()));
+
+        // The smallest task size used is 128, as buckets of up to 128 items are sorted by comparison
+        let (level_1_counts, _) = get_counts(&inputs, 1);
+        let level_0_tasks = level_1_counts.iter().filter(|c| **c > 128).count();
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+
+        for min_task_size in [0, 4_096, usize::MAX] {
+            let tuner = TaskTuner {
+                min_task_size,
+                threads: Mutex::new(HashSet::new()),
+            };
+            let sorter = Sorter::new(true, &tuner);
+
+            let mut data = inputs.clone();
+            pool.install(|| sorter.top_level_director(&mut data, None));
+            assert_eq!(data, expected);
+
+            // Each of levels 62 down to 1 is a single bucket of every item, which then splits into
+            // a bucket for each value of the top byte of the `u16` to sort on level 0
+            let expected_spawned = match min_task_size {
+                0 => 62 + level_0_tasks,
+                4_096 => 62,
+                _ => 0,
+            };
+            assert_eq!(sorter.spawned.into_inner(), expected_spawned);
+
+            // No bucket is large enough to spawn, so they are all sorted by the calling thread
+            if min_task_size == usize::MAX {
+                assert_eq!(tuner.threads.lock().unwrap().len(), 1);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_counting_sort() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        // Records whether any level was sorted by a radix sort rather than counted
+        struct CountingTuner {
+            threshold: usize,
+            picked: AtomicBool,
+        }
+
+        impl Tuner for CountingTuner {
+            fn pick_algorithm(&self, _p: &TuningParams, _counts: &[usize]) -> Algorithm {
+                self.picked.store(true, Ordering::Relaxed);
+
+                Algorithm::Lsb
+            }
+
+            fn counting_sort_threshold(&self) -> usize {
+                self.threshold
+            }
+        }
+
+        let u16s = block_rand::<u16>(1_000_000);
+        let i16s = block_rand::<i16>(1_000_000);
+        let pairs: Vec<[u8; 2]> = u16s.iter().map(|v| v.to_le_bytes()).collect();
+
+        // Counting is single-threaded, so it never replaces a multi-threaded sort
+        for (threshold, parallel) in [
+            (0, false),
+            (1_000_000, false),
+            (1_000_001, false),
+            (0, true),
+        ] {
+            let counted = threshold <= 1_000_000 && !parallel;
+
+            let tuner = CountingTuner {
+                threshold,
+                picked: AtomicBool::new(false),
+            };
+            let mut data = u16s.clone();
+            data.radix_sort_builder()
+                .with_parallel(parallel)
+                .with_tuner(&tuner)
+                .sort();
+            assert!(data.windows(2).all(|w| w[0] <= w[1]));
+            assert_eq!(tuner.picked.into_inner(), !counted);
+
+            let tuner = CountingTuner {
+                threshold,
+                picked: AtomicBool::new(false),
+            };
+            let mut data = i16s.clone();
+            data.radix_sort_builder()
+                .with_parallel(parallel)
+                .with_tuner(&tuner)
+                .sort();
+            assert!(data.windows(2).all(|w| w[0] <= w[1]));
+            assert_eq!(tuner.picked.into_inner(), !counted);
+
+            // Can't be rebuilt from its levels, so it is always radix sorted
+            let tuner = CountingTuner {
+                threshold,
+                picked: AtomicBool::new(false),
+            };
+            let mut data = pairs.clone();
+            data.radix_sort_builder()
+                .with_parallel(parallel)
+                .with_tuner(&tuner)
+                .sort();
+            assert!(data
+                .windows(2)
+                .all(|w| (w[0][1], w[0][0]) <= (w[1][1], w[1][0])));
+            assert!(tuner.picked.into_inner());
+        }
+
+        let mut data = u16s.clone();
+        let mut expected = u16s;
+        expected.sort_unstable();
+        data.radix_sort_unstable();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    pub fn test_tagged_byte_keys() {
+        let mut data: Vec<(u8, [u8; 3])> = vec![
+            (2, [0, 0, 0]),
+            (1, [0, 0, 9]),
+            (1, [9, 0, 0]),
+            (0, [255, 255, 255]),
+            (2, [1, 0, 0]),
+            (1, [0, 1, 0]),
+        ];
+        data.radix_sort_unstable();
+
+        // The payload's last byte is the most significant, as with `[u8; N]`
+        assert_eq!(
+            data,
+            [
+                (0, [255, 255, 255]),
+                (1, [9, 0, 0]),
+                (1, [0, 1, 0]),
+                (1, [0, 0, 9]),
+                (2, [0, 0, 0]),
+                (2, [1, 0, 0]),
+            ]
+        );
+
+        let inputs: Vec<(u8, [u8; 8])> = block_rand::<u64>(100_000)
+            .into_iter()
+            .map(|v| ((v >> 60) as u8, v.to_le_bytes()))
+            .collect();
+        let mut expected = inputs.clone();
+        expected.sort_by_key(|(tag, payload)| (*tag, u64::from_le_bytes(*payload)));
+
+        let mut data = inputs.clone();
+        data.radix_sort_unstable();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    pub fn test_signed_arrays() {
+        let mut data: Vec<[i16; 2]> = vec![[3, -1], [-5, 7], [3, -8], [0, 0], [-5, -7], [-1, 2]];
+        data.radix_sort_unstable();
+
+        assert_eq!(data, [[-5, -7], [-5, 7], [-1, 2], [0, 0], [3, -8], [3, -1]]);
+
+        let mut inputs: Vec<[i8; 3]> = block_rand::<u32>(100_000)
+            .into_iter()
+            .map(|v| [(v >> 28) as i8 - 8, (v >> 8) as i8, v as i8])
+            .collect();
+        let mut expected = inputs.clone();
+        expected.sort();
+        inputs.radix_sort_unstable();
+        assert_eq!(inputs, expected);
+
+        let mut inputs: Vec<[i32; 2]> = block_rand::<u64>(100_000)
+            .into_iter()
+            .map(|v| [(v >> 62) as i32 - 2, v as i32])
+            .collect();
+        let mut expected = inputs.clone();
+        expected.sort();
+        inputs.radix_sort_unstable();
+        assert_eq!(inputs, expected);
+
+        let mut inputs: Vec<[i64; 3]> = block_rand::<i64>(100_000)
+            .into_iter()
+            .map(|v| [v % 3, v >> 32, v])
+            .collect();
+        let mut expected = inputs.clone();
+        expected.sort();
+        inputs.radix_sort_unstable();
+        assert_eq!(inputs, expected);
+    }
+
+    #[test]
+    pub fn test_bool_arrays() {
+        fn bits<const N: usize>(v: u64) -> [bool; N] {
+            std::array::from_fn(|i| (v >> (N - 1 - i)) & 1 == 1)
+        }
+
+        let mut data = [
+            [true, false, true],
+            [false, true, true],
+            [true, false, false],
+            [false, false, false],
+        ];
+        data.radix_sort_unstable();
+        assert_eq!(
+            data.map(|b| b.map(|b| b as u8)),
+            [[0, 0, 0], [0, 1, 1], [1, 0, 0], [1, 0, 1]]
+        );
+
+        let mut values = block_rand::<u64>(100_000);
+        let mut inputs: Vec<[bool; 64]> = values.iter().map(|v| bits(*v)).collect();
+        values.sort_unstable();
+        inputs.radix_sort_unstable();
+        assert_eq!(inputs, values.iter().map(|v| bits(*v)).collect::<Vec<_>>());
+
+        // Not a multiple of 8, so the most significant byte is partially padded
+        let mut values: Vec<u64> = block_rand::<u16>(100_000)
+            .into_iter()
+            .map(|v| (v & 0x1FFF) as u64)
+            .collect();
+        let mut inputs: Vec<[bool; 13]> = values.iter().map(|v| bits(*v)).collect();
+        values.sort_unstable();
+        inputs.radix_sort_unstable();
+        assert_eq!(inputs, values.iter().map(|v| bits(*v)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    pub fn test_hash_tiebreak() {
+        // Few enough hashes that most of them collide between distinct keys
+        let mut inputs: Vec<(u64, [u8; 16])> = block_rand::<u64>(100_000)
+            .into_iter()
+            .map(|v| (v % 64, [(v >> 8) as u8 % 4; 16]))
+            .collect();
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+        inputs.radix_sort_unstable();
+        assert_eq!(inputs, expected);
+
+        let mut data = [(7u64, 2u32), (3, 9), (7, 1), (7, 2), (3, 9), (7, 1)];
+        data.radix_sort_unstable();
+        assert_eq!(data, [(3, 9), (3, 9), (7, 1), (7, 1), (7, 2), (7, 2)]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "RadixKey::get_level is not deterministic")]
+    pub fn test_nondeterministic_key() {
+        use crate::RadixKey;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        // Returns a different byte on every call, regardless of the item
+        #[derive(Clone, Copy)]
+        struct Flaky;
+
+        impl RadixKey for Flaky {
+            const LEVELS: usize = 1;
+
+            fn get_level(&self, _: usize) -> u8 {
+                (CALLS.fetch_add(1, Ordering::Relaxed) * 151) as u8
+            }
+        }
+
+        let mut data = vec![Flaky; 1000];
+        data.radix_sort_unstable();
+    }
+
+    #[test]
+    pub fn test_shared_base_key() {
+        use crate::RadixKey;
+
+        // Delta-encoded in blocks of 64, each relative to the smallest value in its block, and
+        // only decoded as each level is read
+        #[derive(Clone, Copy)]
+        struct Delta<'a> {
+            bases: &'a [u64],
+            block: u32,
+            delta: u32,
+        }
+
+        impl RadixKey for Delta<'_> {
+            const LEVELS: usize = 8;
+
+            #[inline]
+            fn get_level(&self, level: usize) -> u8 {
+                (self.bases[self.block as usize] + self.delta as u64).get_level(level)
+            }
+        }
+
+        let values: Vec<u64> = block_rand::<u64>(1_000_000)
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| ((i as u64) << 20) ^ (v >> 40))
+            .collect();
+        let bases: Vec<u64> = values
+            .chunks(64)
+            .map(|c| *c.iter().min().unwrap())
+            .collect();
+        let mut keys: Vec<Delta> = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| Delta {
+                bases: &bases,
+                block: (i / 64) as u32,
+                delta: (v - bases[i / 64]) as u32,
+            })
+            .collect();
+        keys.reverse();
+        keys.radix_sort_unstable();
+
+        let mut expected = values;
+        expected.sort_unstable();
+        let actual: Vec<u64> = keys
+            .iter()
+            .map(|k| k.bases[k.block as usize] + k.delta as u64)
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    pub fn test_nested_in_rayon() {
+        use rayon::prelude::*;
+
+        let mut inputs: Vec<Vec<u32>> = (0..8).map(|_| block_rand(1_000_000)).collect();
+        let mut expected = inputs.clone();
+        expected.iter_mut().for_each(|v| v.sort_unstable());
+
+        inputs.par_iter_mut().for_each(|v| v.radix_sort_unstable());
+
+        assert_eq!(inputs, expected);
+    }
+
+    #[test]
+    pub fn test_nested_in_install() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+
+        let inputs: Vec<u32> = block_rand(2_000_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        // Sorting within your own pool still uses every thread in it
+        let mut data = inputs.clone();
+        let trace = pool.install(|| data.radix_sort_builder().sort_with_trace());
+        assert_eq!(data, expected);
+        assert_eq!(trace[0].algorithm, Algorithm::Recombinating);
+
+        // Other than the scanning sort, whose workers would block the pool's other work
+        let mut data = inputs;
+        let trace = pool.install(|| {
+            data.radix_sort_builder()
+                .with_algorithm(Algorithm::Scanning)
+                .sort_with_trace()
+        });
+        assert_eq!(data, expected);
+        assert!(trace
+            .iter()
+            .all(|choice| choice.algorithm == Algorithm::Recombinating));
+    }
+
+    #[test]
+    pub fn test_sort_window() {
+        let algorithms = [
+            Algorithm::MtOop,
+            Algorithm::MtLsb,
+            Algorithm::Scanning,
+            Algorithm::Recombinating,
+            Algorithm::Comparative,
+            Algorithm::LrLsb,
+            Algorithm::Lsb,
+            Algorithm::Regions,
+            Algorithm::Ska,
+            Algorithm::Partition,
+        ];
+
+        // Small tiles, so the tiled algorithms split the window into many tiles even when the
+        // global pool has a single thread
+        struct WindowTuner(Algorithm);
+
+        impl Tuner for WindowTuner {
+            fn pick_algorithm(&self, _p: &TuningParams, _counts: &[usize]) -> Algorithm {
+                self.0
+            }
+
+            fn tile_params(&self) -> TileParams {
+                TileParams {
+                    min_tile_size: 1_000,
+                    tiles_per_thread: 8,
+                }
+            }
+        }
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+
+        let inputs: Vec<u32> = block_rand(1_000_000);
+
+        for (start, end) in [(0, 300_000), (123_457, 654_321), (700_001, 1_000_000)] {
+            let mut expected = inputs.clone();
+            expected[start..end].sort_unstable();
+
+            let mut data = inputs.clone();
+            data[start..end].radix_sort_unstable();
+            assert_eq!(data, expected);
+
+            for algorithm in algorithms {
+                // On the global pool, which is the only place the scanning sort runs, and nested
+                // in a pool of 4 threads, to split the window across several workers
+                for nested in [false, true] {
+                    let mut data = inputs.clone();
+                    let sort = |window: &mut [u32]| {
+                        window
+                            .radix_sort_builder()
+                            .with_tuner(&WindowTuner(algorithm))
+                            .sort()
+                    };
+
+                    if nested {
+                        pool.install(|| sort(&mut data[start..end]));
+                    } else {
+                        sort(&mut data[start..end]);
+                    }
+
+                    let context = (algorithm, nested);
+                    assert_eq!(data[..start], inputs[..start], "{:?}", context);
+                    assert_eq!(data[end..], inputs[end..], "{:?}", context);
+                    assert_eq!(data[start..end], expected[start..end], "{:?}", context);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_radix_sorted_copy() {
+        let inputs: Vec<u64> = block_rand(100_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        assert_eq!(inputs.radix_sorted_copy(), expected);
+        assert_eq!(inputs[..].radix_sorted_copy(), expected);
+        assert_ne!(inputs, expected);
+    }
+
+    #[test]
+    pub fn test_radix_sort_unstable_changed() {
+        let mut inputs: Vec<u64> = block_rand(100_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        assert!(inputs.radix_sort_unstable_changed());
+        assert_eq!(inputs, expected);
+        assert!(!inputs.radix_sort_unstable_changed());
+        assert!(!inputs[..].radix_sort_unstable_changed());
+
+        let mut empty: Vec<u32> = Vec::new();
+        assert!(!empty.radix_sort_unstable_changed());
+    }
+
+    #[test]
+    pub fn test_radix_sort_unstable_with() {
+        let inputs: Vec<u64> = block_rand(100_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        let tuner = SingleAlgoTuner {
+            algo: Algorithm::Lsb,
+        };
+
+        let configs = [
+            SortConfig::default(),
+            SortConfig::new().with_parallel(false),
+            SortConfig::new().with_single_threaded_tuner(),
+            SortConfig::new().with_low_mem_tuner(),
+            SortConfig::new().with_tuner(&tuner),
+            SortConfig::new().with_algorithm(Algorithm::Regions),
+            SortConfig::new().with_reused_buffer(true),
+            SortConfig::new()
+                .with_reused_buffer(true)
+                .with_parallel(false),
+        ];
+
+        for config in configs.iter() {
+            let mut data = inputs.clone();
+            data.radix_sort_unstable_with(config);
+            assert_eq!(data, expected);
+
+            let mut data = inputs.clone();
+            data[..].radix_sort_unstable_with(config);
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_radix_sort_with_bounds() {
+        let mut inputs: Vec<i64> = block_rand(100_000);
+        let min = *inputs.iter().min().unwrap();
+        let max = *inputs.iter().max().unwrap();
+
+        assert_eq!(inputs.radix_sort_with_bounds(), Some((min, max)));
+        assert!(inputs.windows(2).all(|w| w[0] <= w[1]));
+
+        let mut single = [7u8];
+        assert_eq!(single[..].radix_sort_with_bounds(), Some((7, 7)));
+
+        let mut empty: Vec<u32> = Vec::new();
+        assert_eq!(empty.radix_sort_with_bounds(), None);
+    }
+
+    #[test]
+    pub fn test_mut_vec_ref() {
+        struct Holder {
+            values: Vec<u32>,
+        }
+
+        fn sort_generic<S: RadixSort<u32> + ?Sized>(s: &mut S) {
+            s.radix_sort_unstable();
+        }
+
+        let inputs = block_rand::<u32>(10_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        // Methods resolve through auto-deref, so a borrowed field needs no re-slicing
+        let mut holder = Holder {
+            values: inputs.clone(),
+        };
+        let values: &mut Vec<u32> = &mut holder.values;
+        values.radix_sort_unstable();
+        assert_eq!(holder.values, expected);
+
+        let mut holder = Holder { values: inputs };
+        let values = &mut holder.values;
+        sort_generic(values);
+        assert_eq!(holder.values, expected);
+    }
+
+    #[test]
+    pub fn test_presorted() {
+        let noise = block_rand::<u64>(200_000);
+
+        // Sorted, with every 300th item backfilled with a random value
+        let inputs: Vec<u64> = (0..200_000u64)
+            .map(|i| {
+                if i % 300 == 0 {
+                    noise[i as usize]
+                } else {
+                    i << 20
+                }
+            })
+            .collect();
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        for threshold in [0.0, 0.001, 0.01, 0.5] {
+            let mut data = inputs.clone();
+            data.radix_sort_builder()
+                .with_presorted_threshold(threshold)
+                .sort();
+            assert_eq!(data, expected);
+        }
+
+        // Keys too wide to be compared as a single integer, where the last byte is the most
+        // significant
+        let as_key = |v: &u64| {
+            let mut key = [0u8; 20];
+            key[8..16].copy_from_slice(&v.to_le_bytes());
+            key
+        };
+        let mut data: Vec<[u8; 20]> = inputs.iter().map(as_key).collect();
+        let expected: Vec<[u8; 20]> = expected.iter().map(as_key).collect();
+
+        data.radix_sort_unstable();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    pub fn test_radix_sort_and_split() {
+        let inputs: Vec<i32> = block_rand::<i32>(100_000)
+            .into_iter()
+            .map(|v| v % 1_000)
+            .collect();
+
+        for threshold in [i32::MIN, -1_000, -999, -1, 0, 1, 500, 999, 1_000, i32::MAX] {
+            let mut data = inputs.clone();
+            let split = data.radix_sort_and_split(threshold);
+
+            assert!(data.windows(2).all(|w| w[0] <= w[1]));
+            assert!(data[..split].iter().all(|v| *v < threshold));
+            assert!(data[split..].iter().all(|v| *v >= threshold));
+
+            let mut data = inputs.clone();
+            assert_eq!(data[..].radix_sort_and_split(threshold), split);
+        }
+
+        let mut empty: Vec<u32> = Vec::new();
+        assert_eq!(empty.radix_sort_and_split(5), 0);
+    }
+
+    #[test]
+    pub fn test_deterministic() {
+        // Sorted by the top byte only, so most items share a key with many others and their order
+        // is up to the algorithm
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Tagged(u32);
+
+        impl RadixKey for Tagged {
+            const LEVELS: usize = 1;
+
+            fn get_level(&self, _level: usize) -> u8 {
+                (self.0 >> 24) as u8
+            }
+        }
+
+        let inputs: Vec<Tagged> = block_rand::<u32>(1_000_000)
+            .into_iter()
+            .map(Tagged)
+            .collect();
+
+        // Picks the scanning sort for every bucket, with enough tiles to split the work up even
+        // on a single thread
+        struct ScanningTuner;
+
+        impl Tuner for ScanningTuner {
+            fn pick_algorithm(&self, _p: &TuningParams, _counts: &[usize]) -> Algorithm {
+                Algorithm::Scanning
+            }
+
+            fn tile_params(&self) -> TileParams {
+                TileParams {
+                    min_tile_size: 1_000,
+                    tiles_per_thread: 16,
+                }
+            }
+        }
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+
+        let sort = |data: &mut Vec<Tagged>| {
+            let trace = data
+                .radix_sort_builder()
+                .with_tuner(&ScanningTuner)
+                .with_deterministic(true)
+                .sort_with_trace();
+
+            assert!(trace
+                .iter()
+                .all(|c| c.algorithm == Algorithm::Recombinating));
+            assert!(data.windows(2).all(|w| w[0].0 >> 24 <= w[1].0 >> 24));
+        };
+
+        let mut first = inputs.clone();
+        sort(&mut first);
+
+        // Both on the global pool, where only `with_deterministic()` keeps the scanning sort out,
+        // and nested in a pool of 4 threads, which run the tiles in parallel
+        for run in 0..6 {
+            let mut data = inputs.clone();
+
+            if run % 2 == 0 {
+                sort(&mut data);
+            } else {
+                pool.install(|| sort(&mut data));
+            }
+
+            assert_eq!(data, first, "run {}", run);
+        }
+    }
 }
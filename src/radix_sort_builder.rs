@@ -1,14 +1,22 @@
+use crate::histogram_cache::HistogramCache;
+use crate::radix_key::check_levels;
+use crate::radix_sort_error::RadixSortError;
+use crate::sort_config::SortConfig;
 use crate::sorter::Sorter;
-use crate::tuner::Tuner;
-use crate::tuners::SingleThreadedTuner;
-#[cfg(feature = "multi-threaded")]
-use crate::tuners::{LowMemoryTuner, StandardTuner};
-use crate::RadixKey;
+use crate::sorts::counting_sort::counting_sort;
+use crate::sorts::presorted_sort::{looks_presorted, presorted_sort_merge, presorted_sort_split};
+use crate::tuner::{
+    Algorithm, AlgorithmChoice, CountingParams, ScanningParams, TileParams, Tuner, TuningParams,
+};
+use crate::tuners::SingleAlgorithmTuner;
+use crate::utils::is_sorted_by_levels;
+use crate::{RadixKey, MAX_LEVELS};
+use std::sync::Mutex;
 
 pub struct RadixSortBuilder<'a, T> {
     data: &'a mut [T],
-    multi_threaded: bool,
-    tuner: &'a (dyn Tuner + Send + Sync),
+    levels: usize,
+    config: SortConfig<'a>,
 }
 
 impl<'a, T> RadixSortBuilder<'a, T>
@@ -16,19 +24,26 @@ where
     T: RadixKey + Copy + Send + Sync,
 {
     pub(crate) fn new(data: &'a mut [T]) -> Self {
+        check_levels::<T>();
+
+        Self::new_with_levels(data, T::LEVELS)
+    }
+
+    pub(crate) fn new_with_levels(data: &'a mut [T], levels: usize) -> Self {
         // TODO(nathan): Try to make this a compile-time assert
         // This is an invariant of RadixKey that must be upheld.
-        assert_ne!(T::LEVELS, 0, "RadixKey must have at least 1 level");
-
-        #[cfg(feature = "multi-threaded")]
-        let (tuner, multi_threaded) = (&StandardTuner, true);
-        #[cfg(not(feature = "multi-threaded"))]
-        let (tuner, multi_threaded) = (&SingleThreadedTuner, false);
+        assert_ne!(levels, 0, "RadixKey must have at least 1 level");
+        assert!(
+            levels <= MAX_LEVELS,
+            "RadixKey has {} levels, more than the maximum of {}",
+            levels,
+            MAX_LEVELS
+        );
 
         Self {
             data,
-            multi_threaded,
-            tuner,
+            levels,
+            config: SortConfig::default(),
         }
     }
 
@@ -50,7 +65,7 @@ where
     ///     .sort();
     /// ```
     pub fn with_parallel(mut self, parallel: bool) -> Self {
-        self.multi_threaded = parallel;
+        self.config = self.config.with_parallel(parallel);
 
         self
     }
@@ -71,7 +86,7 @@ where
     /// ```
     #[cfg(feature = "multi-threaded")]
     pub fn with_low_mem_tuner(mut self) -> Self {
-        self.tuner = &LowMemoryTuner;
+        self.config = self.config.with_low_mem_tuner();
 
         self
     }
@@ -93,7 +108,7 @@ where
     ///     .sort();
     /// ```
     pub fn with_single_threaded_tuner(mut self) -> Self {
-        self.tuner = &SingleThreadedTuner;
+        self.config = self.config.with_single_threaded_tuner();
 
         self
     }
@@ -125,7 +140,143 @@ where
     ///     .sort();
     /// ```
     pub fn with_tuner(mut self, tuner: &'a (dyn Tuner + Send + Sync)) -> Self {
-        self.tuner = tuner;
+        self.config = self.config.with_tuner(tuner);
+
+        self
+    }
+
+    /// `with_algorithm()` forces a single sorting algorithm to be used at every level, in place of
+    /// the tuner. Buckets of 128 items or fewer are still always sorted with
+    /// `Algorithm::Comparative`.
+    ///
+    /// This is mostly useful for benchmarking a specific algorithm, or for isolating an issue
+    /// with one. For general use, the default tuning will perform better.
+    ///
+    /// `Algorithm::Comparative` skips radix sorting entirely, which can be worthwhile for inputs
+    /// known to be tiny. It still compares items by their `RadixKey` levels, so for a type whose
+    /// `Ord` differs from its `RadixKey`, use `slice::sort_unstable()` instead.
+    ///
+    /// ```
+    /// use rdst::RadixSort;
+    /// use rdst::tuner::Algorithm;
+    ///
+    /// let mut data: Vec<usize> = vec![5, 22, 3, 7, 9];
+    ///
+    /// data
+    ///     .radix_sort_builder()
+    ///     .with_algorithm(Algorithm::Ska)
+    ///     .sort();
+    /// ```
+    pub fn with_algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.config = self.config.with_algorithm(algorithm);
+
+        self
+    }
+
+    /// `with_reused_buffer(bool)` controls whether the levels below the top share a single
+    /// temporary buffer the size of the whole input, rather than each bucket that needs one
+    /// allocating its own. This saves hundreds of small allocations on a typical sort, but means
+    /// the full-size buffer is held for the rest of the sort even when most buckets are sorted
+    /// in-place, so it is best avoided alongside `with_low_mem_tuner()`.
+    ///
+    /// Default: `false`
+    ///
+    /// ```
+    /// use rdst::RadixSort;
+    /// let mut data: Vec<usize> = vec![5, 22, 3, 7, 9];
+    ///
+    /// data
+    ///     .radix_sort_builder()
+    ///     .with_reused_buffer(true)
+    ///     .sort();
+    /// ```
+    pub fn with_reused_buffer(mut self, reuse_buffer: bool) -> Self {
+        self.config = self.config.with_reused_buffer(reuse_buffer);
+
+        self
+    }
+
+    /// `with_presorted_threshold(f64)` sets the largest fraction of items which may be out of
+    /// place for `sort()` to treat the input as mostly sorted. Mostly sorted input, such as data
+    /// appended to in order and occasionally backfilled, is sorted by pulling out just the items
+    /// which are out of order, radix sorting those, and merging them back in. This is far cheaper
+    /// than a full radix sort when only a few items are out of place.
+    ///
+    /// Whether the input is mostly sorted is estimated from a sample of adjacent pairs, so other
+    /// input costs next to nothing to rule out. If the sample is misleading and more items than
+    /// the threshold allows turn out to be out of place, the full radix sort is used after all,
+    /// at the cost of one extra pass over the data. Inputs shorter than 10,000 items, and sorts
+    /// with a custom tuner or a forced algorithm, always use the full radix sort.
+    ///
+    /// This is off by default, as sampling is wasted work for input that isn't mostly sorted. A
+    /// threshold of `0.05`, allowing up to 5% of items to be out of place, suits data which is
+    /// known to often be mostly sorted. A threshold of `0.0` turns this off again.
+    ///
+    /// Default: `0.0`
+    ///
+    /// ```
+    /// use rdst::RadixSort;
+    /// let mut data: Vec<u32> = (0..100_000).collect();
+    /// data[500] = 7;
+    /// data[90_000] = 12;
+    ///
+    /// data
+    ///     .radix_sort_builder()
+    ///     .with_presorted_threshold(0.05)
+    ///     .sort();
+    ///
+    /// assert!(data.windows(2).all(|w| w[0] <= w[1]));
+    /// ```
+    pub fn with_presorted_threshold(mut self, threshold: f64) -> Self {
+        self.config = self.config.with_presorted_threshold(threshold);
+
+        self
+    }
+
+    /// `with_deterministic(bool)` controls whether the output must be the same on every run for
+    /// the same input and number of threads, such as for golden-file tests. Every item with the
+    /// same key is always in the same place in any sorted output, but as the sort is unstable,
+    /// items with equal keys can otherwise end up in a different order from run to run.
+    ///
+    /// The only algorithm whose output depends on how the threads are scheduled is
+    /// `Algorithm::Scanning`, whose threads race to claim each bucket. With this, it is replaced
+    /// with `Algorithm::Recombinating`, which splits the work on fixed tile boundaries. This
+    /// applies to custom tuners and `with_algorithm()` too. The default tuner only picks the
+    /// scanning sort for the largest inputs, so for those this can be slightly slower, and needs a
+    /// temporary buffer the size of the input. The number of threads still changes the tile
+    /// boundaries, so the output is only reproducible with the same thread pool size.
+    ///
+    /// Default: `false`
+    ///
+    /// ```
+    /// use rdst::RadixSort;
+    /// let mut data: Vec<usize> = vec![5, 22, 3, 7, 9];
+    ///
+    /// data
+    ///     .radix_sort_builder()
+    ///     .with_deterministic(true)
+    ///     .sort();
+    /// ```
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.config = self.config.with_deterministic(deterministic);
+
+        self
+    }
+
+    /// `with_config()` replaces all of the options set so far with those from a `SortConfig`.
+    ///
+    /// ```
+    /// use rdst::{RadixSort, SortConfig};
+    /// let mut data: Vec<usize> = vec![5, 22, 3, 7, 9];
+    /// let config = SortConfig::new().with_parallel(false);
+    ///
+    /// data
+    ///     .radix_sort_builder()
+    ///     .with_config(&config)
+    ///     .sort();
+    /// ```
+    pub fn with_config(mut self, config: &SortConfig<'a>) -> Self {
+        self.config = *config;
 
         self
     }
@@ -133,6 +284,12 @@ where
     /// `sort()` runs the configured sorting algorithm and consumes the RadixSortBuilder to return
     /// your mutable vec / slice back to you.
     ///
+    /// When called from within a rayon worker thread (e.g. inside a `par_iter` or
+    /// `ThreadPool::install`), the sort still runs on that thread pool, sharing it with the
+    /// surrounding work. The scanning sort is the only exception, as its workers block waiting on
+    /// each other, so wherever it would be picked, even by a custom tuner or `with_algorithm()`,
+    /// the recombinating sort is used instead.
+    ///
     /// ```
     /// use rdst::RadixSort;
     /// let mut data: Vec<usize> = vec![5, 22, 3, 7, 9];
@@ -146,12 +303,427 @@ where
     /// data[0] = 123;
     /// ```
     pub fn sort(self) {
+        self.sort_with_counts(None);
+    }
+
+    /// `sort_with_cache()` behaves like `sort()`, but takes the counts of the top level from a
+    /// `HistogramCache` kept from the previous sort of the same slice, updated for the items at the
+    /// `changed` indices, rather than counting every item again. If nothing has changed, the sort is
+    /// skipped entirely as the data is still sorted.
+    ///
+    /// This is an advanced optimization for large, mostly unchanging data that is re-sorted often.
+    /// `changed` must include the index of every item modified since the last sort with this cache.
+    /// See `HistogramCache` for the details.
+    ///
+    /// ```
+    /// use rdst::{HistogramCache, RadixSort};
+    /// let mut data: Vec<usize> = vec![5, 22, 3, 7, 9];
+    /// let mut cache = HistogramCache::new();
+    ///
+    /// data.radix_sort_builder().sort_with_cache(&mut cache, &[]);
+    ///
+    /// data[0] = 12;
+    /// data.radix_sort_builder().sort_with_cache(&mut cache, &[0]);
+    /// ```
+    pub fn sort_with_cache(self, cache: &mut HistogramCache, changed: &[usize]) {
+        let level = self.levels - 1;
+        let counts = match cache.counts(self.data, level, changed) {
+            Some(counts) => counts,
+            None => return,
+        };
+
+        // Sorting only reorders the data, so the counts still hold afterwards
+        cache.store(self.data, level, counts);
+        self.sort_with_counts(Some(counts));
+    }
+
+    /// `try_sort()` behaves like `sort()`, but returns `RadixSortError::OutOfMemory` rather than
+    /// aborting if the temporary buffer can't be allocated. The data is left untouched in that
+    /// case.
+    ///
+    /// To make this possible, a single temporary buffer the size of the data is allocated up-front
+    /// and shared by every level, as with `with_reused_buffer(true)`. Algorithms which would
+    /// allocate their own buffers are swapped for ones which use the shared buffer or sort in
+    /// place, so this can be slower than `sort()` for large inputs. Small allocations which don't
+    /// grow with the data, such as the counts for each level, can still abort.
+    ///
+    /// ```
+    /// use rdst::{RadixSort, RadixSortError};
+    /// let mut data: Vec<usize> = vec![5, 22, 3, 7, 9];
+    ///
+    /// match data.radix_sort_builder().try_sort() {
+    ///     Ok(()) => assert_eq!(data, [3, 5, 7, 9, 22]),
+    ///     Err(RadixSortError::OutOfMemory) => eprintln!("shedding load"),
+    ///     Err(e) => panic!("{}", e),
+    /// }
+    /// ```
+    pub fn try_sort(self) -> Result<(), RadixSortError> {
+        // By definition, this is already sorted
+        if self.data.len() <= 1 {
+            return Ok(());
+        }
+
+        self.with_sorter(true, None, |sorter, data| {
+            sorter.try_top_level_director(data)
+        })
+    }
+
+    /// `sort_with_trace()` behaves like `sort()`, but also returns every decision made by the tuner
+    /// along the way, as the length and level of each bucket with the algorithm picked for it. This
+    /// is useful for checking whether the tuning suits your data, before deciding whether to
+    /// provide your own with `with_tuner()`.
+    ///
+    /// Only the tuner's decisions are recorded. Buckets of 128 items or fewer, which are always
+    /// sorted with `Algorithm::Comparative`, and levels which are skipped because they only hold one
+    /// distinct byte, don't appear in the trace. For multi-threaded
+    /// sorts the order of the trace depends on the order in which the threads reached each bucket.
+    ///
+    /// Recording takes a lock for each decision, so this is slower than `sort()`. Sorts run without
+    /// `sort_with_trace()` are not affected.
+    ///
+    /// ```
+    /// use rdst::RadixSort;
+    /// let mut data: Vec<u32> = (0..10_000).rev().collect();
+    ///
+    /// let trace = data.radix_sort_builder().sort_with_trace();
+    ///
+    /// for choice in trace {
+    ///     println!("{} items at level {}: {:?}", choice.input_len, choice.level, choice.algorithm);
+    /// }
+    /// ```
+    pub fn sort_with_trace(self) -> Vec<AlgorithmChoice> {
+        // By definition, this is already sorted
+        if self.data.len() <= 1 {
+            return Vec::new();
+        }
+
+        let trace = Mutex::new(Vec::new());
+        self.with_sorter(false, Some(&trace), |sorter, data| {
+            sorter.top_level_director(data, None)
+        });
+
+        trace.into_inner().unwrap()
+    }
+
+    fn sort_with_counts(mut self, counts: Option<[usize; 256]>) {
         // By definition, this is already sorted
         if self.data.len() <= 1 {
             return;
         }
 
-        let sorter = Sorter::new(self.multi_threaded, self.tuner);
-        sorter.top_level_director(self.data);
+        if counts.is_none() && self.presorted_sort() {
+            return;
+        }
+
+        if counts.is_none() && self.counting_sort() {
+            return;
+        }
+
+        self.with_sorter(false, None, |sorter, data| {
+            sorter.top_level_director(data, counts)
+        });
+    }
+
+    // Sorts mostly sorted data by pulling out the items which are out of order, sorting those and
+    // merging them back in. Returns false, leaving the data for the full sort, if the data isn't
+    // mostly sorted, or if the config rules this out.
+    fn presorted_sort(&mut self) -> bool {
+        let config = self.config;
+        let threshold = config.presorted_threshold;
+
+        if threshold <= 0.0
+            || config.custom_tuner
+            || config.algorithm.is_some()
+            || self.data.len() < 10_000
+            || !looks_presorted(self.data, self.levels, threshold)
+        {
+            return false;
+        }
+
+        // Each item out of place can pull out one other along with it
+        let max_pulled = (2.0 * threshold * self.data.len() as f64) as usize;
+        let (kept, mut pulled) = match presorted_sort_split(self.data, self.levels, max_pulled) {
+            Some(split) => split,
+            None => return false,
+        };
+
+        RadixSortBuilder::new_with_levels(&mut pulled, self.levels)
+            .with_config(&config.with_presorted_threshold(0.0))
+            .sort();
+        presorted_sort_merge(self.data, kept, &pulled, self.levels);
+
+        true
+    }
+
+    // Sorts `u16` and `i16` keys, which can be rebuilt from their 2 levels, by counting them. This
+    // is a single-threaded pass, so it only replaces sorts which aren't multi-threaded. Returns
+    // false, leaving the data for the full sort, if the input is too small for this to be faster,
+    // if the key can't be rebuilt, or if the config rules it out.
+    fn counting_sort(&mut self) -> bool {
+        let config = self.config;
+
+        if self.levels != 2
+            || config.multi_threaded
+            || config.algorithm.is_some()
+            || self.data.len() < config.tuner.counting_sort_threshold()
+        {
+            return false;
+        }
+
+        counting_sort(self.data)
+    }
+
+    // Runs `f` with a sorter set up from the config. With `fallible`, the tuner only picks
+    // algorithms which don't allocate their own temporary buffers. With `trace`, every algorithm
+    // the tuner picks is recorded into it.
+    fn with_sorter<R>(
+        self,
+        fallible: bool,
+        trace: Option<&Mutex<Vec<AlgorithmChoice>>>,
+        f: impl FnOnce(&Sorter<'_>, &mut [T]) -> R,
+    ) -> R {
+        let config = self.config;
+        let single_algorithm_tuner;
+        let tuner = match config.algorithm {
+            Some(algorithm) => {
+                single_algorithm_tuner = SingleAlgorithmTuner { algorithm };
+                &single_algorithm_tuner as &(dyn Tuner + Send + Sync)
+            }
+            None => config.tuner,
+        };
+
+        // Nested inside another rayon job, such as a `par_iter` or `ThreadPool::install`. The
+        // scanning sort blocks its workers waiting on each other, so it can't share the pool with
+        // other work, but every other algorithm only uses join and scope, which are safe to nest.
+        #[cfg(feature = "multi-threaded")]
+        let nested = rayon::current_thread_index().is_some();
+        #[cfg(not(feature = "multi-threaded"))]
+        let nested = false;
+
+        let no_scanning_tuner = NoScanningTuner { inner: tuner };
+        let tuner = if config.deterministic || nested {
+            &no_scanning_tuner as &(dyn Tuner + Send + Sync)
+        } else {
+            tuner
+        };
+
+        let fallible_tuner = FallibleTuner { inner: tuner };
+        let tuner = if fallible {
+            &fallible_tuner as &(dyn Tuner + Send + Sync)
+        } else {
+            tuner
+        };
+
+        let tracing_tuner;
+        let tuner = match trace {
+            Some(trace) => {
+                tracing_tuner = TracingTuner {
+                    inner: tuner,
+                    trace,
+                };
+                &tracing_tuner as &(dyn Tuner + Send + Sync)
+            }
+            None => tuner,
+        };
+
+        let sorter = Sorter::new(config.multi_threaded, tuner)
+            .with_levels(self.levels)
+            .with_reused_buffer(config.reuse_buffer);
+        f(&sorter, self.data)
+    }
+
+    /// `sort_changed()` behaves like `sort()`, but first checks whether the data is already sorted
+    /// by key. If it is, the sort is skipped entirely and `false` is returned, as nothing would have
+    /// moved. Otherwise the data is sorted and `true` is returned.
+    ///
+    /// The check stops at the first item found out of order, so for unsorted data it adds very
+    /// little to the cost of the sort. This is useful for change-detection, such as only
+    /// re-writing a file or invalidating a cache when its contents were actually reordered.
+    ///
+    /// ```
+    /// use rdst::RadixSort;
+    /// let mut data: Vec<usize> = vec![5, 22, 3, 7, 9];
+    ///
+    /// assert!(data.radix_sort_builder().sort_changed());
+    /// assert!(!data.radix_sort_builder().sort_changed());
+    /// ```
+    pub fn sort_changed(self) -> bool {
+        if is_sorted_by_levels(self.data, self.levels) {
+            return false;
+        }
+
+        self.sort();
+
+        true
+    }
+}
+
+// The tuner wrappers below each replace some of the algorithms picked by `inner`, but must pass
+// every other hook on the `Tuner` trait through to it unchanged, or a custom tuner's settings are
+// silently replaced by the defaults for the wrapped sorts.
+
+// Wraps the configured tuner for `try_sort()`, swapping out the algorithms which allocate their
+// own temporary buffers for ones which use the shared buffer or sort in place.
+struct FallibleTuner<'a> {
+    inner: &'a (dyn Tuner + Send + Sync),
+}
+
+impl<'a> Tuner for FallibleTuner<'a> {
+    #[inline]
+    fn pick_algorithm(&self, p: &TuningParams, counts: &[usize]) -> Algorithm {
+        match self.inner.pick_algorithm(p, counts) {
+            #[cfg(feature = "multi-threaded")]
+            Algorithm::MtLsb => Algorithm::Lsb,
+            #[cfg(feature = "multi-threaded")]
+            Algorithm::Scanning
+            | Algorithm::Recombinating
+            | Algorithm::Regions
+            | Algorithm::MtOop => Algorithm::Ska,
+            algorithm => algorithm,
+        }
+    }
+
+    #[inline]
+    fn counting_params(&self) -> CountingParams {
+        self.inner.counting_params()
+    }
+
+    #[inline]
+    fn scanning_params(&self) -> ScanningParams {
+        self.inner.scanning_params()
+    }
+
+    #[inline]
+    fn tile_params(&self) -> TileParams {
+        self.inner.tile_params()
+    }
+
+    #[inline]
+    fn min_task_size(&self) -> usize {
+        self.inner.min_task_size()
+    }
+
+    #[inline]
+    fn insertion_sort_threshold(&self) -> usize {
+        self.inner.insertion_sort_threshold()
+    }
+
+    #[inline]
+    fn counting_sort_threshold(&self) -> usize {
+        self.inner.counting_sort_threshold()
+    }
+
+    #[inline]
+    fn scanning_yield(&self) {
+        self.inner.scanning_yield()
+    }
+}
+
+// Wraps the configured tuner for `with_deterministic()`, or for sorts nested inside another rayon
+// job, swapping out the scanning sort, whose output depends on thread scheduling and whose workers
+// block on each other, for the recombinating sort, which splits the work the same way on every run.
+pub(crate) struct NoScanningTuner<'a> {
+    pub(crate) inner: &'a (dyn Tuner + Send + Sync),
+}
+
+impl<'a> Tuner for NoScanningTuner<'a> {
+    #[inline]
+    fn pick_algorithm(&self, p: &TuningParams, counts: &[usize]) -> Algorithm {
+        match self.inner.pick_algorithm(p, counts) {
+            #[cfg(feature = "multi-threaded")]
+            Algorithm::Scanning => Algorithm::Recombinating,
+            algorithm => algorithm,
+        }
+    }
+
+    #[inline]
+    fn counting_params(&self) -> CountingParams {
+        self.inner.counting_params()
+    }
+
+    #[inline]
+    fn scanning_params(&self) -> ScanningParams {
+        self.inner.scanning_params()
+    }
+
+    #[inline]
+    fn tile_params(&self) -> TileParams {
+        self.inner.tile_params()
+    }
+
+    #[inline]
+    fn min_task_size(&self) -> usize {
+        self.inner.min_task_size()
+    }
+
+    #[inline]
+    fn insertion_sort_threshold(&self) -> usize {
+        self.inner.insertion_sort_threshold()
+    }
+
+    #[inline]
+    fn counting_sort_threshold(&self) -> usize {
+        self.inner.counting_sort_threshold()
+    }
+
+    #[inline]
+    fn scanning_yield(&self) {
+        self.inner.scanning_yield()
+    }
+}
+
+// Passes every decision through to `inner`, recording each one into `trace`
+struct TracingTuner<'a> {
+    inner: &'a (dyn Tuner + Send + Sync),
+    trace: &'a Mutex<Vec<AlgorithmChoice>>,
+}
+
+impl<'a> Tuner for TracingTuner<'a> {
+    #[inline]
+    fn pick_algorithm(&self, p: &TuningParams, counts: &[usize]) -> Algorithm {
+        let algorithm = self.inner.pick_algorithm(p, counts);
+
+        self.trace.lock().unwrap().push(AlgorithmChoice {
+            input_len: p.input_len,
+            level: p.level,
+            algorithm,
+        });
+
+        algorithm
+    }
+
+    #[inline]
+    fn counting_params(&self) -> CountingParams {
+        self.inner.counting_params()
+    }
+
+    #[inline]
+    fn scanning_params(&self) -> ScanningParams {
+        self.inner.scanning_params()
+    }
+
+    #[inline]
+    fn tile_params(&self) -> TileParams {
+        self.inner.tile_params()
+    }
+
+    #[inline]
+    fn min_task_size(&self) -> usize {
+        self.inner.min_task_size()
+    }
+
+    #[inline]
+    fn insertion_sort_threshold(&self) -> usize {
+        self.inner.insertion_sort_threshold()
+    }
+
+    #[inline]
+    fn counting_sort_threshold(&self) -> usize {
+        self.inner.counting_sort_threshold()
+    }
+
+    #[inline]
+    fn scanning_yield(&self) {
+        self.inner.scanning_yield()
     }
 }
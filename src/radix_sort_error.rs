@@ -0,0 +1,31 @@
+use std::error::Error;
+use std::fmt;
+
+/// `RadixSortError` is returned by the fallible sorts, such as `try_radix_sort_unstable()`, when
+/// the sort couldn't be run. The data is left untouched in that case. It is also returned by
+/// `init_with_threads()` when the thread pool couldn't be set up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RadixSortError {
+    /// The temporary buffer, which holds as many items as the data being sorted, couldn't be
+    /// allocated.
+    OutOfMemory,
+    /// The global thread pool couldn't be built, usually because it was already running after an
+    /// earlier sort or call to `init()`.
+    ThreadPoolInit,
+}
+
+impl fmt::Display for RadixSortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RadixSortError::OutOfMemory => {
+                write!(f, "not enough memory for the radix sort temporary buffer")
+            }
+            RadixSortError::ThreadPoolInit => {
+                write!(f, "the global thread pool could not be initialized")
+            }
+        }
+    }
+}
+
+impl Error for RadixSortError {}
@@ -0,0 +1,161 @@
+use crate::radix_key::check_levels;
+#[cfg(feature = "multi-threaded")]
+use crate::radix_sort_builder::NoScanningTuner;
+use crate::sorter::Sorter;
+use crate::tuner::Tuner;
+#[cfg(feature = "multi-threaded")]
+use crate::tuners::DefaultTuner;
+#[cfg(not(feature = "multi-threaded"))]
+use crate::tuners::SingleThreadedTuner;
+use crate::RadixKey;
+use std::iter::FusedIterator;
+
+/// `RadixSortStream` yields a slice in sorted chunks, sorting each chunk only when it is
+/// requested. It is created by `RadixSort::radix_sort_streaming()`.
+///
+/// When the stream is created, the slice is sorted on its most significant level only, which
+/// splits it into up to 256 independent buckets. Each call to `next()` then fully sorts the next
+/// bucket in place and yields it, so consumers can start on the first chunk before the later
+/// ones have been sorted.
+///
+/// Ordering guarantees:
+///
+///  * Each chunk is fully sorted.
+///  * Chunks are yielded in ascending order, so every item in a chunk sorts before or equal to
+///    every item in any later chunk.
+///  * Together, the chunks cover the whole slice, in order, and no chunk is empty.
+///
+/// How many chunks there are depends on the data and the algorithm chosen for the top level.
+/// Small slices, or slices where a single algorithm sorts every level at once, are yielded as a
+/// single chunk. If the stream is dropped early, the chunks not yet yielded are left partially
+/// sorted.
+pub struct RadixSortStream<'a, T> {
+    sorter: Sorter<'static>,
+    remaining: &'a mut [T],
+    counts: [usize; 256],
+    bucket: usize,
+    // The level the remaining buckets still need sorting from, or `None` if `remaining` is
+    // already fully sorted
+    level: Option<usize>,
+    parent_len: usize,
+}
+
+impl<'a, T> RadixSortStream<'a, T>
+where
+    T: RadixKey + Copy + Send + Sync,
+{
+    pub(crate) fn new(data: &'a mut [T]) -> Self {
+        assert_ne!(T::LEVELS, 0, "RadixKey must have at least 1 level");
+        check_levels::<T>();
+
+        // Nested inside another rayon job, where the scanning sort's blocking workers can't share
+        // the pool, as for `RadixSortBuilder::sort()`
+        #[cfg(feature = "multi-threaded")]
+        let (multi_threaded, tuner) = match rayon::current_thread_index() {
+            Some(_) => (
+                true,
+                &NoScanningTuner {
+                    inner: &DefaultTuner {},
+                } as &(dyn Tuner + Send + Sync),
+            ),
+            None => (true, &DefaultTuner {} as &(dyn Tuner + Send + Sync)),
+        };
+        #[cfg(not(feature = "multi-threaded"))]
+        let (multi_threaded, tuner) = (false, &SingleThreadedTuner as &(dyn Tuner + Send + Sync));
+
+        let sorter = Sorter::new(multi_threaded, tuner);
+        let counts = sorter.top_level_only(data);
+
+        Self {
+            sorter,
+            parent_len: data.len(),
+            remaining: data,
+            counts: counts.unwrap_or([0; 256]),
+            bucket: 0,
+            level: counts.map(|_| T::LEVELS - 2),
+        }
+    }
+}
+
+impl<'a, T> Iterator for RadixSortStream<'a, T>
+where
+    T: RadixKey + Copy + Send + Sync,
+{
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+
+        let remaining: &'a mut [T] = std::mem::take(&mut self.remaining);
+        let level = match self.level {
+            Some(level) => level,
+            None => return Some(remaining),
+        };
+
+        while self.counts[self.bucket] == 0 {
+            self.bucket += 1;
+        }
+
+        let (chunk, rest) = remaining.split_at_mut(self.counts[self.bucket]);
+        self.remaining = rest;
+        self.bucket += 1;
+
+        self.sorter.bucket_director(chunk, level, self.parent_len);
+
+        Some(chunk)
+    }
+}
+
+impl<'a, T> FusedIterator for RadixSortStream<'a, T> where T: RadixKey + Copy + Send + Sync {}
+
+#[cfg(test)]
+mod tests {
+    use crate::RadixSort;
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_streaming() {
+        for n in [0, 1, 100, 10_000, 1_000_000] {
+            let mut inputs: Vec<u32> = block_rand(n);
+            let mut expected = inputs.clone();
+            expected.sort_unstable();
+
+            let mut streamed: Vec<u32> = Vec::with_capacity(n);
+            let mut prev_max: Option<u32> = None;
+
+            for chunk in inputs.radix_sort_streaming() {
+                assert!(!chunk.is_empty());
+                assert!(chunk.windows(2).all(|w| w[0] <= w[1]));
+                assert!(prev_max.is_none_or(|max| max <= chunk[0]));
+
+                prev_max = chunk.last().copied();
+                streamed.extend_from_slice(chunk);
+            }
+
+            assert_eq!(streamed, expected);
+            assert_eq!(inputs, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_streaming_chunks() {
+        // Large enough that the top level is split into buckets
+        let mut inputs: Vec<u64> = block_rand(1_000_000);
+
+        assert!(inputs.radix_sort_streaming().count() > 1);
+        assert!(inputs.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    pub fn test_streaming_single_level() {
+        let mut inputs: Vec<u8> = block_rand(100_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        let streamed: Vec<u8> = inputs.radix_sort_streaming().flatten().copied().collect();
+
+        assert_eq!(streamed, expected);
+    }
+}
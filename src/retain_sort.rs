@@ -0,0 +1,61 @@
+use crate::{RadixKey, RadixSort};
+
+/// radix_sort_retain sorts `data` and removes every item for which `keep` returns `false`, such as
+/// tombstones marked with a sentinel key, leaving only the kept items, in sorted order.
+///
+/// The items are filtered before they are sorted rather than after, which is the same single
+/// extra pass over the data as filtering during the last pass of the sort, but leaves fewer items
+/// to sort. As with `Vec::retain()`, `keep` is called exactly once for each item, in the original
+/// order.
+///
+/// ```
+/// use rdst::radix_sort_retain;
+///
+/// let mut data: Vec<u32> = vec![7, u32::MAX, 3, 9, u32::MAX, 1];
+/// radix_sort_retain(&mut data, |v| *v != u32::MAX);
+///
+/// assert_eq!(data, [1, 3, 7, 9]);
+/// ```
+pub fn radix_sort_retain<T, F>(data: &mut Vec<T>, keep: F)
+where
+    T: RadixKey + Copy + Send + Sync,
+    F: FnMut(&T) -> bool,
+{
+    data.retain(keep);
+    data.radix_sort_unstable();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::radix_sort_retain;
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_radix_sort_retain() {
+        for n in [0, 1, 2, 100, 100_000] {
+            let inputs: Vec<u32> = block_rand(n);
+
+            let mut expected: Vec<u32> = inputs.iter().copied().filter(|v| v % 3 != 0).collect();
+            expected.sort_unstable();
+
+            let mut data = inputs;
+            radix_sort_retain(&mut data, |v| v % 3 != 0);
+
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_retain_none() {
+        let mut data: Vec<u64> = block_rand(10_000);
+        let mut calls = 0;
+
+        radix_sort_retain(&mut data, |_| {
+            calls += 1;
+            false
+        });
+
+        assert!(data.is_empty());
+        assert_eq!(calls, 10_000);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::RadixKey;
+
+/// `SemverKey` wraps a software version encoded into a `u64` as
+/// `major << 40 | minor << 20 | patch`, which sorts in version order: by major, then minor, then
+/// patch. This gives `minor` and `patch` 20 bits each (up to `1_048_575`), and `major` the
+/// remaining 24 bits (up to `16_777_215`).
+///
+/// Pre-release and build metadata aren't part of the encoding. In debug builds, `new()` panics if
+/// a part doesn't fit in its bits, as it would otherwise spill into the part above it. Release
+/// builds skip the check.
+///
+/// ```
+/// use rdst::{RadixSort, SemverKey};
+///
+/// let mut versions = vec![
+///     SemverKey::new(1, 10, 0),
+///     SemverKey::new(1, 2, 3),
+///     SemverKey::new(0, 9, 12),
+/// ];
+/// versions.radix_sort_unstable();
+///
+/// let parts: Vec<(u32, u32, u32)> = versions.iter().map(|v| v.parts()).collect();
+/// assert_eq!(parts, [(0, 9, 12), (1, 2, 3), (1, 10, 0)]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct SemverKey(pub u64);
+
+const MINOR_SHIFT: u32 = 20;
+const MAJOR_SHIFT: u32 = 40;
+const PART_MASK: u64 = (1 << MINOR_SHIFT) - 1;
+
+impl SemverKey {
+    /// `new` encodes `major.minor.patch`.
+    #[inline]
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        debug_assert!(
+            (major as u64) < 1 << (64 - MAJOR_SHIFT),
+            "SemverKey: major {} doesn't fit in 24 bits",
+            major
+        );
+        debug_assert!(
+            (minor as u64) <= PART_MASK,
+            "SemverKey: minor {} doesn't fit in 20 bits",
+            minor
+        );
+        debug_assert!(
+            (patch as u64) <= PART_MASK,
+            "SemverKey: patch {} doesn't fit in 20 bits",
+            patch
+        );
+
+        Self((major as u64) << MAJOR_SHIFT | (minor as u64) << MINOR_SHIFT | patch as u64)
+    }
+
+    /// `major` decodes the major version.
+    #[inline]
+    pub fn major(&self) -> u32 {
+        (self.0 >> MAJOR_SHIFT) as u32
+    }
+
+    /// `minor` decodes the minor version.
+    #[inline]
+    pub fn minor(&self) -> u32 {
+        ((self.0 >> MINOR_SHIFT) & PART_MASK) as u32
+    }
+
+    /// `patch` decodes the patch version.
+    #[inline]
+    pub fn patch(&self) -> u32 {
+        (self.0 & PART_MASK) as u32
+    }
+
+    /// `parts` decodes the version as `(major, minor, patch)`.
+    #[inline]
+    pub fn parts(&self) -> (u32, u32, u32) {
+        (self.major(), self.minor(), self.patch())
+    }
+}
+
+impl From<(u32, u32, u32)> for SemverKey {
+    #[inline]
+    fn from((major, minor, patch): (u32, u32, u32)) -> Self {
+        Self::new(major, minor, patch)
+    }
+}
+
+impl RadixKey for SemverKey {
+    const LEVELS: usize = 8;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        self.0.get_level(level)
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        self.0.get_all_levels(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RadixSort, SemverKey};
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_round_trip() {
+        for parts in [(0, 0, 0), (1, 2, 3), (16_777_215, 1_048_575, 1_048_575)] {
+            assert_eq!(SemverKey::from(parts).parts(), parts);
+        }
+    }
+
+    #[test]
+    pub fn test_version_order() {
+        // Patch and minor at their largest, either side of rolling over into the part above
+        let mut versions: Vec<SemverKey> = vec![
+            (1, 3, 0),
+            (1, 2, 1_048_575),
+            (2, 0, 0),
+            (1, 1_048_575, 1_048_575),
+            (1, 2, 10),
+            (1, 2, 9),
+            (0, 10, 0),
+        ]
+        .into_iter()
+        .map(SemverKey::from)
+        .collect();
+        versions.radix_sort_unstable();
+
+        let parts: Vec<(u32, u32, u32)> = versions.iter().map(|v| v.parts()).collect();
+        assert_eq!(
+            parts,
+            [
+                (0, 10, 0),
+                (1, 2, 9),
+                (1, 2, 10),
+                (1, 2, 1_048_575),
+                (1, 3, 0),
+                (1, 1_048_575, 1_048_575),
+                (2, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_random_versions() {
+        let mut versions: Vec<SemverKey> = block_rand::<u64>(100_000)
+            .into_iter()
+            .map(|v| {
+                SemverKey::new(
+                    v as u32 % 50,
+                    (v >> 20) as u32 % 200,
+                    (v >> 40) as u32 % 3_000,
+                )
+            })
+            .collect();
+
+        let mut expected: Vec<(u32, u32, u32)> = versions.iter().map(|v| v.parts()).collect();
+        expected.sort_unstable();
+        versions.radix_sort_unstable();
+
+        let parts: Vec<(u32, u32, u32)> = versions.iter().map(|v| v.parts()).collect();
+        assert_eq!(parts, expected);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "SemverKey: patch 1048576 doesn't fit in 20 bits")]
+    pub fn test_patch_overflow() {
+        SemverKey::new(1, 2, 1 << 20);
+    }
+}
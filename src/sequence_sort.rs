@@ -0,0 +1,172 @@
+use crate::RadixKey;
+use std::cmp::Ordering;
+
+/// radix_sort_sequences sorts variable-length sequences of any `RadixKey` type, such as `&[u32]`,
+/// in lexicographic order by element, where a sequence sorts before any longer sequence it is a
+/// prefix of. For types whose `Ord` matches their `RadixKey`, such as the integer types, this is
+/// the same order as `Ord` for slices.
+///
+/// This is an MSD radix sort which works through the sequences one byte of one element at a time,
+/// starting with the most significant byte of the first element. Sequences which have run out of
+/// elements sort first, and are finished with. Unlike `radix_sort_smallvecs()`, sequences aren't
+/// padded to the length of the longest, so a few long sequences only cost extra passes over the
+/// buckets they end up in. Small buckets are finished with a comparison sort.
+///
+/// ```
+/// use rdst::radix_sort_sequences;
+///
+/// let sequences: Vec<Vec<u32>> = vec![vec![2, 1], vec![2], vec![1, 900, 3], vec![]];
+/// let mut data: Vec<&[u32]> = sequences.iter().map(|s| s.as_slice()).collect();
+/// radix_sort_sequences(&mut data);
+///
+/// assert_eq!(data, [&[][..], &[1, 900, 3], &[2], &[2, 1]]);
+/// ```
+pub fn radix_sort_sequences<T>(data: &mut [&[T]])
+where
+    T: RadixKey,
+{
+    assert_ne!(T::LEVELS, 0, "RadixKey must have at least 1 level");
+
+    if data.len() < 2 {
+        return;
+    }
+
+    let mut tmp = data.to_vec();
+
+    // Buckets still to be sorted, as (start, end, pos), where pos is the index of the byte to sort
+    // by, counting from the most significant byte of the first element. A stack rather than
+    // recursion, as long shared prefixes could otherwise run out of stack space.
+    let mut stack = vec![(0, data.len(), 0)];
+
+    while let Some((start, end, pos)) = stack.pop() {
+        let bucket = &mut data[start..end];
+
+        if bucket.len() <= 64 {
+            bucket.sort_unstable_by(|a, b| cmp_from(a, b, pos));
+            continue;
+        }
+
+        // Bucket 0 holds the sequences which have ended, and bucket b + 1 those with byte b
+        let mut counts = [0usize; 257];
+        for s in bucket.iter() {
+            counts[bucket_at(s, pos)] += 1;
+        }
+
+        if counts[0] == bucket.len() {
+            continue;
+        } else if counts[1..].contains(&bucket.len()) {
+            // Every sequence shares this byte, so there's nothing to move
+            stack.push((start, end, pos + 1));
+            continue;
+        }
+
+        let mut prefix_sums = [0usize; 257];
+        let mut running_total = 0;
+        for (sum, count) in prefix_sums.iter_mut().zip(counts.iter()) {
+            *sum = running_total;
+            running_total += count;
+        }
+
+        let tmp = &mut tmp[start..end];
+        for s in bucket.iter() {
+            let b = bucket_at(s, pos);
+            tmp[prefix_sums[b]] = *s;
+            prefix_sums[b] += 1;
+        }
+        bucket.copy_from_slice(tmp);
+
+        let mut offset = start + counts[0];
+        for count in counts[1..].iter() {
+            if *count > 1 {
+                stack.push((offset, offset + count, pos + 1));
+            }
+
+            offset += count;
+        }
+    }
+}
+
+// The bucket of `s` for the byte at `pos`, with 0 for a sequence which has already ended
+#[inline]
+fn bucket_at<T: RadixKey>(s: &[T], pos: usize) -> usize {
+    match s.get(pos / T::LEVELS) {
+        Some(v) => v.get_level(T::LEVELS - 1 - pos % T::LEVELS) as usize + 1,
+        None => 0,
+    }
+}
+
+// Compares two sequences from the byte at `pos` onwards, as every byte before it is equal
+#[inline]
+fn cmp_from<T: RadixKey>(a: &[T], b: &[T], mut pos: usize) -> Ordering {
+    loop {
+        let e = pos / T::LEVELS;
+
+        let (x, y) = match (a.get(e), b.get(e)) {
+            (Some(x), Some(y)) => (x, y),
+            (x, y) => return x.is_some().cmp(&y.is_some()),
+        };
+
+        for i in (pos % T::LEVELS)..T::LEVELS {
+            let level = T::LEVELS - 1 - i;
+            let ord = x.get_level(level).cmp(&y.get_level(level));
+
+            if ord != Ordering::Equal {
+                return ord;
+            }
+        }
+
+        pos = (e + 1) * T::LEVELS;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::radix_sort_sequences;
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_u16_sequences() {
+        let lens = block_rand::<u8>(100_000);
+        let values = block_rand::<u16>(100_000);
+
+        for n in [0, 1, 2, 64, 65, 1_000, 100_000] {
+            // A few values in both bytes, so that there are plenty of shared prefixes
+            let sequences: Vec<Vec<u16>> = (0..n)
+                .map(|i| {
+                    (0..lens[i] % 9)
+                        .map(|j| values[(i + j as usize * 7) % n] & 0x0301)
+                        .collect()
+                })
+                .collect();
+
+            let mut data: Vec<&[u16]> = sequences.iter().map(|s| s.as_slice()).collect();
+            let mut expected = data.clone();
+            expected.sort_unstable();
+            radix_sort_sequences(&mut data);
+
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_signed_sequences() {
+        let values = block_rand::<i32>(100_000);
+
+        // Long runs of equal elements, which must be descended through before the order differs
+        let sequences: Vec<Vec<i32>> = values
+            .chunks(10)
+            .map(|c| {
+                let mut s = vec![0; (c[0] as u32 % 200) as usize];
+                s.extend(c.iter().map(|v| v % 3));
+                s
+            })
+            .collect();
+
+        let mut data: Vec<&[i32]> = sequences.iter().map(|s| s.as_slice()).collect();
+        let mut expected = data.clone();
+        expected.sort_unstable();
+        radix_sort_sequences(&mut data);
+
+        assert_eq!(data, expected);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::tuner::{Algorithm, Tuner};
+#[cfg(feature = "multi-threaded")]
+use crate::tuners::LowMemoryTuner;
+use crate::tuners::{DefaultTuner, SingleThreadedTuner};
+
+/// `SortConfig` holds every option for a sort, to be passed to `radix_sort_unstable_with()`.
+/// This is useful when the same configuration is used for many sorts, or is decided separately
+/// from where the sorting happens. It provides the same options as `RadixSortBuilder`.
+///
+/// `SortConfig::default()` gives the same behavior as `radix_sort_unstable()`.
+///
+/// ```
+/// use rdst::{RadixSort, SortConfig};
+///
+/// let config = SortConfig::new()
+///     .with_single_threaded_tuner()
+///     .with_parallel(false);
+///
+/// let mut a: Vec<usize> = vec![5, 22, 3, 7, 9];
+/// let mut b: Vec<usize> = vec![10, 1, 7];
+/// a.radix_sort_unstable_with(&config);
+/// b.radix_sort_unstable_with(&config);
+///
+/// assert_eq!(a, [3, 5, 7, 9, 22]);
+/// assert_eq!(b, [1, 7, 10]);
+/// ```
+#[derive(Clone, Copy)]
+pub struct SortConfig<'a> {
+    /// Whether multiple algorithms may run in parallel on different threads.
+    /// Default: `true` with the `multi-threaded` feature, otherwise `false`
+    pub(crate) multi_threaded: bool,
+    /// The tuner which picks the algorithm for each level. Default: `DefaultTuner`
+    pub(crate) tuner: &'a (dyn Tuner + Send + Sync),
+    /// Whether `tuner` was provided with `with_tuner()`. The presorted fast path doesn't consult
+    /// the tuner, so it is skipped for custom tuners to leave every decision to them.
+    /// Default: `false`
+    pub(crate) custom_tuner: bool,
+    /// A single algorithm to use in place of the tuner. Default: `None`
+    pub(crate) algorithm: Option<Algorithm>,
+    /// Whether the levels below the top share one temporary buffer. Default: `false`
+    pub(crate) reuse_buffer: bool,
+    /// The fraction of items which may be out of place for the input to be sorted as mostly
+    /// sorted data, or `0.0` to never do so. Default: `0.0`
+    pub(crate) presorted_threshold: f64,
+    /// Whether only algorithms whose output doesn't depend on thread scheduling are used.
+    /// Default: `false`
+    pub(crate) deterministic: bool,
+}
+
+impl<'a> SortConfig<'a> {
+    /// `new()` creates a config with the default options, the same as `SortConfig::default()`.
+    pub fn new() -> Self {
+        Self {
+            multi_threaded: cfg!(feature = "multi-threaded"),
+            tuner: &DefaultTuner {},
+            custom_tuner: false,
+            algorithm: None,
+            reuse_buffer: false,
+            presorted_threshold: 0.0,
+            deterministic: false,
+        }
+    }
+
+    /// `with_parallel(bool)` controls whether or not multiple algorithms will be allowed to run
+    /// in parallel on different threads. See `RadixSortBuilder::with_parallel()`.
+    ///
+    /// Default: `true` with the `multi-threaded` feature, otherwise `false`
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.multi_threaded = parallel;
+
+        self
+    }
+
+    /// `with_low_mem_tuner()` uses a tuner which prefers algorithms that use less memory. See
+    /// `RadixSortBuilder::with_low_mem_tuner()`.
+    #[cfg(feature = "multi-threaded")]
+    pub fn with_low_mem_tuner(mut self) -> Self {
+        self.tuner = &LowMemoryTuner;
+        self.custom_tuner = false;
+        self.algorithm = None;
+
+        self
+    }
+
+    /// `with_single_threaded_tuner()` uses a tuner which only uses single-threaded algorithms. See
+    /// `RadixSortBuilder::with_single_threaded_tuner()`.
+    pub fn with_single_threaded_tuner(mut self) -> Self {
+        self.tuner = &SingleThreadedTuner;
+        self.custom_tuner = false;
+        self.algorithm = None;
+
+        self
+    }
+
+    /// `with_tuner()` uses your own tuner to pick the algorithm for each level. See
+    /// `RadixSortBuilder::with_tuner()`.
+    ///
+    /// Default: `DefaultTuner`
+    pub fn with_tuner(mut self, tuner: &'a (dyn Tuner + Send + Sync)) -> Self {
+        self.tuner = tuner;
+        self.custom_tuner = true;
+        self.algorithm = None;
+
+        self
+    }
+
+    /// `with_algorithm()` forces a single sorting algorithm to be used at every level, in place of
+    /// the tuner. See `RadixSortBuilder::with_algorithm()`.
+    ///
+    /// Default: none, the tuner picks the algorithm
+    pub fn with_algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = Some(algorithm);
+
+        self
+    }
+
+    /// `with_reused_buffer(bool)` controls whether the levels below the top share a single
+    /// temporary buffer, rather than each bucket allocating its own. See
+    /// `RadixSortBuilder::with_reused_buffer()`.
+    ///
+    /// Default: `false`
+    pub fn with_reused_buffer(mut self, reuse_buffer: bool) -> Self {
+        self.reuse_buffer = reuse_buffer;
+
+        self
+    }
+
+    /// `with_presorted_threshold(f64)` sets the largest fraction of items which may be out of
+    /// place for the input to be treated as mostly sorted. See
+    /// `RadixSortBuilder::with_presorted_threshold()`.
+    ///
+    /// Default: `0.0`
+    pub fn with_presorted_threshold(mut self, threshold: f64) -> Self {
+        self.presorted_threshold = threshold;
+
+        self
+    }
+
+    /// `with_deterministic(bool)` controls whether the output must be the same on every run for
+    /// the same input and number of threads, including the order of items with equal keys. See
+    /// `RadixSortBuilder::with_deterministic()`.
+    ///
+    /// Default: `false`
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+
+        self
+    }
+}
+
+impl<'a> Default for SortConfig<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
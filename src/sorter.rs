@@ -1,16 +1,23 @@
+use crate::radix_sort_error::RadixSortError;
 use crate::tuner::{Algorithm, Tuner, TuningParams};
 use crate::utils::*;
 use crate::RadixKey;
 use arbitrary_chunks::ArbitraryChunks;
 #[cfg(feature = "multi-threaded")]
 use rayon::current_num_threads;
-#[cfg(feature = "multi-threaded")]
-use rayon::prelude::*;
 use std::cmp::max;
 
 pub struct Sorter<'a> {
-    multi_threaded: bool,
+    pub(crate) multi_threaded: bool,
     pub(crate) tuner: &'a (dyn Tuner + Send + Sync),
+    // Overrides `RadixKey::LEVELS` for keys which only know their number of levels at runtime
+    levels: Option<usize>,
+    // Whether the levels below the top share one temporary buffer, rather than each bucket
+    // allocating its own
+    reuse_buffer: bool,
+    // Counts the tasks spawned for buckets, so tests can check how finely the work is split
+    #[cfg(all(test, feature = "multi-threaded"))]
+    pub(crate) spawned: std::sync::atomic::AtomicUsize,
 }
 
 impl<'a> Sorter<'a> {
@@ -18,10 +25,34 @@ impl<'a> Sorter<'a> {
         Self {
             multi_threaded,
             tuner,
+            levels: None,
+            reuse_buffer: false,
+            #[cfg(all(test, feature = "multi-threaded"))]
+            spawned: std::sync::atomic::AtomicUsize::new(0),
         }
     }
 
+    pub fn with_levels(mut self, levels: usize) -> Self {
+        self.levels = Some(levels);
+
+        self
+    }
+
+    pub fn with_reused_buffer(mut self, reuse_buffer: bool) -> Self {
+        self.reuse_buffer = reuse_buffer;
+
+        self
+    }
+
+    #[inline]
+    fn total_levels<T: RadixKey>(&self) -> usize {
+        self.levels.unwrap_or(T::LEVELS)
+    }
+
+    // Returns true when the chosen algorithm only sorted `level`, and the resulting buckets still
+    // need to be sorted on the levels below it.
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     fn run_sort<T>(
         &self,
         level: usize,
@@ -30,74 +61,144 @@ impl<'a> Sorter<'a> {
         tile_counts: Option<Vec<[usize; 256]>>,
         #[allow(unused)] tile_size: usize,
         algorithm: Algorithm,
-    ) where
+        tmp: Option<&mut [T]>,
+    ) -> bool
+    where
         T: RadixKey + Copy + Sized + Send + Sync,
     {
         #[allow(unused)]
         if let Some(tile_counts) = tile_counts {
             match algorithm {
                 #[cfg(feature = "multi-threaded")]
-                Algorithm::Scanning => self.scanning_sort_adapter(bucket, counts, level),
+                Algorithm::Scanning => {
+                    self.scanning_sort_adapter(bucket, counts, level);
+                    true
+                }
                 #[cfg(feature = "multi-threaded")]
                 Algorithm::Recombinating => {
-                    self.recombinating_sort_adapter(bucket, counts, &tile_counts, tile_size, level)
+                    self.recombinating_sort_adapter(bucket, counts, &tile_counts, tile_size, level);
+                    true
+                }
+                Algorithm::LrLsb => {
+                    self.lsb_sort_adapter_with_tmp(true, bucket, counts, 0, level, tmp);
+                    false
+                }
+                Algorithm::Lsb => {
+                    self.lsb_sort_adapter_with_tmp(false, bucket, counts, 0, level, tmp);
+                    false
+                }
+                Algorithm::Ska => {
+                    self.ska_sort_adapter(bucket, counts, level);
+                    true
+                }
+                Algorithm::Partition => {
+                    self.partition_sort_adapter(bucket, counts, level);
+                    true
+                }
+                Algorithm::Comparative => {
+                    self.comparative_sort(bucket, level);
+                    false
                 }
-                Algorithm::LrLsb => self.lsb_sort_adapter(true, bucket, counts, 0, level),
-                Algorithm::Lsb => self.lsb_sort_adapter(false, bucket, counts, 0, level),
-                Algorithm::Ska => self.ska_sort_adapter(bucket, counts, level),
-                Algorithm::Comparative => self.comparative_sort(bucket, level),
                 #[cfg(feature = "multi-threaded")]
                 Algorithm::Regions => {
-                    self.regions_sort_adapter(bucket, counts, &tile_counts, tile_size, level)
+                    self.regions_sort_adapter(bucket, counts, &tile_counts, tile_size, level);
+                    true
                 }
                 #[cfg(feature = "multi-threaded")]
                 Algorithm::MtOop => {
-                    self.mt_oop_sort_adapter(bucket, level, counts, &tile_counts, tile_size)
+                    self.mt_oop_sort_adapter(bucket, level, &tile_counts, tile_size);
+                    true
                 }
                 #[cfg(feature = "multi-threaded")]
-                Algorithm::MtLsb => self.mt_lsb_sort_adapter(bucket, 0, level, tile_size),
+                Algorithm::MtLsb => {
+                    self.mt_lsb_sort_adapter(bucket, 0, level, tile_size);
+                    false
+                }
             }
         } else {
             match algorithm {
                 #[cfg(feature = "multi-threaded")]
-                Algorithm::Scanning => self.scanning_sort_adapter(bucket, counts, level),
-                Algorithm::LrLsb => self.lsb_sort_adapter(true, bucket, counts, 0, level),
-                Algorithm::Lsb => self.lsb_sort_adapter(false, bucket, counts, 0, level),
-                Algorithm::Ska => self.ska_sort_adapter(bucket, counts, level),
-                Algorithm::Comparative => self.comparative_sort(bucket, level),
+                Algorithm::Scanning => {
+                    self.scanning_sort_adapter(bucket, counts, level);
+                    true
+                }
+                Algorithm::LrLsb => {
+                    self.lsb_sort_adapter_with_tmp(true, bucket, counts, 0, level, tmp);
+                    false
+                }
+                Algorithm::Lsb => {
+                    self.lsb_sort_adapter_with_tmp(false, bucket, counts, 0, level, tmp);
+                    false
+                }
+                Algorithm::Ska => {
+                    self.ska_sort_adapter(bucket, counts, level);
+                    true
+                }
+                Algorithm::Partition => {
+                    self.partition_sort_adapter(bucket, counts, level);
+                    true
+                }
+                Algorithm::Comparative => {
+                    self.comparative_sort(bucket, level);
+                    false
+                }
                 #[cfg(feature = "multi-threaded")]
                 e => panic!("Bad algorithm: {:?} for len: {}", e, bucket.len()),
             }
         }
     }
 
+    // Sorts `chunk` on `level`. If the levels below still need sorting, the counts for this level
+    // are returned so the caller can queue up the resulting buckets for the next level. `tmp` can
+    // provide a temporary buffer the same length as `chunk` for the out-of-place sorts to use.
+    #[inline]
     fn handle_chunk<T>(
         &self,
         chunk: &mut [T],
         level: usize,
         parent_len: Option<usize>,
         threads: usize,
-    ) where
+        tmp: Option<&mut [T]>,
+    ) -> Option<[usize; 256]>
+    where
+        T: RadixKey + Sized + Send + Copy + Sync,
+    {
+        self.handle_chunk_with_counts(chunk, level, parent_len, threads, None, tmp)
+    }
+
+    // As `handle_chunk`, but `known_counts` can provide the counts for `level` if they are
+    // already known, to skip counting. Tile counts are then only counted if the chosen algorithm
+    // needs them.
+    fn handle_chunk_with_counts<T>(
+        &self,
+        chunk: &mut [T],
+        level: usize,
+        parent_len: Option<usize>,
+        threads: usize,
+        known_counts: Option<[usize; 256]>,
+        tmp: Option<&mut [T]>,
+    ) -> Option<[usize; 256]>
+    where
         T: RadixKey + Sized + Send + Copy + Sync,
     {
         if chunk.len() <= 1 {
-            return;
+            return None;
         } else if chunk.len() <= 128 {
             self.comparative_sort(chunk, level);
-            return;
+            return None;
         }
 
         let use_tiles =
             cfg!(feature = "multi-threaded") && self.multi_threaded && chunk.len() >= 260_000;
         let tile_size = if use_tiles {
-            max(30_000, cdiv(chunk.len(), threads))
+            self.tile_size(chunk.len(), threads)
         } else {
             chunk.len()
         };
         let tp = TuningParams {
             threads,
             level,
-            total_levels: T::LEVELS,
+            total_levels: self.total_levels::<T>(),
             input_len: chunk.len(),
             parent_len,
         };
@@ -105,13 +206,15 @@ impl<'a> Sorter<'a> {
         let mut tile_counts: Option<Vec<[usize; 256]>> = None;
         let mut already_sorted = false;
 
-        if use_tiles {
-            let (tc, s) = get_tile_counts(chunk, tile_size, level);
+        if use_tiles && known_counts.is_none() {
+            let (tc, s) = get_tile_counts(chunk, tile_size, level, &self.tuner.counting_params());
             tile_counts = Some(tc);
             already_sorted = s;
         }
 
-        let counts = if let Some(tile_counts) = &tile_counts {
+        let counts = if let Some(counts) = known_counts {
+            counts
+        } else if let Some(tile_counts) = &tile_counts {
             aggregate_tile_counts(tile_counts)
         } else {
             let (counts, s) = get_counts(chunk, level);
@@ -119,13 +222,13 @@ impl<'a> Sorter<'a> {
 
             counts
         };
+        let samples = LevelSamples::record(chunk, level);
 
+        // A level where every item shares the same byte is skipped. Smaller chunks are left to the
+        // tuner, as the LSB sorts it picks for them already skip constant levels, and sorting all
+        // of the remaining levels in one go is cheaper than descending one level at a time.
         if already_sorted || (chunk.len() >= 30_000 && is_homogenous_bucket(&counts)) {
-            if level != 0 {
-                self.director(chunk, &counts, level - 1);
-            }
-
-            return;
+            return if level != 0 { Some(counts) } else { None };
         }
 
         let algorithm = self.tuner.pick_algorithm(&tp, &counts);
@@ -137,7 +240,14 @@ impl<'a> Sorter<'a> {
                 Algorithm::MtOop
                 | Algorithm::MtLsb
                 | Algorithm::Recombinating
-                | Algorithm::Regions => Some(vec![counts]),
+                | Algorithm::Regions => {
+                    if use_tiles {
+                        let params = self.tuner.counting_params();
+                        Some(get_tile_counts(chunk, tile_size, level, &params).0)
+                    } else {
+                        Some(vec![counts])
+                    }
+                }
                 _ => None,
             };
         }
@@ -145,63 +255,250 @@ impl<'a> Sorter<'a> {
         #[cfg(feature = "work_profiles")]
         println!("({}) PAR: {:?}", level, algorithm);
 
-        self.run_sort(level, chunk, &counts, tile_counts, tile_size, algorithm);
+        samples.check(chunk);
+        let descend = self.run_sort(
+            level,
+            chunk,
+            &counts,
+            tile_counts,
+            tile_size,
+            algorithm,
+            tmp,
+        );
+
+        if descend && level != 0 {
+            Some(counts)
+        } else {
+            None
+        }
     }
 
+    // The size of the tiles `len` items are split into across `threads` threads, as given by the
+    // tuner's `tile_params()`.
     #[inline]
-    pub fn top_level_director<T>(&self, bucket: &mut [T])
-    where
-        T: RadixKey + Sized + Send + Copy + Sync,
-    {
+    pub(crate) fn tile_size(&self, len: usize, threads: usize) -> usize {
+        let params = self.tuner.tile_params();
+
+        max(
+            params.min_tile_size.max(1),
+            cdiv(len, threads * params.tiles_per_thread.max(1)),
+        )
+    }
+
+    #[inline]
+    fn threads(&self) -> usize {
         #[cfg(feature = "multi-threaded")]
         let threads = current_num_threads();
 
         #[cfg(not(feature = "multi-threaded"))]
         let threads = 1;
 
-        let level = T::LEVELS - 1;
+        threads
+    }
 
-        self.handle_chunk(bucket, level, None, threads);
+    // `known_counts` can provide the counts of the top level if they are already known.
+    #[inline]
+    pub fn top_level_director<T>(&self, bucket: &mut [T], known_counts: Option<[usize; 256]>)
+    where
+        T: RadixKey + Sized + Send + Copy + Sync,
+    {
+        let level = self.total_levels::<T>() - 1;
+
+        if let Some(counts) =
+            self.handle_chunk_with_counts(bucket, level, None, self.threads(), known_counts, None)
+        {
+            self.director(bucket, &counts, level - 1);
+        }
     }
 
+    // As `top_level_director`, but the only temporary buffer is allocated fallibly up-front and
+    // shared by every level. The tuner must only pick algorithms which use that buffer, or which
+    // sort in place.
     #[inline]
-    #[cfg(feature = "multi-threaded")]
-    pub fn multi_threaded_director<T>(&self, bucket: &mut [T], counts: &[usize; 256], level: usize)
+    pub fn try_top_level_director<T>(&self, bucket: &mut [T]) -> Result<(), RadixSortError>
     where
+        T: RadixKey + Sized + Send + Copy + Sync,
+    {
+        let level = self.total_levels::<T>() - 1;
+        let mut tmp_bucket = try_get_tmp_bucket(bucket).map_err(|_| RadixSortError::OutOfMemory)?;
+
+        if let Some(counts) = self.handle_chunk_with_counts(
+            bucket,
+            level,
+            None,
+            self.threads(),
+            None,
+            Some(&mut tmp_bucket),
+        ) {
+            self.director_with_tmp(bucket, &counts, level - 1, Some(&mut tmp_bucket));
+        }
+
+        Ok(())
+    }
+
+    // Sorts only the top level of `bucket`. If the resulting buckets still need sorting on the
+    // levels below, the counts of the top level are returned.
+    #[inline]
+    pub fn top_level_only<T>(&self, bucket: &mut [T]) -> Option<[usize; 256]>
+    where
+        T: RadixKey + Sized + Send + Copy + Sync,
+    {
+        let level = self.total_levels::<T>() - 1;
+
+        self.handle_chunk(bucket, level, None, self.threads(), None)
+    }
+
+    // Fully sorts a `bucket` of `parent_len` that is already sorted on the levels above `level`.
+    #[inline]
+    pub fn bucket_director<T>(&self, bucket: &mut [T], level: usize, parent_len: usize)
+    where
+        T: RadixKey + Sized + Send + Copy + Sync,
+    {
+        let threads = self.threads();
+
+        if let Some(counts) = self.handle_chunk(bucket, level, Some(parent_len), threads, None) {
+            self.director(bucket, &counts, level - 1);
+        }
+    }
+
+    // Each bucket that needs sorting on the next level is spawned as a separate task, rather than
+    // recursing into the next level directly. This keeps stack usage flat for keys with many levels
+    // and lets idle threads steal buckets from anywhere in the tree.
+    #[cfg(feature = "multi-threaded")]
+    fn spawn_chunks<'scope, T>(
+        &'scope self,
+        scope: &rayon::Scope<'scope>,
+        bucket: &'scope mut [T],
+        counts: &[usize; 256],
+        level: usize,
+        threads: usize,
+        tmp: Option<&'scope mut [T]>,
+    ) where
         T: RadixKey + Send + Copy + Sync,
     {
         let parent_len = Some(bucket.len());
+        let min_task_size = self.tuner.min_task_size().max(128);
+        let mut tmp_chunks = tmp.map(|tmp| tmp.arbitrary_chunks_mut(counts));
+
+        for chunk in bucket.arbitrary_chunks_mut(counts) {
+            let mut tmp = tmp_chunks.as_mut().and_then(|t| t.next());
+
+            if chunk.len() <= min_task_size {
+                // Small chunks are cheaper to sort directly than to spawn, along with every bucket
+                // below them
+                let counts =
+                    self.handle_chunk(chunk, level, parent_len, threads, tmp.as_deref_mut());
+
+                if let Some(counts) = counts {
+                    self.single_threaded_director(chunk, &counts, level - 1, tmp);
+                }
+
+                continue;
+            }
+
+            #[cfg(feature = "work_profiles")]
+            println!("({}) SPAWN: {}", level, chunk.len());
+
+            #[cfg(test)]
+            self.spawned
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+            scope.spawn(move |scope| {
+                let counts =
+                    self.handle_chunk(chunk, level, parent_len, threads, tmp.as_deref_mut());
+
+                if let Some(counts) = counts {
+                    self.spawn_chunks(scope, chunk, &counts, level - 1, threads, tmp);
+                }
+            });
+        }
+    }
+
+    #[inline]
+    #[cfg(feature = "multi-threaded")]
+    pub fn multi_threaded_director<T>(
+        &self,
+        bucket: &mut [T],
+        counts: &[usize; 256],
+        level: usize,
+        tmp: Option<&mut [T]>,
+    ) where
+        T: RadixKey + Send + Copy + Sync,
+    {
         let threads = current_num_threads();
 
-        bucket
-            .arbitrary_chunks_mut(counts)
-            .par_bridge()
-            .for_each(|chunk| self.handle_chunk(chunk, level, parent_len, threads));
+        rayon::scope(|scope| self.spawn_chunks(scope, bucket, counts, level, threads, tmp));
     }
 
     #[inline]
-    pub fn single_threaded_director<T>(&self, bucket: &mut [T], counts: &[usize; 256], level: usize)
-    where
+    pub fn single_threaded_director<T>(
+        &self,
+        bucket: &mut [T],
+        counts: &[usize; 256],
+        level: usize,
+        tmp: Option<&mut [T]>,
+    ) where
         T: RadixKey + Send + Sync + Copy,
     {
-        let parent_len = Some(bucket.len());
         let threads = 1;
 
-        bucket
-            .arbitrary_chunks_mut(counts)
-            .for_each(|chunk| self.handle_chunk(chunk, level, parent_len, threads));
+        // Buckets still to be sorted, along with the counts and level they were last sorted on,
+        // and their part of the shared temporary buffer if there is one. Working through an
+        // explicit stack rather than recursing keeps stack usage flat no matter how many levels
+        // the key has.
+        let mut pending = vec![(bucket, *counts, level, tmp)];
+
+        while let Some((bucket, counts, level, tmp)) = pending.pop() {
+            let parent_len = Some(bucket.len());
+            let mut tmp_chunks = tmp.map(|tmp| tmp.arbitrary_chunks_mut(&counts));
+
+            for chunk in bucket.arbitrary_chunks_mut(&counts) {
+                let mut tmp = tmp_chunks.as_mut().and_then(|t| t.next());
+
+                let counts =
+                    self.handle_chunk(chunk, level, parent_len, threads, tmp.as_deref_mut());
+
+                if let Some(counts) = counts {
+                    pending.push((chunk, counts, level - 1, tmp));
+                }
+            }
+        }
     }
 
+    // Sorts every bucket of `bucket`, as given by `counts`, on `level` and below. With
+    // `reuse_buffer`, a single temporary buffer is allocated up-front and split alongside the
+    // buckets, rather than each bucket allocating its own.
     #[inline]
     pub fn director<T>(&self, bucket: &mut [T], counts: &[usize; 256], level: usize)
     where
         T: RadixKey + Send + Sync + Copy,
+    {
+        let mut tmp_bucket = if self.reuse_buffer {
+            Some(get_tmp_bucket(bucket))
+        } else {
+            None
+        };
+
+        self.director_with_tmp(bucket, counts, level, tmp_bucket.as_deref_mut());
+    }
+
+    // As `director`, but `tmp` can provide the temporary buffer shared by every bucket, which must
+    // be the same length as `bucket`.
+    #[inline]
+    fn director_with_tmp<T>(
+        &self,
+        bucket: &mut [T],
+        counts: &[usize; 256],
+        level: usize,
+        tmp: Option<&mut [T]>,
+    ) where
+        T: RadixKey + Send + Sync + Copy,
     {
         if cfg!(feature = "multi-threaded") && self.multi_threaded {
             #[cfg(feature = "multi-threaded")]
-            self.multi_threaded_director(bucket, counts, level);
+            self.multi_threaded_director(bucket, counts, level, tmp);
         } else {
-            self.single_threaded_director(bucket, counts, level);
+            self.single_threaded_director(bucket, counts, level, tmp);
         }
     }
 }
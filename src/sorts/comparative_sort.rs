@@ -20,8 +20,14 @@
 //! This is even slower than a typical comparison sort and so is only used as a fallback for very
 //! small inputs. However for those very small inputs it provides a significant speed-up due to
 //! having essentially no overhead (from count arrays, buffers etc.) compared to a radix sort.
+//!
+//! The smallest buckets of keys with up to 16 levels, up to `Tuner::insertion_sort_threshold()`,
+//! use a plain insertion sort instead, comparing each key as a single integer. This has even less
+//! overhead than `sort_unstable_by()`, and these buckets are very common at the bottom of an MSB
+//! sort, so it adds up.
 
 use crate::sorter::Sorter;
+use crate::utils::levels_as_u128;
 use crate::RadixKey;
 use std::cmp::Ordering;
 
@@ -34,31 +40,66 @@ impl<'a> Sorter<'a> {
             return;
         }
 
-        bucket.sort_unstable_by(|a, b| -> Ordering {
-            let mut level = start_level;
-            loop {
-                let cmp = a.get_level(level).cmp(&b.get_level(level));
+        if T::LEVELS <= 16 && bucket.len() <= self.tuner.insertion_sort_threshold() {
+            insertion_sort(bucket, start_level);
+            return;
+        }
 
-                if level != 0 && cmp == Ordering::Equal {
-                    level -= 1;
-                    continue;
-                }
+        bucket.sort_unstable_by(|a, b| cmp_from_level(a, b, start_level));
+    }
+}
 
-                return cmp;
-            }
-        });
+// Only used for keys of up to 16 levels, which are compared as a single integer, masked to the
+// levels being sorted. Comparing level by level is slow enough that `sort_unstable_by()` is faster.
+#[inline]
+fn insertion_sort<T>(bucket: &mut [T], start_level: usize)
+where
+    T: RadixKey + Copy,
+{
+    debug_assert!(T::LEVELS <= 16);
+    let mask = u128::MAX >> (127 - (start_level * 8 + 7));
+
+    for i in 1..bucket.len() {
+        let item = bucket[i];
+        let key = levels_as_u128(&item) & mask;
+        let mut j = i;
+
+        while j > 0 && key < levels_as_u128(&bucket[j - 1]) & mask {
+            bucket[j] = bucket[j - 1];
+            j -= 1;
+        }
+
+        bucket[j] = item;
+    }
+}
+
+// Compares `a` and `b` from `start_level` down to level 0, as the levels above it are equal
+#[inline]
+fn cmp_from_level<T: RadixKey>(a: &T, b: &T, start_level: usize) -> Ordering {
+    let mut level = start_level;
+    loop {
+        let cmp = a.get_level(level).cmp(&b.get_level(level));
+
+        if level != 0 && cmp == Ordering::Equal {
+            level -= 1;
+            continue;
+        }
+
+        return cmp;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::sorter::Sorter;
+    use crate::sorts::comparative_sort::insertion_sort;
     use crate::tuner::Algorithm;
     use crate::tuners::StandardTuner;
     use crate::utils::test_utils::{
         sort_comparison_suite, sort_single_algorithm, validate_u32_patterns, NumericTest,
     };
-    use crate::RadixKey;
+    use crate::{RadixKey, RadixSort};
+    use block_pseudorand::block_rand;
 
     fn test_comparative_sort_adapter<T>(shift: T)
     where
@@ -114,4 +155,43 @@ mod tests {
             sorter.comparative_sort(inputs, u32::LEVELS - 1);
         });
     }
+
+    #[test]
+    pub fn test_cutoff() {
+        // Either side of the 128 item cutoff, below which every algorithm falls back to this one
+        for len in 120..=136 {
+            let inputs: Vec<u64> = block_rand(len);
+            let mut expected = inputs.clone();
+            expected.sort_unstable();
+
+            for algorithm in [Algorithm::Comparative, Algorithm::Lsb, Algorithm::Ska] {
+                let mut data = inputs.clone();
+                data.radix_sort_builder().with_algorithm(algorithm).sort();
+
+                assert_eq!(data, expected, "{:?} with {} items", algorithm, len);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_insertion_sort() {
+        // Either side of the default threshold, with the top level shared by every item so only
+        // the levels below it are compared
+        for len in 0..=40 {
+            let inputs: Vec<u32> = block_rand::<u32>(len)
+                .into_iter()
+                .map(|v| (v & 0x00FF_FFFF) | 0x0700_0000)
+                .collect();
+            let mut expected = inputs.clone();
+            expected.sort_unstable();
+
+            let mut data = inputs.clone();
+            insertion_sort(&mut data, u32::LEVELS - 2);
+            assert_eq!(data, expected, "insertion_sort with {} items", len);
+
+            let mut data = inputs;
+            Sorter::new(true, &StandardTuner).comparative_sort(&mut data, u32::LEVELS - 2);
+            assert_eq!(data, expected, "comparative_sort with {} items", len);
+        }
+    }
 }
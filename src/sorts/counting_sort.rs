@@ -0,0 +1,133 @@
+//! `counting_sort` sorts `u16` and `i16`, the keys which can be rebuilt from their levels, without
+//! moving any items. Both levels together are counted into 65536 buckets, and the slice is then
+//! overwritten with as many copies of each key as were counted, rebuilt from the bucket index.
+//!
+//! ## Characteristics
+//!
+//!  * in-place
+//!  * unstable, though every item with the same key is identical
+//!  * single-threaded
+//!
+//! ## Performance
+//!
+//! This is one pass to count and one sequential pass to write, where `lsb_sort` would count and
+//! scatter once for each level. Scattering into 65536 buckets in a single pass instead was
+//! benchmarked as slower than either, as it writes to too many places at once to stay in the
+//! cache. The counts take 512KiB though, so this is only worth it once the input is large enough
+//! to outweigh clearing and reading them, as set by `Tuner::counting_sort_threshold()`.
+
+use crate::RadixKey;
+use std::any::type_name;
+use std::mem::{size_of, transmute_copy};
+
+// Returns a function to rebuild a `T` from both of its levels, as `level 1 << 8 | level 0`, if `T`
+// is `u16` or `i16`. Other keys may have levels which are only part of the item, so can't be
+// rebuilt. The sorts don't require `T: 'static`, which rules out `TypeId`, but the name of a
+// primitive is never shared with any other type, as those are always qualified by their path.
+#[inline]
+fn rebuild_fn<T>() -> Option<fn(u16) -> T> {
+    if size_of::<T>() != 2 {
+        return None;
+    }
+
+    match type_name::<T>() {
+        // Safety: `T` is `u16`
+        "u16" => Some(|key| unsafe { transmute_copy(&key) }),
+        // Safety: `T` is `i16`, whose levels are those of the key with its sign bit flipped
+        "i16" => Some(|key| unsafe { transmute_copy(&(key as i16 ^ i16::MIN)) }),
+        _ => None,
+    }
+}
+
+#[inline]
+fn wide_level<T: RadixKey>(item: &T) -> usize {
+    (item.get_level(1) as usize) << 8 | item.get_level(0) as usize
+}
+
+/// `counting_sort` sorts `bucket` by levels `1` and `0` at once. Returns false without sorting
+/// if `T` can't be rebuilt from its levels.
+pub(crate) fn counting_sort<T>(bucket: &mut [T]) -> bool
+where
+    T: RadixKey + Copy,
+{
+    let rebuild = match rebuild_fn::<T>() {
+        Some(rebuild) => rebuild,
+        None => return false,
+    };
+
+    let mut counts = vec![0usize; 1 << 16];
+    for item in bucket.iter() {
+        counts[wide_level(item)] += 1;
+    }
+
+    let mut start = 0;
+    for (key, count) in counts.into_iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+
+        bucket[start..start + count].fill(rebuild(key as u16));
+        start += count;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sorts::counting_sort::counting_sort;
+    use crate::utils::test_utils::sort_comparison_suite;
+    use crate::RadixKey;
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_u16() {
+        sort_comparison_suite(8u16, |inputs| assert!(counting_sort(inputs)));
+    }
+
+    #[test]
+    pub fn test_i16() {
+        let mut inputs = block_rand::<i16>(1_000_000);
+        inputs.extend([i16::MIN, i16::MAX, 0, -1]);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        assert!(counting_sort(&mut inputs));
+        assert_eq!(inputs, expected);
+    }
+
+    #[test]
+    pub fn test_not_rebuildable() {
+        // The levels are only part of the item, so it can't be rebuilt from them
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Low(u32);
+
+        impl RadixKey for Low {
+            const LEVELS: usize = 2;
+
+            fn get_level(&self, level: usize) -> u8 {
+                self.0.get_level(level)
+            }
+        }
+
+        let mut data = vec![Low(0x0001_0002), Low(0x0002_0001)];
+        assert!(!counting_sort(&mut data));
+        assert_eq!(data, [Low(0x0001_0002), Low(0x0002_0001)]);
+
+        // The same size as a `u16`, but only `u16` and `i16` themselves are rebuilt
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Wrapped(u16);
+
+        impl RadixKey for Wrapped {
+            const LEVELS: usize = 2;
+
+            fn get_level(&self, level: usize) -> u8 {
+                self.0.get_level(level)
+            }
+        }
+
+        let mut data = vec![Wrapped(2), Wrapped(1)];
+        assert!(!counting_sort(&mut data));
+        assert_eq!(data, [Wrapped(2), Wrapped(1)]);
+    }
+}
@@ -17,16 +17,35 @@
 //! As this is an out-of-place algorithm, we can save some copying by using the temporary array
 //! as our input array, and our original array as our output array on odd runs of the algorithm.
 //!
+//! The data is only copied back into the original array once, at the end, and only when an odd
+//! number of levels were sorted. As the sort works on a borrowed slice rather than an owned buffer,
+//! this final copy can't be avoided by swapping the arrays instead. For `u64` and other keys with
+//! an even number of levels, it is only needed when some levels were skipped.
+//!
 //! ### Level skipping
 //!
 //! When a level has all counts in one bucket (i.e. all values are equal), we can skip the level
 //! entirely. This is done by checking counts rather than the actual data.
 //!
+//! ### Zero level skipping
+//!
+//! Keys padded out to a wider type, such as values which fit in 24 bits stored in a `u64`, have
+//! several more significant levels which are zero for every item. Level skipping avoids sorting
+//! them, but each would still cost a pass to count. So the first time a level is found entirely
+//! in bucket 0, a single pass checks every level between it and the last level for any non-zero
+//! bytes, and the levels which are all zero are then skipped without being counted.
+//!
 //! ### Counting while sorting
 //!
 //! This is implemented in the underlying `out_of_place_sort`. While sorting, we also count the next
 //! level to provide a small but significant performance boost. This is not a huge win as it removes
 //! some caching benefits etc., but has been benchmarked at roughly 5-15% speedup.
+//!
+//! ### Batched counting
+//!
+//! For keys with `RadixKey::BATCHED_LEVELS` set, every level is counted up-front in a single pass
+//! via `get_all_levels` instead. Counting while sorting requires a second `get_level` call for
+//! each item on each pass, which is expensive for keys computed on the fly.
 
 use crate::sorter::Sorter;
 use crate::sorts::out_of_place_sort::{
@@ -46,24 +65,61 @@ impl<'a> Sorter<'a> {
         end_level: usize,
     ) where
         T: RadixKey + Sized + Send + Copy + Sync,
+    {
+        self.lsb_sort_adapter_with_tmp(lr, bucket, last_counts, start_level, end_level, None);
+    }
+
+    // As `lsb_sort_adapter`, but `tmp` can provide the temporary buffer to use, which must be the
+    // same length as `bucket`. Otherwise, one is allocated.
+    pub(crate) fn lsb_sort_adapter_with_tmp<T>(
+        &self,
+        lr: bool,
+        bucket: &mut [T],
+        last_counts: &[usize; 256],
+        start_level: usize,
+        end_level: usize,
+        tmp: Option<&mut [T]>,
+    ) where
+        T: RadixKey + Sized + Send + Copy + Sync,
     {
         if bucket.len() < 2 {
             return;
         }
 
-        let mut tmp_bucket = get_tmp_bucket(bucket.len());
-        let levels: Vec<usize> = (start_level..=end_level).collect();
+        let mut owned_tmp;
+        let tmp_bucket: &mut [T] = match tmp {
+            Some(tmp) => tmp,
+            None => {
+                owned_tmp = get_tmp_bucket(bucket);
+                &mut owned_tmp
+            }
+        };
         let mut invert = false;
         let mut next_counts = None;
+        let mut zero_levels: Option<Vec<bool>> = None;
+        let all_counts = if T::BATCHED_LEVELS {
+            Some(get_all_counts(bucket))
+        } else {
+            None
+        };
+
+        'outer: for level in start_level..=end_level {
+            if let Some(zero_levels) = &zero_levels {
+                if level < end_level && zero_levels[level - start_level] {
+                    next_counts = None;
+                    continue 'outer;
+                }
+            }
 
-        'outer: for level in levels {
             let counts = if level == end_level {
                 *last_counts
+            } else if let Some(all_counts) = &all_counts {
+                all_counts[level]
             } else if let Some(next_counts) = next_counts {
                 next_counts
             } else {
                 let (counts, already_sorted) = if invert {
-                    get_counts(&tmp_bucket, level)
+                    get_counts(tmp_bucket, level)
                 } else {
                     get_counts(bucket, level)
                 };
@@ -76,16 +132,30 @@ impl<'a> Sorter<'a> {
                 counts
             };
 
-            for c in counts.iter() {
+            for (b, c) in counts.iter().enumerate() {
                 if *c == bucket.len() {
                     next_counts = None;
+
+                    // The last level's counts are already known, so only the levels before it
+                    // would need counting
+                    if b == 0
+                        && zero_levels.is_none()
+                        && all_counts.is_none()
+                        && level + 1 < end_level
+                    {
+                        let src = if invert { &*tmp_bucket } else { &*bucket };
+                        let mut z = vec![false; level + 1 - start_level];
+                        z.extend(get_zero_levels(src, level + 1, end_level - 1));
+                        zero_levels = Some(z);
+                    }
+
                     continue 'outer;
                 } else if *c > 0 {
                     break;
                 }
             }
 
-            let should_count = end_level != 0 && level < (end_level - 1);
+            let should_count = all_counts.is_none() && end_level != 0 && level < (end_level - 1);
             if !should_count {
                 next_counts = None;
             }
@@ -93,49 +163,35 @@ impl<'a> Sorter<'a> {
             match (lr, invert, should_count) {
                 (true, true, true) => {
                     next_counts = Some(lr_out_of_place_sort_with_counts(
-                        &tmp_bucket,
-                        bucket,
-                        &counts,
-                        level,
+                        tmp_bucket, bucket, &counts, level,
                     ))
                 }
-                (true, true, false) => lr_out_of_place_sort(&tmp_bucket, bucket, &counts, level),
+                (true, true, false) => lr_out_of_place_sort(tmp_bucket, bucket, &counts, level),
                 (true, false, true) => {
                     next_counts = Some(lr_out_of_place_sort_with_counts(
-                        bucket,
-                        &mut tmp_bucket,
-                        &counts,
-                        level,
+                        bucket, tmp_bucket, &counts, level,
                     ))
                 }
-                (true, false, false) => {
-                    lr_out_of_place_sort(bucket, &mut tmp_bucket, &counts, level)
-                }
+                (true, false, false) => lr_out_of_place_sort(bucket, tmp_bucket, &counts, level),
                 (false, true, true) => {
                     next_counts = Some(out_of_place_sort_with_counts(
-                        &tmp_bucket,
-                        bucket,
-                        &counts,
-                        level,
+                        tmp_bucket, bucket, &counts, level,
                     ))
                 }
-                (false, true, false) => out_of_place_sort(&tmp_bucket, bucket, &counts, level),
+                (false, true, false) => out_of_place_sort(tmp_bucket, bucket, &counts, level),
                 (false, false, true) => {
                     next_counts = Some(out_of_place_sort_with_counts(
-                        bucket,
-                        &mut tmp_bucket,
-                        &counts,
-                        level,
+                        bucket, tmp_bucket, &counts, level,
                     ))
                 }
-                (false, false, false) => out_of_place_sort(bucket, &mut tmp_bucket, &counts, level),
+                (false, false, false) => out_of_place_sort(bucket, tmp_bucket, &counts, level),
             };
 
             invert = !invert;
         }
 
         if invert {
-            bucket.copy_from_slice(&tmp_bucket);
+            bucket.copy_from_slice(tmp_bucket);
         }
     }
 }
@@ -150,6 +206,7 @@ mod tests {
         sort_comparison_suite, sort_single_algorithm, validate_u32_patterns, NumericTest,
     };
     use crate::RadixKey;
+    use block_pseudorand::block_rand;
 
     fn test_lsb_sort_adapter<T>(shift: T)
     where
@@ -210,6 +267,72 @@ mod tests {
         sort_single_algorithm::<u32>(1_000_000, Algorithm::LrLsb);
     }
 
+    #[derive(Copy, Clone)]
+    struct ExpensiveKey(u32);
+
+    impl ExpensiveKey {
+        fn key(&self) -> u32 {
+            self.0.wrapping_mul(0x9E37_79B9).rotate_left(13)
+        }
+    }
+
+    impl RadixKey for ExpensiveKey {
+        const LEVELS: usize = 4;
+        const BATCHED_LEVELS: bool = true;
+
+        fn get_level(&self, level: usize) -> u8 {
+            (self.key() >> (level * 8)) as u8
+        }
+
+        fn get_all_levels(&self, out: &mut [u8]) {
+            out.copy_from_slice(&self.key().to_le_bytes());
+        }
+    }
+
+    #[test]
+    pub fn test_batched_levels() {
+        let sorter = Sorter::new(true, &StandardTuner);
+
+        for len in [0, 1, 5, 200, 100_000] {
+            let mut inputs: Vec<ExpensiveKey> = block_rand::<u32>(len)
+                .into_iter()
+                .map(ExpensiveKey)
+                .collect();
+            let mut expected: Vec<u32> = inputs.iter().map(|v| v.key()).collect();
+            expected.sort_unstable();
+
+            let (counts, _) = get_counts(&inputs, ExpensiveKey::LEVELS - 1);
+            sorter.lsb_sort_adapter(false, &mut inputs, &counts, 0, ExpensiveKey::LEVELS - 1);
+
+            let actual: Vec<u32> = inputs.iter().map(|v| v.key()).collect();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_zero_levels() {
+        let sorter = Sorter::new(true, &StandardTuner);
+        let inputs = block_rand::<u64>(100_000);
+
+        // Zero levels in the middle, at the top, and below the only non-zero levels
+        for mask in [
+            0xFF00_0000_0000_FFFFu64,
+            0x0000_0000_00FF_FFFF,
+            0x00FF_0000_FF00_00FF,
+            0xFFFF_0000_0000_0000,
+        ] {
+            for lr in [false, true] {
+                let mut data: Vec<u64> = inputs.iter().map(|v| v & mask).collect();
+                let mut expected = data.clone();
+                expected.sort_unstable();
+
+                let (counts, _) = get_counts(&data, u64::LEVELS - 1);
+                sorter.lsb_sort_adapter(lr, &mut data, &counts, 0, u64::LEVELS - 1);
+                assert_eq!(data, expected, "{:x}", mask);
+            }
+        }
+    }
+
     #[test]
     pub fn test_u32_patterns() {
         validate_u32_patterns(|inputs| {
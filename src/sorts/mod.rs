@@ -1,12 +1,15 @@
 mod comparative_sort;
+pub(crate) mod counting_sort;
 mod lsb_sort;
 #[cfg(feature = "multi-threaded")]
 mod mt_lsb_sort;
-mod out_of_place_sort;
+pub(crate) mod out_of_place_sort;
+mod partition_sort;
+pub(crate) mod presorted_sort;
 #[cfg(feature = "multi-threaded")]
 mod recombinating_sort;
 #[cfg(feature = "multi-threaded")]
-mod regions_sort;
+pub(crate) mod regions_sort;
 #[cfg(feature = "multi-threaded")]
 mod scanning_sort;
-mod ska_sort;
+pub(crate) mod ska_sort;
@@ -35,7 +35,7 @@ use arbitrary_chunks::ArbitraryChunks;
 use rayon::prelude::*;
 
 pub fn mt_lsb_sort<T>(
-    src_bucket: &mut [T],
+    src_bucket: &[T],
     dst_bucket: &mut [T],
     tile_counts: &[[usize; 256]],
     tile_size: usize,
@@ -52,15 +52,15 @@ pub fn mt_lsb_sort<T>(
         }
     }
 
-    let mut chunks: Vec<&mut [T]> = dst_bucket.arbitrary_chunks_mut(&minor_counts).collect();
-    chunks.reverse();
-
-    let mut collated_chunks: Vec<Vec<&mut [T]>> = Vec::with_capacity(tiles);
-    collated_chunks.resize_with(tiles, Vec::new);
+    // Output chunks are ordered by radix, then by tile. Hand them straight out to each tile in that
+    // order, rather than collecting them all up-front.
+    let mut chunks = dst_bucket.arbitrary_chunks_mut(&minor_counts);
+    let mut collated_chunks: Vec<Vec<&mut [T]>> =
+        (0..tiles).map(|_| Vec::with_capacity(256)).collect();
 
     for _ in 0..256 {
-        for coll_chunk in collated_chunks.iter_mut().take(tiles) {
-            coll_chunk.push(chunks.pop().unwrap());
+        for coll_chunk in collated_chunks.iter_mut() {
+            coll_chunk.push(chunks.next().unwrap());
         }
     }
 
@@ -72,27 +72,27 @@ pub fn mt_lsb_sort<T>(
                 return;
             }
 
+            // Each tile fills its chunk of each bucket from both ends: `offsets` counts up from
+            // the start, and `ends` counts down from one past the last slot. A chunk is exactly as
+            // long as the tile's count for that radix, so an empty chunk starts with `ends` at 0,
+            // but no item in this tile has that radix to decrement it.
             let mut offsets = [0usize; 256];
             let mut ends = [0usize; 256];
 
-            for (i, b) in buckets.iter().enumerate() {
-                if b.is_empty() {
-                    continue;
-                }
-
-                ends[i] = b.len() - 1;
+            for (end, b) in ends.iter_mut().zip(buckets.iter()) {
+                *end = b.len();
             }
 
             let mut left = 0;
-            let mut right = bucket.len() - 1;
+            let mut right = bucket.len();
             let pre = bucket.len() % 8;
 
             for _ in 0..pre {
+                right -= 1;
                 let b = bucket[right].get_level(level) as usize;
 
+                ends[b] = checked_dec!(ends[b], offsets[b], level, b);
                 buckets[b][ends[b]] = bucket[right];
-                ends[b] = ends[b].wrapping_sub(1);
-                right = right.saturating_sub(1);
             }
 
             if pre == bucket.len() {
@@ -106,34 +106,135 @@ pub fn mt_lsb_sort<T>(
                 let bl_1 = bucket[left + 1].get_level(level) as usize;
                 let bl_2 = bucket[left + 2].get_level(level) as usize;
                 let bl_3 = bucket[left + 3].get_level(level) as usize;
-                let br_0 = bucket[right].get_level(level) as usize;
-                let br_1 = bucket[right - 1].get_level(level) as usize;
-                let br_2 = bucket[right - 2].get_level(level) as usize;
-                let br_3 = bucket[right - 3].get_level(level) as usize;
+                let br_0 = bucket[right - 1].get_level(level) as usize;
+                let br_1 = bucket[right - 2].get_level(level) as usize;
+                let br_2 = bucket[right - 3].get_level(level) as usize;
+                let br_3 = bucket[right - 4].get_level(level) as usize;
 
                 buckets[bl_0][offsets[bl_0]] = bucket[left];
-                offsets[bl_0] += 1;
-                buckets[br_0][ends[br_0]] = bucket[right];
-                ends[br_0] = ends[br_0].wrapping_sub(1);
+                offsets[bl_0] = checked_inc!(offsets[bl_0], ends[bl_0], level, bl_0);
+                ends[br_0] = checked_dec!(ends[br_0], offsets[br_0], level, br_0);
+                buckets[br_0][ends[br_0]] = bucket[right - 1];
                 buckets[bl_1][offsets[bl_1]] = bucket[left + 1];
-                offsets[bl_1] += 1;
-                buckets[br_1][ends[br_1]] = bucket[right - 1];
-                ends[br_1] = ends[br_1].wrapping_sub(1);
+                offsets[bl_1] = checked_inc!(offsets[bl_1], ends[bl_1], level, bl_1);
+                ends[br_1] = checked_dec!(ends[br_1], offsets[br_1], level, br_1);
+                buckets[br_1][ends[br_1]] = bucket[right - 2];
                 buckets[bl_2][offsets[bl_2]] = bucket[left + 2];
-                offsets[bl_2] += 1;
-                buckets[br_2][ends[br_2]] = bucket[right - 2];
-                ends[br_2] = ends[br_2].wrapping_sub(1);
+                offsets[bl_2] = checked_inc!(offsets[bl_2], ends[bl_2], level, bl_2);
+                ends[br_2] = checked_dec!(ends[br_2], offsets[br_2], level, br_2);
+                buckets[br_2][ends[br_2]] = bucket[right - 3];
                 buckets[bl_3][offsets[bl_3]] = bucket[left + 3];
-                offsets[bl_3] += 1;
-                buckets[br_3][ends[br_3]] = bucket[right - 3];
-                ends[br_3] = ends[br_3].wrapping_sub(1);
+                offsets[bl_3] = checked_inc!(offsets[bl_3], ends[bl_3], level, bl_3);
+                ends[br_3] = checked_dec!(ends[br_3], offsets[br_3], level, br_3);
+                buckets[br_3][ends[br_3]] = bucket[right - 4];
 
                 left += 4;
-                right = right.wrapping_sub(4);
+                right -= 4;
             }
         });
 }
 
+/// `mt_lsb_sort_with_counts` is `mt_lsb_sort`, but also counts the next level while scattering,
+/// returning the tile counts of `dst_bucket` (in tiles of `tile_size`) for that level. This saves
+/// a counting pass over the data for each level.
+///
+/// Each tile of the input is scattered across every tile of the output, so each thread keeps its
+/// own set of output tile counts, which are summed at the end. To avoid working out the output
+/// tile of every write, each bucket's cursor only needs to be checked against the offset at which
+/// it crosses into the next output tile.
+///
+/// Each input tile keeps counts for every output tile, so the counts take `O(tiles²)` memory. Use
+/// `counts_while_scattering()` to check that is small next to the data before using this.
+pub fn mt_lsb_sort_with_counts<T>(
+    src_bucket: &[T],
+    dst_bucket: &mut [T],
+    tile_counts: &[[usize; 256]],
+    tile_size: usize,
+    level: usize,
+) -> Vec<[usize; 256]>
+where
+    T: RadixKey + Sized + Send + Copy + Sync,
+{
+    let tiles = tile_counts.len();
+    let next_level = level + 1;
+    let mut minor_counts = Vec::with_capacity(256 * tiles);
+    let mut starts = vec![[0usize; 256]; tiles];
+    let mut running_total = 0;
+
+    for b in 0..256 {
+        for (tile, tile_starts) in tile_counts.iter().zip(starts.iter_mut()) {
+            minor_counts.push(tile[b]);
+            tile_starts[b] = running_total;
+            running_total += tile[b];
+        }
+    }
+
+    let mut chunks = dst_bucket.arbitrary_chunks_mut(&minor_counts);
+    let mut collated_chunks: Vec<Vec<&mut [T]>> =
+        (0..tiles).map(|_| Vec::with_capacity(256)).collect();
+
+    for _ in 0..256 {
+        for coll_chunk in collated_chunks.iter_mut() {
+            coll_chunk.push(chunks.next().unwrap());
+        }
+    }
+
+    collated_chunks
+        .into_par_iter()
+        .zip(src_bucket.par_chunks(tile_size))
+        .zip(starts.par_iter())
+        .map(|((mut buckets, bucket), starts)| {
+            let mut next_counts = vec![[0usize; 256]; tiles];
+            let mut offsets = [0usize; 256];
+            let mut out_tiles = [0usize; 256];
+            let mut out_tile_ends = [0usize; 256];
+
+            for b in 0..256 {
+                out_tiles[b] = starts[b] / tile_size;
+                out_tile_ends[b] = (out_tiles[b] + 1) * tile_size - starts[b];
+            }
+
+            for item in bucket {
+                let b = item.get_level(level) as usize;
+                let bn = item.get_level(next_level) as usize;
+
+                if offsets[b] == out_tile_ends[b] {
+                    out_tiles[b] += 1;
+                    out_tile_ends[b] += tile_size;
+                }
+
+                buckets[b][offsets[b]] = *item;
+                offsets[b] += 1;
+                next_counts[out_tiles[b]][bn] += 1;
+            }
+
+            next_counts
+        })
+        // Combined pairwise as rayon joins the splits back together, so there is no serial fold
+        // over every chunk's counts, nor an extra set of counts allocated for each split
+        .reduce_with(|mut a, b| {
+            for (a, b) in a.iter_mut().zip(b.iter()) {
+                for i in 0..256 {
+                    a[i] += b[i];
+                }
+            }
+
+            a
+        })
+        .unwrap_or_else(|| vec![[0usize; 256]; tiles])
+}
+
+/// `counts_while_scattering` returns whether `mt_lsb_sort_with_counts` is worth using to count the
+/// next level of `len` items in tiles of `tile_size`, rather than a separate counting pass. Its
+/// counts grow with the square of the number of tiles, so with many small tiles, clearing and
+/// summing them would cost more than counting the data again.
+#[inline]
+pub fn counts_while_scattering(len: usize, tile_size: usize) -> bool {
+    let tiles = cdiv(len, tile_size);
+
+    tiles.saturating_mul(tiles).saturating_mul(256) <= len
+}
+
 impl<'a> Sorter<'a> {
     pub(crate) fn mt_lsb_sort_adapter<T>(
         &self,
@@ -148,25 +249,64 @@ impl<'a> Sorter<'a> {
             return;
         }
 
-        let mut tmp_bucket = get_tmp_bucket(bucket.len());
+        let mut tmp_bucket = get_tmp_bucket(bucket);
         let levels: Vec<usize> = (start_level..=end_level).collect();
         let mut invert = false;
-
-        for level in levels {
-            let (tile_counts, already_sorted) = if invert {
-                get_tile_counts(&tmp_bucket, tile_size, level)
-            } else {
-                get_tile_counts(bucket, tile_size, level)
+        let counting_params = self.tuner.counting_params();
+        let count_next = counts_while_scattering(bucket.len(), tile_size);
+        let mut next_tile_counts: Option<Vec<[usize; 256]>> = None;
+
+        'outer: for level in levels {
+            let tile_counts = match next_tile_counts.take() {
+                Some(tile_counts) => tile_counts,
+                None => {
+                    let (tile_counts, already_sorted) = if invert {
+                        get_tile_counts(&tmp_bucket, tile_size, level, &counting_params)
+                    } else {
+                        get_tile_counts(bucket, tile_size, level, &counting_params)
+                    };
+
+                    if already_sorted {
+                        continue;
+                    }
+
+                    tile_counts
+                }
             };
 
-            if already_sorted {
-                continue;
+            for c in aggregate_tile_counts(&tile_counts).iter() {
+                if *c == bucket.len() {
+                    continue 'outer;
+                } else if *c > 0 {
+                    break;
+                }
             }
 
-            if invert {
-                mt_lsb_sort(&mut tmp_bucket, bucket, &tile_counts, tile_size, level)
-            } else {
-                mt_lsb_sort(bucket, &mut tmp_bucket, &tile_counts, tile_size, level)
+            let should_count = count_next && level < end_level;
+
+            next_tile_counts = match (invert, should_count) {
+                (true, true) => Some(mt_lsb_sort_with_counts(
+                    &tmp_bucket,
+                    bucket,
+                    &tile_counts,
+                    tile_size,
+                    level,
+                )),
+                (true, false) => {
+                    mt_lsb_sort(&tmp_bucket, bucket, &tile_counts, tile_size, level);
+                    None
+                }
+                (false, true) => Some(mt_lsb_sort_with_counts(
+                    bucket,
+                    &mut tmp_bucket,
+                    &tile_counts,
+                    tile_size,
+                    level,
+                )),
+                (false, false) => {
+                    mt_lsb_sort(bucket, &mut tmp_bucket, &tile_counts, tile_size, level);
+                    None
+                }
             };
 
             invert = !invert;
@@ -186,7 +326,6 @@ impl<'a> Sorter<'a> {
         &self,
         bucket: &mut [T],
         level: usize,
-        counts: &[usize; 256],
         tile_counts: &[[usize; 256]],
         tile_size: usize,
     ) where
@@ -196,7 +335,7 @@ impl<'a> Sorter<'a> {
             return;
         }
 
-        let mut tmp_bucket = get_tmp_bucket(bucket.len());
+        let mut tmp_bucket = get_tmp_bucket(bucket);
         mt_lsb_sort(bucket, &mut tmp_bucket, tile_counts, tile_size, level);
 
         bucket
@@ -205,23 +344,23 @@ impl<'a> Sorter<'a> {
             .for_each(|(chunk, tmp_chunk)| {
                 chunk.copy_from_slice(tmp_chunk);
             });
-
-        drop(tmp_bucket);
-
-        self.director(bucket, counts, level - 1);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::sorter::Sorter;
-    use crate::tuner::Algorithm;
+    use crate::sorts::mt_lsb_sort::{
+        counts_while_scattering, mt_lsb_sort, mt_lsb_sort_with_counts,
+    };
+    use crate::tuner::{Algorithm, CountingParams, TileParams, Tuner, TuningParams};
     use crate::tuners::StandardTuner;
-    use crate::utils::cdiv;
     use crate::utils::test_utils::{
         sort_comparison_suite, sort_single_algorithm, validate_u32_patterns, NumericTest,
     };
-    use crate::RadixKey;
+    use crate::utils::{cdiv, get_tile_counts, get_tmp_bucket};
+    use crate::{RadixKey, RadixSort};
+    use block_pseudorand::block_rand;
     use rayon::current_num_threads;
 
     fn test_mt_lsb_sort_adapter<T>(shift: T)
@@ -231,7 +370,7 @@ mod tests {
         let sorter = Sorter::new(true, &StandardTuner);
 
         sort_comparison_suite(shift, |inputs| {
-            if inputs.len() == 0 {
+            if inputs.is_empty() {
                 return;
             }
 
@@ -286,7 +425,7 @@ mod tests {
     #[test]
     pub fn test_u32_patterns() {
         validate_u32_patterns(|inputs| {
-            if inputs.len() == 0 {
+            if inputs.is_empty() {
                 return;
             }
 
@@ -296,4 +435,153 @@ mod tests {
             sorter.mt_lsb_sort_adapter(inputs, 0, u32::LEVELS - 1, tile_size);
         });
     }
+
+    #[test]
+    pub fn test_u32_patterns_multiple_tiles() {
+        validate_u32_patterns(|inputs| {
+            if inputs.is_empty() {
+                return;
+            }
+
+            let sorter = Sorter::new(true, &StandardTuner);
+            let tile_size = cdiv(inputs.len(), 4);
+
+            sorter.mt_lsb_sort_adapter(inputs, 0, u32::LEVELS - 1, tile_size);
+        });
+    }
+
+    #[test]
+    pub fn test_next_tile_counts() {
+        let inputs: Vec<u32> = block_rand(100_000);
+
+        // Tile sizes which don't evenly divide the input, and a single tile
+        for tile_size in [7_919, 30_000, 100_000] {
+            let (tile_counts, _) =
+                get_tile_counts(&inputs, tile_size, 0, &CountingParams::default());
+            let mut out = get_tmp_bucket(&inputs);
+
+            let next_tile_counts =
+                mt_lsb_sort_with_counts(&inputs, &mut out, &tile_counts, tile_size, 0);
+            let (expected, _) = get_tile_counts(&out, tile_size, 1, &CountingParams::default());
+
+            assert_eq!(next_tile_counts, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_counts_while_scattering() {
+        // 62 tiles of 16_130 items is the most which can count while scattering 1M items
+        assert!(counts_while_scattering(1_000_000, 16_130));
+        assert!(!counts_while_scattering(1_000_000, 16_000));
+        assert!(counts_while_scattering(256, 256));
+        assert!(!counts_while_scattering(200, 1));
+        assert!(!counts_while_scattering(1 << 40, 1));
+    }
+
+    #[test]
+    pub fn test_empty_buckets() {
+        // A single value leaves 255 of the 256 buckets empty in every tile, and a few values spread
+        // across tiles leave whole tiles with nothing in most of the buckets that others fill
+        let two_values: Vec<u32> = (0..200u32)
+            .map(|i| if i % 67 == 0 { u32::MAX } else { 0x0102_0304 })
+            .collect();
+
+        for inputs in [vec![0u32; 200], vec![u32::MAX; 200], two_values] {
+            let mut expected = inputs.clone();
+            expected.sort_unstable();
+
+            // Tiles smaller than, equal to and just past the 8 items scattered per loop
+            for tile_size in [1, 3, 8, 9, 100, 199, 200] {
+                for level in 0..u32::LEVELS {
+                    let (tile_counts, _) =
+                        get_tile_counts(&inputs, tile_size, level, &CountingParams::default());
+                    let mut out = get_tmp_bucket(&inputs);
+
+                    mt_lsb_sort(&inputs, &mut out, &tile_counts, tile_size, level);
+                    assert!(out
+                        .windows(2)
+                        .all(|w| w[0].get_level(level) <= w[1].get_level(level)));
+
+                    if level + 1 < u32::LEVELS {
+                        let next_tile_counts = mt_lsb_sort_with_counts(
+                            &inputs,
+                            &mut out,
+                            &tile_counts,
+                            tile_size,
+                            level,
+                        );
+                        let (expected_counts, _) =
+                            get_tile_counts(&out, tile_size, level + 1, &CountingParams::default());
+                        assert_eq!(next_tile_counts, expected_counts);
+                    }
+                }
+
+                let mut data = inputs.clone();
+                let sorter = Sorter::new(true, &StandardTuner);
+                sorter.mt_lsb_sort_adapter(&mut data, 0, u32::LEVELS - 1, tile_size);
+                assert_eq!(data, expected, "tile size {}", tile_size);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_tile_params() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Counts how often the tile size is looked up
+        struct TiledTuner {
+            algorithm: Algorithm,
+            lookups: AtomicUsize,
+        }
+
+        impl Tuner for TiledTuner {
+            fn pick_algorithm(&self, _p: &TuningParams, _counts: &[usize]) -> Algorithm {
+                self.algorithm
+            }
+
+            fn tile_params(&self) -> TileParams {
+                self.lookups.fetch_add(1, Ordering::Relaxed);
+
+                TileParams {
+                    min_tile_size: 1_000,
+                    tiles_per_thread: 8,
+                }
+            }
+        }
+
+        let inputs: Vec<u32> = block_rand(1_000_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+
+        pool.install(|| {
+            for algorithm in [
+                Algorithm::MtLsb,
+                Algorithm::MtOop,
+                Algorithm::Recombinating,
+                Algorithm::Regions,
+            ] {
+                let tuner = TiledTuner {
+                    algorithm,
+                    lookups: AtomicUsize::new(0),
+                };
+
+                // 8 tiles for each of the 4 threads, rather than the default of 1
+                let sorter = Sorter::new(true, &tuner);
+                assert_eq!(sorter.tile_size(inputs.len(), 4), 31_250);
+                assert_eq!(sorter.tile_size(5_000, 4), 1_000);
+                tuner.lookups.store(0, Ordering::Relaxed);
+
+                let mut data = inputs.clone();
+                data.radix_sort_builder().with_tuner(&tuner).sort();
+
+                assert_eq!(data, expected, "{:?}", algorithm);
+                assert!(tuner.lookups.into_inner() > 0, "{:?}", algorithm);
+            }
+        });
+    }
 }
@@ -37,14 +37,27 @@
 //! As with the other with_counts variant, this combines the left-right optimization with counting
 //! the next level.
 //!
+//! ### out_of_place_sort_into_adapter
+//!
+//! Sorts the top level from one array into a separate output array, then sorts the remaining levels
+//! in-place within that output. This allows the output to live somewhere other than the heap, such
+//! as a memory-mapped file. The top level is sorted with `mt_lsb_sort` when multi-threading is
+//! enabled.
+//!
 //! ## Characteristics
 //!
 //!  * out-of-place
 //!  * single-threaded
 //!  * lsb-first
 
+#[cfg(feature = "memmap2")]
+use crate::sorter::Sorter;
+#[cfg(all(feature = "memmap2", feature = "multi-threaded"))]
+use crate::sorts::mt_lsb_sort::mt_lsb_sort;
 use crate::utils::*;
 use crate::RadixKey;
+#[cfg(all(feature = "memmap2", feature = "multi-threaded"))]
+use rayon::current_num_threads;
 
 #[inline]
 pub fn out_of_place_sort<T>(
@@ -61,6 +74,8 @@ pub fn out_of_place_sort<T>(
     }
 
     let mut prefix_sums = get_prefix_sums(counts);
+    #[cfg(feature = "debug-checks")]
+    let ends = get_end_offsets(counts, &prefix_sums);
 
     let chunks = src_bucket.chunks_exact(8);
     let rem = chunks.remainder();
@@ -76,27 +91,27 @@ pub fn out_of_place_sort<T>(
         let h = chunk[7].get_level(level) as usize;
 
         dst_bucket[prefix_sums[a]] = chunk[0];
-        prefix_sums[a] += 1;
+        prefix_sums[a] = checked_inc!(prefix_sums[a], ends[a], level, a);
         dst_bucket[prefix_sums[b]] = chunk[1];
-        prefix_sums[b] += 1;
+        prefix_sums[b] = checked_inc!(prefix_sums[b], ends[b], level, b);
         dst_bucket[prefix_sums[c]] = chunk[2];
-        prefix_sums[c] += 1;
+        prefix_sums[c] = checked_inc!(prefix_sums[c], ends[c], level, c);
         dst_bucket[prefix_sums[d]] = chunk[3];
-        prefix_sums[d] += 1;
+        prefix_sums[d] = checked_inc!(prefix_sums[d], ends[d], level, d);
         dst_bucket[prefix_sums[e]] = chunk[4];
-        prefix_sums[e] += 1;
+        prefix_sums[e] = checked_inc!(prefix_sums[e], ends[e], level, e);
         dst_bucket[prefix_sums[f]] = chunk[5];
-        prefix_sums[f] += 1;
+        prefix_sums[f] = checked_inc!(prefix_sums[f], ends[f], level, f);
         dst_bucket[prefix_sums[g]] = chunk[6];
-        prefix_sums[g] += 1;
+        prefix_sums[g] = checked_inc!(prefix_sums[g], ends[g], level, g);
         dst_bucket[prefix_sums[h]] = chunk[7];
-        prefix_sums[h] += 1;
+        prefix_sums[h] = checked_inc!(prefix_sums[h], ends[h], level, h);
     });
 
     rem.iter().for_each(|val| {
         let b = val.get_level(level) as usize;
         dst_bucket[prefix_sums[b]] = *val;
-        prefix_sums[b] += 1;
+        prefix_sums[b] = checked_inc!(prefix_sums[b], ends[b], level, b);
     });
 }
 
@@ -115,12 +130,14 @@ where
     } else if src_bucket.len() == 1 {
         let mut counts = [0usize; 256];
         dst_bucket.copy_from_slice(src_bucket);
-        counts[src_bucket[0].get_level(level) as usize] = 1;
+        counts[src_bucket[0].get_level(level + 1) as usize] = 1;
         return counts;
     }
 
     let next_level = level + 1;
     let mut prefix_sums = get_prefix_sums(counts);
+    #[cfg(feature = "debug-checks")]
+    let ends = get_end_offsets(counts, &prefix_sums);
     let mut next_counts_0 = [0usize; 256];
     let mut next_counts_1 = [0usize; 256];
 
@@ -146,28 +163,28 @@ where
         let bn7 = chunk[7].get_level(next_level) as usize;
 
         dst_bucket[prefix_sums[b0]] = chunk[0];
-        prefix_sums[b0] += 1;
+        prefix_sums[b0] = checked_inc!(prefix_sums[b0], ends[b0], level, b0);
         next_counts_0[bn0] += 1;
         dst_bucket[prefix_sums[b1]] = chunk[1];
-        prefix_sums[b1] += 1;
+        prefix_sums[b1] = checked_inc!(prefix_sums[b1], ends[b1], level, b1);
         next_counts_1[bn1] += 1;
         dst_bucket[prefix_sums[b2]] = chunk[2];
-        prefix_sums[b2] += 1;
+        prefix_sums[b2] = checked_inc!(prefix_sums[b2], ends[b2], level, b2);
         next_counts_0[bn2] += 1;
         dst_bucket[prefix_sums[b3]] = chunk[3];
-        prefix_sums[b3] += 1;
+        prefix_sums[b3] = checked_inc!(prefix_sums[b3], ends[b3], level, b3);
         next_counts_1[bn3] += 1;
         dst_bucket[prefix_sums[b4]] = chunk[4];
-        prefix_sums[b4] += 1;
+        prefix_sums[b4] = checked_inc!(prefix_sums[b4], ends[b4], level, b4);
         next_counts_0[bn4] += 1;
         dst_bucket[prefix_sums[b5]] = chunk[5];
-        prefix_sums[b5] += 1;
+        prefix_sums[b5] = checked_inc!(prefix_sums[b5], ends[b5], level, b5);
         next_counts_1[bn5] += 1;
         dst_bucket[prefix_sums[b6]] = chunk[6];
-        prefix_sums[b6] += 1;
+        prefix_sums[b6] = checked_inc!(prefix_sums[b6], ends[b6], level, b6);
         next_counts_0[bn6] += 1;
         dst_bucket[prefix_sums[b7]] = chunk[7];
-        prefix_sums[b7] += 1;
+        prefix_sums[b7] = checked_inc!(prefix_sums[b7], ends[b7], level, b7);
         next_counts_1[bn7] += 1;
     });
 
@@ -175,7 +192,7 @@ where
         let b = val.get_level(level) as usize;
         let bn = val.get_level(next_level) as usize;
         dst_bucket[prefix_sums[b]] = *val;
-        prefix_sums[b] += 1;
+        prefix_sums[b] = checked_inc!(prefix_sums[b], ends[b], level, b);
         next_counts_0[bn] += 1;
     });
 
@@ -204,19 +221,19 @@ pub fn lr_out_of_place_sort<T>(
     let mut ends = [0usize; 256];
 
     for (i, b) in offsets.iter().enumerate() {
-        ends[i] = b + counts[i].saturating_sub(1);
+        ends[i] = b + counts[i];
     }
 
     let mut left = 0;
-    let mut right = src_bucket.len() - 1;
+    let mut right = src_bucket.len();
     let pre = src_bucket.len() % 8;
 
     for _ in 0..pre {
+        right -= 1;
         let b = src_bucket[right].get_level(level) as usize;
 
+        ends[b] = checked_dec!(ends[b], offsets[b], level, b);
         dst_bucket[ends[b]] = src_bucket[right];
-        ends[b] = ends[b].saturating_sub(1);
-        right = right.saturating_sub(1);
     }
 
     if pre == src_bucket.len() {
@@ -230,27 +247,27 @@ pub fn lr_out_of_place_sort<T>(
         let bl_1 = src_bucket[left + 1].get_level(level) as usize;
         let bl_2 = src_bucket[left + 2].get_level(level) as usize;
         let bl_3 = src_bucket[left + 3].get_level(level) as usize;
-        let br_0 = src_bucket[right].get_level(level) as usize;
-        let br_1 = src_bucket[right - 1].get_level(level) as usize;
-        let br_2 = src_bucket[right - 2].get_level(level) as usize;
-        let br_3 = src_bucket[right - 3].get_level(level) as usize;
+        let br_0 = src_bucket[right - 1].get_level(level) as usize;
+        let br_1 = src_bucket[right - 2].get_level(level) as usize;
+        let br_2 = src_bucket[right - 3].get_level(level) as usize;
+        let br_3 = src_bucket[right - 4].get_level(level) as usize;
 
         dst_bucket[offsets[bl_0]] = src_bucket[left];
-        offsets[bl_0] = offsets[bl_0].wrapping_add(1);
-        dst_bucket[ends[br_0]] = src_bucket[right];
-        ends[br_0] = ends[br_0].wrapping_sub(1);
+        offsets[bl_0] = checked_inc!(offsets[bl_0], ends[bl_0], level, bl_0);
+        ends[br_0] = checked_dec!(ends[br_0], offsets[br_0], level, br_0);
+        dst_bucket[ends[br_0]] = src_bucket[right - 1];
         dst_bucket[offsets[bl_1]] = src_bucket[left + 1];
-        offsets[bl_1] = offsets[bl_1].wrapping_add(1);
-        dst_bucket[ends[br_1]] = src_bucket[right - 1];
-        ends[br_1] = ends[br_1].wrapping_sub(1);
+        offsets[bl_1] = checked_inc!(offsets[bl_1], ends[bl_1], level, bl_1);
+        ends[br_1] = checked_dec!(ends[br_1], offsets[br_1], level, br_1);
+        dst_bucket[ends[br_1]] = src_bucket[right - 2];
         dst_bucket[offsets[bl_2]] = src_bucket[left + 2];
-        offsets[bl_2] = offsets[bl_2].wrapping_add(1);
-        dst_bucket[ends[br_2]] = src_bucket[right - 2];
-        ends[br_2] = ends[br_2].wrapping_sub(1);
+        offsets[bl_2] = checked_inc!(offsets[bl_2], ends[bl_2], level, bl_2);
+        ends[br_2] = checked_dec!(ends[br_2], offsets[br_2], level, br_2);
+        dst_bucket[ends[br_2]] = src_bucket[right - 3];
         dst_bucket[offsets[bl_3]] = src_bucket[left + 3];
-        offsets[bl_3] = offsets[bl_3].wrapping_add(1);
-        dst_bucket[ends[br_3]] = src_bucket[right - 3];
-        ends[br_3] = ends[br_3].wrapping_sub(1);
+        offsets[bl_3] = checked_inc!(offsets[bl_3], ends[bl_3], level, bl_3);
+        ends[br_3] = checked_dec!(ends[br_3], offsets[br_3], level, br_3);
+        dst_bucket[ends[br_3]] = src_bucket[right - 4];
 
         left += 4;
         right -= 4;
@@ -272,7 +289,7 @@ where
     } else if src_bucket.len() == 1 {
         let mut counts = [0usize; 256];
         dst_bucket.copy_from_slice(src_bucket);
-        counts[src_bucket[0].get_level(level) as usize] = 1;
+        counts[src_bucket[0].get_level(level + 1) as usize] = 1;
         return counts;
     }
 
@@ -284,20 +301,20 @@ where
     let mut ends = [0usize; 256];
 
     for (i, b) in offsets.iter().enumerate() {
-        ends[i] = b + counts[i].saturating_sub(1);
+        ends[i] = b + counts[i];
     }
 
     let mut left = 0;
-    let mut right = src_bucket.len() - 1;
+    let mut right = src_bucket.len();
     let pre = src_bucket.len() % 8;
 
     for _ in 0..pre {
+        right -= 1;
         let b = src_bucket[right].get_level(level) as usize;
         let bn = src_bucket[right].get_level(next_level) as usize;
 
+        ends[b] = checked_dec!(ends[b], offsets[b], level, b);
         dst_bucket[ends[b]] = src_bucket[right];
-        ends[b] = ends[b].wrapping_sub(1);
-        right = right.wrapping_sub(1);
         next_counts_0[bn] += 1;
     }
 
@@ -312,39 +329,39 @@ where
         let bl_1 = src_bucket[left + 1].get_level(level) as usize;
         let bl_2 = src_bucket[left + 2].get_level(level) as usize;
         let bl_3 = src_bucket[left + 3].get_level(level) as usize;
-        let br_0 = src_bucket[right].get_level(level) as usize;
-        let br_1 = src_bucket[right - 1].get_level(level) as usize;
-        let br_2 = src_bucket[right - 2].get_level(level) as usize;
-        let br_3 = src_bucket[right - 3].get_level(level) as usize;
+        let br_0 = src_bucket[right - 1].get_level(level) as usize;
+        let br_1 = src_bucket[right - 2].get_level(level) as usize;
+        let br_2 = src_bucket[right - 3].get_level(level) as usize;
+        let br_3 = src_bucket[right - 4].get_level(level) as usize;
 
         dst_bucket[offsets[bl_0]] = src_bucket[left];
-        dst_bucket[ends[br_0]] = src_bucket[right];
-        ends[br_0] = ends[br_0].wrapping_sub(1);
-        offsets[bl_0] = offsets[bl_0].wrapping_add(1);
+        ends[br_0] = checked_dec!(ends[br_0], offsets[br_0], level, br_0);
+        dst_bucket[ends[br_0]] = src_bucket[right - 1];
+        offsets[bl_0] = checked_inc!(offsets[bl_0], ends[bl_0], level, bl_0);
 
         dst_bucket[offsets[bl_1]] = src_bucket[left + 1];
-        dst_bucket[ends[br_1]] = src_bucket[right - 1];
-        ends[br_1] = ends[br_1].wrapping_sub(1);
-        offsets[bl_1] = offsets[bl_1].wrapping_add(1);
+        ends[br_1] = checked_dec!(ends[br_1], offsets[br_1], level, br_1);
+        dst_bucket[ends[br_1]] = src_bucket[right - 2];
+        offsets[bl_1] = checked_inc!(offsets[bl_1], ends[bl_1], level, bl_1);
 
         dst_bucket[offsets[bl_2]] = src_bucket[left + 2];
-        dst_bucket[ends[br_2]] = src_bucket[right - 2];
-        ends[br_2] = ends[br_2].wrapping_sub(1);
-        offsets[bl_2] = offsets[bl_2].wrapping_add(1);
+        ends[br_2] = checked_dec!(ends[br_2], offsets[br_2], level, br_2);
+        dst_bucket[ends[br_2]] = src_bucket[right - 3];
+        offsets[bl_2] = checked_inc!(offsets[bl_2], ends[bl_2], level, bl_2);
 
         dst_bucket[offsets[bl_3]] = src_bucket[left + 3];
-        dst_bucket[ends[br_3]] = src_bucket[right - 3];
-        ends[br_3] = ends[br_3].wrapping_sub(1);
-        offsets[bl_3] = offsets[bl_3].wrapping_add(1);
+        ends[br_3] = checked_dec!(ends[br_3], offsets[br_3], level, br_3);
+        dst_bucket[ends[br_3]] = src_bucket[right - 4];
+        offsets[bl_3] = checked_inc!(offsets[bl_3], ends[bl_3], level, bl_3);
 
         let bnl_0 = src_bucket[left].get_level(next_level) as usize;
         let bnl_1 = src_bucket[left + 1].get_level(next_level) as usize;
         let bnl_2 = src_bucket[left + 2].get_level(next_level) as usize;
         let bnl_3 = src_bucket[left + 3].get_level(next_level) as usize;
-        let bnr_0 = src_bucket[right].get_level(next_level) as usize;
-        let bnr_1 = src_bucket[right - 1].get_level(next_level) as usize;
-        let bnr_2 = src_bucket[right - 2].get_level(next_level) as usize;
-        let bnr_3 = src_bucket[right - 3].get_level(next_level) as usize;
+        let bnr_0 = src_bucket[right - 1].get_level(next_level) as usize;
+        let bnr_1 = src_bucket[right - 2].get_level(next_level) as usize;
+        let bnr_2 = src_bucket[right - 3].get_level(next_level) as usize;
+        let bnr_3 = src_bucket[right - 4].get_level(next_level) as usize;
 
         next_counts_0[bnl_0] += 1;
         next_counts_1[bnr_0] += 1;
@@ -365,3 +382,112 @@ where
 
     next_counts_0
 }
+
+#[cfg(feature = "memmap2")]
+impl<'a> Sorter<'a> {
+    pub(crate) fn out_of_place_sort_into_adapter<T>(&self, src: &[T], dst: &mut [T], level: usize)
+    where
+        T: RadixKey + Sized + Send + Copy + Sync,
+    {
+        assert_eq!(src.len(), dst.len());
+
+        if src.is_empty() {
+            return;
+        }
+
+        #[cfg(feature = "multi-threaded")]
+        let counts = if self.multi_threaded {
+            let tile_size = self.tile_size(src.len(), current_num_threads());
+            let (tile_counts, _) =
+                get_tile_counts(src, tile_size, level, &self.tuner.counting_params());
+            mt_lsb_sort(src, dst, &tile_counts, tile_size, level);
+
+            aggregate_tile_counts(&tile_counts)
+        } else {
+            let (counts, _) = get_counts(src, level);
+            out_of_place_sort(src, dst, &counts, level);
+
+            counts
+        };
+
+        #[cfg(not(feature = "multi-threaded"))]
+        let counts = {
+            let (counts, _) = get_counts(src, level);
+            out_of_place_sort(src, dst, &counts, level);
+
+            counts
+        };
+
+        if level != 0 {
+            self.director(dst, &counts, level - 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(feature = "debug-checks")]
+    #[should_panic(expected = "bucket overflow: level 0, bucket 0")]
+    pub fn test_debug_checks_underflow() {
+        use crate::sorts::out_of_place_sort::lr_out_of_place_sort;
+
+        // Every item belongs in bucket 0, but the counts claim only half of them do
+        let src = [0u8; 10];
+        let mut dst = [0u8; 10];
+        let mut counts = [0usize; 256];
+        counts[0] = 5;
+        counts[1] = 5;
+
+        lr_out_of_place_sort(&src, &mut dst, &counts, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "debug-checks")]
+    #[should_panic(expected = "bucket overflow: level 0, bucket 3")]
+    pub fn test_debug_checks_back_cursor() {
+        use crate::sorts::out_of_place_sort::lr_out_of_place_sort;
+
+        // Every item belongs in bucket 3, but the counts claim only half of them do. Without the
+        // check, the back cursor for bucket 3 would run on into bucket 1.
+        let src = [3u8; 6];
+        let mut dst = [0u8; 6];
+        let mut counts = [0usize; 256];
+        counts[1] = 3;
+        counts[3] = 3;
+
+        lr_out_of_place_sort(&src, &mut dst, &counts, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "debug-checks")]
+    #[should_panic(expected = "bucket overflow: level 0, bucket 1")]
+    pub fn test_debug_checks_front_cursor() {
+        use crate::sorts::out_of_place_sort::lr_out_of_place_sort;
+
+        // The front cursor for bucket 1 meets its back cursor halfway through the items
+        let src = [1u8; 8];
+        let mut dst = [0u8; 8];
+        let mut counts = [0usize; 256];
+        counts[1] = 4;
+        counts[3] = 4;
+
+        lr_out_of_place_sort(&src, &mut dst, &counts, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "debug-checks")]
+    #[should_panic(expected = "bucket overflow: level 0, bucket 1")]
+    pub fn test_debug_checks_forward_only() {
+        use crate::sorts::out_of_place_sort::out_of_place_sort;
+
+        // Without the check, the 4th item would be left in bucket 3 rather than panicking
+        let src = [1u8; 6];
+        let mut dst = [0u8; 6];
+        let mut counts = [0usize; 256];
+        counts[1] = 3;
+        counts[3] = 3;
+
+        out_of_place_sort(&src, &mut dst, &counts, 0);
+    }
+}
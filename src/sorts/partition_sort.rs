@@ -0,0 +1,182 @@
+//! `partition_sort` is a two-way partition, used in place of a full radix sort when the counts
+//! show that a level holds only two distinct values, such as a boolean flag.
+//!
+//! It is a branchless Lomuto partition: each item is swapped into place at the boundary of the
+//! lower value, and the boundary only advances if the item has the lower value. As the values
+//! of real data are often unpredictable, this avoids a branch misprediction on roughly every
+//! other item, at the cost of a swap for every item.
+//!
+//! ## Characteristics
+//!
+//!  * in-place
+//!  * unstable
+//!  * single-threaded
+//!
+//! ## Performance
+//!
+//! This avoids the 256-bucket bookkeeping of the other sorts entirely, and only needs a single
+//! pass over the data once the counts are known. As it is a serial pass, the tuners only pick it
+//! for buckets with exactly two distinct values which are too small to benefit from the
+//! multi-threaded sorts, or when sorting on a single thread.
+
+use crate::sorter::Sorter;
+use crate::utils::two_value_buckets;
+use crate::RadixKey;
+
+pub fn partition_sort<T>(bucket: &mut [T], low: u8, level: usize)
+where
+    T: RadixKey,
+{
+    let mut boundary = 0;
+
+    for i in 0..bucket.len() {
+        let is_low = bucket[i].get_level(level) == low;
+        bucket.swap(boundary, i);
+        boundary += is_low as usize;
+    }
+}
+
+impl<'a> Sorter<'a> {
+    pub(crate) fn partition_sort_adapter<T>(
+        &self,
+        bucket: &mut [T],
+        counts: &[usize; 256],
+        level: usize,
+    ) where
+        T: RadixKey + Sized + Send + Copy + Sync,
+    {
+        let low = match two_value_buckets(counts) {
+            Some((low, _)) => low,
+            None => return self.ska_sort_adapter(bucket, counts, level),
+        };
+
+        #[cfg(feature = "work_profiles")]
+        println!("({}) PARTITION", level);
+
+        partition_sort(bucket, low, level);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sorts::partition_sort::partition_sort;
+    use crate::tuner::Algorithm;
+    use crate::utils::test_utils::sort_single_algorithm;
+    use crate::utils::{get_counts, two_value_buckets};
+    use crate::RadixSort;
+    use block_pseudorand::block_rand;
+
+    // Random `u32`s with only two distinct values in their top byte
+    fn two_value_inputs(n: usize) -> Vec<u32> {
+        block_rand::<u32>(n)
+            .into_iter()
+            .map(|v| {
+                if v & 1 == 0 {
+                    v >> 8
+                } else {
+                    0x8000_0000 | (v >> 8)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_partition_sort() {
+        for n in [2, 129, 10_000, 1_000_000] {
+            let mut inputs: Vec<u16> = block_rand::<u16>(n)
+                .into_iter()
+                .map(|v| (v & 0x1FF) | 0x4000)
+                .collect();
+            let (counts, _) = get_counts(&inputs, 1);
+            let (low, high) = match two_value_buckets(&counts) {
+                Some(b) => b,
+                None => continue,
+            };
+
+            partition_sort(&mut inputs, low, 1);
+
+            let split = counts[low as usize];
+            assert!(inputs[..split].iter().all(|v| (v >> 8) as u8 == low));
+            assert!(inputs[split..].iter().all(|v| (v >> 8) as u8 == high));
+        }
+    }
+
+    #[test]
+    pub fn test_two_value_full_sort() {
+        for n in [200, 10_000, 1_000_000] {
+            let mut inputs: Vec<u32> = block_rand::<u32>(n)
+                .into_iter()
+                .map(|v| {
+                    if v & 1 == 0 {
+                        3
+                    } else {
+                        0x8000_0000 | (v >> 16)
+                    }
+                })
+                .collect();
+            let mut expected = inputs.clone();
+            expected.sort_unstable();
+
+            inputs.radix_sort_unstable();
+
+            assert_eq!(inputs, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_partition_sort_fallback() {
+        // Every level of random data has more than two values, so this is sorted with Ska
+        sort_single_algorithm::<u32>(100_000, Algorithm::Partition);
+    }
+
+    #[test]
+    pub fn test_partition_tuning() {
+        let inputs = two_value_inputs(100_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        let mut data = inputs.clone();
+        let trace = data.radix_sort_builder().sort_with_trace();
+        assert_eq!(data, expected);
+        assert_eq!(trace[0].level, 3);
+        assert_eq!(trace[0].algorithm, Algorithm::Partition);
+
+        // The tuner picks the partition, so it can be overridden like any other algorithm
+        let mut data = inputs;
+        let trace = data
+            .radix_sort_builder()
+            .with_algorithm(Algorithm::Lsb)
+            .sort_with_trace();
+        assert_eq!(data, expected);
+        assert!(trace.iter().all(|c| c.algorithm == Algorithm::Lsb));
+    }
+
+    #[test]
+    #[cfg(feature = "multi-threaded")]
+    pub fn test_partition_threads() {
+        let inputs = two_value_inputs(2_000_000);
+        let mut expected = inputs.clone();
+        expected.sort_unstable();
+
+        // Large buckets are left to the multi-threaded sorts when there are threads to share them,
+        // but partitioned when sorting on a single thread
+        for (threads, partitioned) in [(4, false), (1, true)] {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+
+            let mut data = inputs.clone();
+            let trace = pool.install(|| data.radix_sort_builder().sort_with_trace());
+
+            assert_eq!(data, expected);
+            assert_eq!(trace[0].input_len, 2_000_000);
+            assert_eq!(
+                trace[0].algorithm == Algorithm::Partition,
+                partitioned,
+                "{} threads",
+                threads
+            );
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! `presorted_sort` handles input which is mostly sorted already, with a few items out of place,
+//! such as data which is appended to in order and occasionally backfilled. Rather than radix
+//! sorting every item, only the items which are out of place are sorted, then merged back in.
+//!
+//! It works in three steps:
+//!
+//!  1. A sample of adjacent pairs is checked. If more of them are out of order than
+//!     the threshold allows, the input is left to the full radix sort.
+//!  2. A single pass moves every item which is in order into a sorted run at the front of the
+//!     bucket. When an item is smaller than the end of the run, both it and the end of the run are
+//!     pulled out into a separate buffer. This pulls out at most twice as many items as would
+//!     strictly need to move, and a single large item out of place only costs itself and one other.
+//!  3. The pulled out items are radix sorted, then merged back in from the back of the bucket. As
+//!     there are few of them, the runs of the bucket between them are moved as whole blocks.
+//!
+//! If step 2 pulls out more items than the threshold allows, every item is put back and the input
+//! is left to the full radix sort, so a misleading sample only costs a pass over the data.
+//!
+//! ## Characteristics
+//!
+//!  * out-of-place, but only for the items which are pulled out
+//!  * unstable
+//!  * single-threaded, apart from sorting the pulled out items
+//!
+//! ## Performance
+//!
+//! For mostly sorted input this is two passes over the data plus a sort of the items out of
+//! place, rather than a pass for every level. For anything else, the sample rejects it for a
+//! negligible cost.
+
+use crate::utils::{cmp_by_levels, levels_as_u128};
+use crate::RadixKey;
+use std::cmp::Ordering;
+
+// The number of adjacent pairs checked to estimate how many items are out of place
+const SAMPLES: usize = 1024;
+
+/// `looks_presorted` checks a sample of adjacent pairs of `bucket`, returning true if no more than
+/// `threshold` (as a fraction of the sample) are out of order by their first `levels` levels.
+#[inline]
+pub(crate) fn looks_presorted<T>(bucket: &[T], levels: usize, threshold: f64) -> bool
+where
+    T: RadixKey,
+{
+    if bucket.len() < 2 {
+        return true;
+    }
+
+    // Positions are scattered with a multiplicative hash rather than evenly spaced, so that items
+    // out of place at regular intervals can't line up with (or avoid) every sampled pair
+    let pairs = bucket.len() as u128 - 1;
+    let samples = SAMPLES.min(bucket.len() - 1);
+    let descents = (0..samples as u64)
+        .map(|i| ((i.wrapping_mul(0x9E37_79B9_7F4A_7C15) as u128 * pairs) >> 64) as usize)
+        .filter(|&i| is_greater(&bucket[i], &bucket[i + 1], levels))
+        .count();
+
+    descents as f64 <= threshold * samples as f64
+}
+
+/// `presorted_sort_split` moves the items of `bucket` which are in order into a sorted run at the
+/// front, and returns its length along with the items pulled out. If more than `max_pulled` items
+/// would be pulled out, they are put back at the end of `bucket` and `None` is returned, leaving
+/// `bucket` with all of its items, but in an unspecified order.
+#[inline]
+pub(crate) fn presorted_sort_split<T>(
+    bucket: &mut [T],
+    levels: usize,
+    max_pulled: usize,
+) -> Option<(usize, Vec<T>)>
+where
+    T: RadixKey + Copy,
+{
+    let mut pulled = Vec::new();
+    let mut kept = 0;
+
+    for i in 0..bucket.len() {
+        let item = bucket[i];
+
+        if kept > 0 && is_greater(&bucket[kept - 1], &item, levels) {
+            kept -= 1;
+            pulled.push(bucket[kept]);
+            pulled.push(item);
+
+            if pulled.len() > max_pulled {
+                // Every item up to i is either kept or pulled, so they fill bucket[..=i] again
+                bucket[kept..=i].copy_from_slice(&pulled);
+
+                return None;
+            }
+        } else {
+            bucket[kept] = item;
+            kept += 1;
+        }
+    }
+
+    Some((kept, pulled))
+}
+
+/// `presorted_sort_merge` merges the sorted `pulled` items back into `bucket`, whose first `kept`
+/// items are sorted and the rest are free. `kept + pulled.len()` must equal `bucket.len()`.
+#[inline]
+pub(crate) fn presorted_sort_merge<T>(bucket: &mut [T], kept: usize, pulled: &[T], levels: usize)
+where
+    T: RadixKey + Copy,
+{
+    debug_assert_eq!(kept + pulled.len(), bucket.len());
+
+    let mut end = kept;
+
+    // Working from the largest pulled item down, the kept items larger than it are moved up past
+    // the slots left for it and every smaller pulled item
+    for (j, item) in pulled.iter().enumerate().rev() {
+        let pos = bucket[..end].partition_point(|k| !is_greater(k, item, levels));
+
+        bucket.copy_within(pos..end, pos + j + 1);
+        bucket[pos + j] = *item;
+        end = pos;
+    }
+}
+
+// Whether `a` is greater than `b` by their first `levels` levels. Keys of up to 16 levels are
+// compared as a single integer, as comparing level by level is several times slower.
+#[inline]
+fn is_greater<T: RadixKey>(a: &T, b: &T, levels: usize) -> bool {
+    if levels == T::LEVELS && levels <= 16 {
+        return levels_as_u128(a) > levels_as_u128(b);
+    }
+
+    cmp_by_levels(a, b, levels) == Ordering::Greater
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sorts::presorted_sort::{
+        looks_presorted, presorted_sort_merge, presorted_sort_split,
+    };
+    use crate::RadixSort;
+    use block_pseudorand::block_rand;
+
+    // Sorted data with every `every`th item replaced by a random value
+    fn backfilled(len: usize, every: usize) -> Vec<u64> {
+        let noise = block_rand::<u64>(len);
+        let mut data: Vec<u64> = (0..len as u64).map(|v| v * 1_000).collect();
+
+        for i in (0..len).step_by(every) {
+            data[i] = noise[i] % (len as u64 * 1_000);
+        }
+
+        data
+    }
+
+    #[test]
+    pub fn test_split_and_merge() {
+        for (len, every) in [
+            (0, 1),
+            (1, 1),
+            (2, 1),
+            (1_000, 7),
+            (100_000, 100),
+            (100_000, 3),
+        ] {
+            let mut data = backfilled(len, every);
+            let mut expected = data.clone();
+            expected.sort_unstable();
+
+            let (kept, mut pulled) = presorted_sort_split(&mut data, 8, len).unwrap();
+            assert!(data[..kept].windows(2).all(|w| w[0] <= w[1]));
+            assert!(pulled.len() <= 2 * len.div_ceil(every));
+
+            pulled.radix_sort_unstable();
+            presorted_sort_merge(&mut data, kept, &pulled, 8);
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_split_over_max() {
+        let mut data = backfilled(100_000, 10);
+        let mut expected = data.clone();
+        expected.sort_unstable();
+
+        assert!(presorted_sort_split(&mut data, 8, 1_000).is_none());
+
+        data.sort_unstable();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    pub fn test_looks_presorted() {
+        assert!(looks_presorted(&backfilled(100_000, 1_000), 8, 0.01));
+        assert!(!looks_presorted(&backfilled(100_000, 10), 8, 0.01));
+        assert!(!looks_presorted(&block_rand::<u64>(100_000), 8, 0.01));
+        assert!(looks_presorted::<u64>(&[], 8, 0.0));
+    }
+}
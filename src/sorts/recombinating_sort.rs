@@ -38,8 +38,7 @@ pub fn recombinating_sort<T>(
 ) where
     T: RadixKey + Sized + Send + Copy + Sync,
 {
-    let bucket_len = bucket.len();
-    let mut tmp_bucket = get_tmp_bucket::<T>(bucket_len);
+    let mut tmp_bucket = get_tmp_bucket(bucket);
 
     let locals: Vec<([usize; 256], [usize; 256])> = bucket
         .par_chunks(tile_size)
@@ -92,19 +91,13 @@ impl<'a> Sorter<'a> {
         }
 
         recombinating_sort(bucket, counts, tile_counts, tile_size, level);
-
-        if level == 0 {
-            return;
-        }
-
-        self.director(bucket, counts, level - 1);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::sorter::Sorter;
-    use crate::tuner::Algorithm;
+    use crate::tuner::{Algorithm, CountingParams};
     use crate::tuners::StandardTuner;
     use crate::utils::test_utils::{
         sort_comparison_suite, sort_single_algorithm, validate_u32_patterns, NumericTest,
@@ -123,11 +116,12 @@ mod tests {
             let level = T::LEVELS - 1;
             let tile_size = cdiv(inputs.len(), current_num_threads());
 
-            if inputs.len() == 0 {
+            if inputs.is_empty() {
                 return;
             }
 
-            let (tile_counts, _) = get_tile_counts(inputs, tile_size, level);
+            let (tile_counts, _) =
+                get_tile_counts(inputs, tile_size, level, &CountingParams::default());
             let counts = aggregate_tile_counts(&tile_counts);
 
             sorter.recombinating_sort_adapter(
@@ -136,7 +130,11 @@ mod tests {
                 &tile_counts,
                 tile_size,
                 T::LEVELS - 1,
-            )
+            );
+
+            if T::LEVELS > 1 {
+                sorter.director(inputs, &counts, T::LEVELS - 2);
+            }
         });
     }
 
@@ -183,14 +181,16 @@ mod tests {
             let level = u32::LEVELS - 1;
             let tile_size = cdiv(inputs.len(), current_num_threads());
 
-            if inputs.len() == 0 {
+            if inputs.is_empty() {
                 return;
             }
 
-            let (tile_counts, _) = get_tile_counts(inputs, tile_size, level);
+            let (tile_counts, _) =
+                get_tile_counts(inputs, tile_size, level, &CountingParams::default());
             let counts = aggregate_tile_counts(&tile_counts);
 
-            sorter.recombinating_sort_adapter(inputs, &counts, &tile_counts, tile_size, level)
+            sorter.recombinating_sort_adapter(inputs, &counts, &tile_counts, tile_size, level);
+            sorter.director(inputs, &counts, level - 1);
         });
     }
 }
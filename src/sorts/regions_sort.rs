@@ -6,6 +6,8 @@
 //! In ACM Symposium on Parallelism in Algorithms and Architectures (SPAA), 2019.
 //!
 //! Summary:
+//!
+//! ```text
 //! 1. Split into buckets
 //! 2. Compute counts for each bucket and sort each bucket in-place
 //! 3. Generate global counts
@@ -19,6 +21,7 @@
 //!             4.2.2.2/b: If I is bigger than O, keep the remainder of I in the queue and continue
 //!             4.2.2.2/c: If O is bigger than I, keep the remainder of O in the queue and continue
 //!             4.2.2.3: Swap items in C heading to O, with items in I destined for C (items in C may or may not be destined for O ultimately)
+//! ```
 //!
 //! ## Characteristics
 //!
@@ -277,25 +280,19 @@ impl<'a> Sorter<'a> {
         }
 
         regions_sort(bucket, counts, tile_counts, tile_size, level);
-
-        if level == 0 {
-            return;
-        }
-
-        self.director(bucket, counts, level - 1);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::sorter::Sorter;
-    use crate::tuner::Algorithm;
+    use crate::tuner::{Algorithm, CountingParams};
     use crate::tuners::StandardTuner;
     use crate::utils::test_utils::{
         sort_comparison_suite, sort_single_algorithm, validate_u32_patterns, NumericTest,
     };
     use crate::utils::{aggregate_tile_counts, cdiv, get_tile_counts};
-    use crate::RadixKey;
+    use crate::{RadixKey, RadixSort};
     use rayon::current_num_threads;
 
     fn test_regions_sort<T>(shift: T)
@@ -305,15 +302,20 @@ mod tests {
         let sorter = Sorter::new(true, &StandardTuner);
 
         sort_comparison_suite(shift, |inputs| {
-            if inputs.len() == 0 {
+            if inputs.is_empty() {
                 return;
             }
 
             let tile_size = cdiv(inputs.len(), current_num_threads());
-            let (tile_counts, _) = get_tile_counts(inputs, tile_size, T::LEVELS - 1);
+            let (tile_counts, _) =
+                get_tile_counts(inputs, tile_size, T::LEVELS - 1, &CountingParams::default());
             let counts = aggregate_tile_counts(&tile_counts);
 
             sorter.regions_sort_adapter(inputs, &counts, &tile_counts, tile_size, T::LEVELS - 1);
+
+            if T::LEVELS > 1 {
+                sorter.director(inputs, &counts, T::LEVELS - 2);
+            }
         });
     }
 
@@ -357,15 +359,31 @@ mod tests {
         let sorter = Sorter::new(true, &StandardTuner);
 
         validate_u32_patterns(|inputs| {
-            if inputs.len() == 0 {
+            if inputs.is_empty() {
                 return;
             }
 
             let tile_size = cdiv(inputs.len(), current_num_threads());
-            let (tile_counts, _) = get_tile_counts(inputs, tile_size, u32::LEVELS - 1);
+            let (tile_counts, _) = get_tile_counts(
+                inputs,
+                tile_size,
+                u32::LEVELS - 1,
+                &CountingParams::default(),
+            );
             let counts = aggregate_tile_counts(&tile_counts);
 
             sorter.regions_sort_adapter(inputs, &counts, &tile_counts, tile_size, u32::LEVELS - 1);
+            sorter.director(inputs, &counts, u32::LEVELS - 2);
+        });
+    }
+
+    #[test]
+    pub fn test_with_algorithm() {
+        validate_u32_patterns(|inputs| {
+            inputs
+                .radix_sort_builder()
+                .with_algorithm(Algorithm::Regions)
+                .sort();
         });
     }
 }
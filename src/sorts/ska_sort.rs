@@ -101,12 +101,6 @@ impl<'a> Sorter<'a> {
         let end_offsets = get_end_offsets(counts, &prefix_sums);
 
         ska_sort(bucket, &mut prefix_sums, &end_offsets, level);
-
-        if level == 0 {
-            return;
-        }
-
-        self.director(bucket, counts, level - 1);
     }
 }
 
@@ -131,6 +125,10 @@ mod tests {
             let (counts, _) = get_counts(inputs, T::LEVELS - 1);
 
             sorter.ska_sort_adapter(inputs, &counts, T::LEVELS - 1);
+
+            if T::LEVELS > 1 {
+                sorter.director(inputs, &counts, T::LEVELS - 2);
+            }
         });
     }
 
@@ -177,6 +175,7 @@ mod tests {
             let (counts, _) = get_counts(inputs, u32::LEVELS - 1);
 
             sorter.ska_sort_adapter(inputs, &counts, u32::LEVELS - 1);
+            sorter.director(inputs, &counts, u32::LEVELS - 2);
         });
     }
 }
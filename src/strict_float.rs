@@ -0,0 +1,91 @@
+use crate::RadixKey;
+
+/// `StrictFloat` wraps an `f32` or `f64` to sort it in the same order as the plain float, but
+/// treats a NaN key as a bug. In debug builds, sorting a NaN panics. Release builds skip the
+/// check, and NaNs are sorted to either end as with the plain float.
+///
+/// This is useful for catching data-quality issues during development, where a NaN would
+/// otherwise silently end up clustered at the ends of the sorted output.
+///
+/// ```
+/// use rdst::{RadixSort, StrictFloat};
+///
+/// let mut values = vec![StrictFloat(2.5f64), StrictFloat(-1.0), StrictFloat(0.0)];
+/// values.radix_sort_unstable();
+///
+/// assert_eq!(values, [StrictFloat(-1.0), StrictFloat(0.0), StrictFloat(2.5)]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct StrictFloat<T>(pub T);
+
+impl RadixKey for StrictFloat<f32> {
+    const LEVELS: usize = 4;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        debug_assert!(!self.0.is_nan(), "StrictFloat: NaN sort key");
+
+        self.0.get_level(level)
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        debug_assert!(!self.0.is_nan(), "StrictFloat: NaN sort key");
+
+        self.0.get_all_levels(out)
+    }
+}
+
+impl RadixKey for StrictFloat<f64> {
+    const LEVELS: usize = 8;
+
+    #[inline]
+    fn get_level(&self, level: usize) -> u8 {
+        debug_assert!(!self.0.is_nan(), "StrictFloat: NaN sort key");
+
+        self.0.get_level(level)
+    }
+
+    #[inline]
+    fn get_all_levels(&self, out: &mut [u8]) {
+        debug_assert!(!self.0.is_nan(), "StrictFloat: NaN sort key");
+
+        self.0.get_all_levels(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RadixSort, StrictFloat};
+    use block_pseudorand::block_rand;
+
+    #[test]
+    pub fn test_matches_float_order() {
+        let inputs: Vec<f64> = block_rand::<f64>(100_000)
+            .into_iter()
+            .filter(|v| !v.is_nan())
+            .collect();
+
+        let mut expected = inputs.clone();
+        expected.radix_sort_unstable();
+
+        let mut strict: Vec<StrictFloat<f64>> = inputs.into_iter().map(StrictFloat).collect();
+        strict.radix_sort_unstable();
+
+        let actual: Vec<f64> = strict.into_iter().map(|v| v.0).collect();
+        assert_eq!(actual, expected);
+
+        let mut strict: Vec<StrictFloat<f32>> = vec![StrictFloat(1.5), StrictFloat(-0.5)];
+        strict.radix_sort_unstable();
+        assert_eq!(strict, [StrictFloat(-0.5), StrictFloat(1.5)]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "StrictFloat: NaN sort key")]
+    pub fn test_nan_panics() {
+        let mut values = vec![StrictFloat(1.0f32), StrictFloat(f32::NAN), StrictFloat(0.0)];
+        values.radix_sort_unstable();
+    }
+}
@@ -0,0 +1,56 @@
+use crate::radix_sort_error::RadixSortError;
+
+/// `init()` starts rayon's global thread pool, which the multi-threaded sorts run on, and waits
+/// until every thread in it is running. Otherwise the pool is started by the first sort, which
+/// pays for spawning the threads.
+///
+/// Calling this is optional, and only worthwhile for latency-sensitive code which would rather
+/// take the cost up-front, such as at startup. Later sorts reuse the same pool, and calling
+/// `init()` again does nothing.
+///
+/// ```
+/// rdst::init();
+/// ```
+pub fn init() {
+    rayon::broadcast(|_| ());
+}
+
+/// `init_with_threads()` behaves like `init()`, but builds the global thread pool with `threads`
+/// threads rather than one per CPU. Passing `0` picks the default, as for rayon.
+///
+/// The global pool can only be built once per process. If it is already running, whether from an
+/// earlier sort, a call to `init()`, or other code using rayon's global pool, this returns
+/// `RadixSortError::ThreadPoolInit` and the existing pool is left as it is.
+///
+/// ```
+/// use rdst::RadixSortError;
+///
+/// match rdst::init_with_threads(4) {
+///     Ok(()) => {}
+///     Err(RadixSortError::ThreadPoolInit) => eprintln!("thread pool was already running"),
+///     Err(e) => panic!("{}", e),
+/// }
+/// ```
+pub fn init_with_threads(threads: usize) -> Result<(), RadixSortError> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build_global()
+        .map_err(|_| RadixSortError::ThreadPoolInit)?;
+
+    init();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{init, init_with_threads, RadixSortError};
+
+    #[test]
+    pub fn test_init() {
+        init();
+        init();
+
+        assert_eq!(init_with_threads(1), Err(RadixSortError::ThreadPoolInit));
+    }
+}
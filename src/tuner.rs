@@ -7,29 +7,221 @@ pub struct TuningParams {
     pub parent_len: Option<usize>,
 }
 
+/// `Algorithm` is the sorting algorithm chosen by a `Tuner` for a bucket. MSB algorithms sort a
+/// single level, after which each resulting bucket is handed back to the tuner for the next level.
+/// LSB algorithms sort all remaining levels at once.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 #[cfg(feature = "multi-threaded")]
 pub enum Algorithm {
+    /// Multi-threaded, out-of-place MSB sort of a single level, based on `mt_lsb_sort`
     MtOop,
+    /// Multi-threaded, out-of-place LSB sort of all remaining levels. Stable
     MtLsb,
+    /// Multi-threaded MSB sort which scans buckets into per-thread stashes. Fastest for large inputs
     Scanning,
+    /// Multi-threaded MSB sort which sorts tiles out-of-place, then recombines them
     Recombinating,
+    /// Comparison-based sort of all remaining levels, for very small buckets. Items are compared
+    /// by their `RadixKey` levels, not by `Ord`
     Comparative,
+    /// Single-threaded LSB sort of all remaining levels, reading from both ends at once
     LrLsb,
+    /// Single-threaded, out-of-place LSB sort of all remaining levels. Stable
     Lsb,
+    /// Multi-threaded, mostly in-place MSB sort based on Obeya et al.'s Regions Sort
     Regions,
+    /// Single-threaded, in-place MSB sort (American flag sort, as in ska_sort)
     Ska,
+    /// Single-threaded, in-place MSB two-way partition, for buckets with exactly two distinct
+    /// values on the level being sorted, such as a boolean flag. Other buckets are sorted with
+    /// `Ska` instead
+    Partition,
 }
 
+/// `Algorithm` is the sorting algorithm chosen by a `Tuner` for a bucket. MSB algorithms sort a
+/// single level, after which each resulting bucket is handed back to the tuner for the next level.
+/// LSB algorithms sort all remaining levels at once.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 #[cfg(not(feature = "multi-threaded"))]
 pub enum Algorithm {
+    /// Comparison-based sort of all remaining levels, for very small buckets. Items are compared
+    /// by their `RadixKey` levels, not by `Ord`
     Comparative,
+    /// Single-threaded LSB sort of all remaining levels, reading from both ends at once
     LrLsb,
+    /// Single-threaded, out-of-place LSB sort of all remaining levels. Stable
     Lsb,
+    /// Single-threaded, in-place MSB sort (American flag sort, as in ska_sort)
     Ska,
+    /// Single-threaded, in-place MSB two-way partition, for buckets with exactly two distinct
+    /// values on the level being sorted, such as a boolean flag. Other buckets are sorted with
+    /// `Ska` instead
+    Partition,
+}
+
+/// `AlgorithmChoice` records the algorithm a `Tuner` picked for one bucket, as returned by
+/// `RadixSortBuilder::sort_with_trace()`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct AlgorithmChoice {
+    /// The number of items in the bucket
+    pub input_len: usize,
+    /// The level the bucket was being sorted on, where `0` is the least significant
+    pub level: usize,
+    /// The algorithm the tuner picked for the bucket
+    pub algorithm: Algorithm,
+}
+
+/// `CountingParams` controls when counting a bucket is split across multiple threads. The
+/// crossover point is very machine-specific, so the defaults may not suit your hardware. The
+/// `tune_counts` bench in `benches/tuning_parameters.rs` can be used to find your own values.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CountingParams {
+    /// Buckets of 4-byte items shorter than this are counted on a single thread. For other item
+    /// sizes the threshold is scaled, see `par_count_threshold_for()`. Default: `400_000`
+    pub par_count_threshold: usize,
+    /// Each thread receives this many chunks to count when counting in parallel. Default: `8`
+    pub par_count_chunk_divisor: usize,
+}
+
+impl CountingParams {
+    /// `par_count_threshold_for` returns the bucket length above which a bucket of `T` is counted
+    /// in parallel. Counting reads one byte of each item, but pulls in the cache line holding it,
+    /// so larger items cost more memory traffic per item and parallel counting pays off for
+    /// shorter buckets, while smaller items pay off later. So `par_count_threshold`, which is for
+    /// 4-byte items such as `u32`, is scaled by `4 / size_of::<T>()`, with sizes capped at a
+    /// 64-byte cache line, as larger items still only pull in one line each.
+    #[inline]
+    pub fn par_count_threshold_for<T>(&self) -> usize {
+        let size = std::mem::size_of::<T>().clamp(1, 64);
+
+        self.par_count_threshold.saturating_mul(4) / size
+    }
+}
+
+impl Default for CountingParams {
+    fn default() -> Self {
+        Self {
+            par_count_threshold: 400_000,
+            par_count_chunk_divisor: 8,
+        }
+    }
+}
+
+/// `ScanningParams` controls the memory used by the scanning sort (`Algorithm::Scanning`), and
+/// how often its workers yield to other work.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ScanningParams {
+    /// The maximum number of elements each worker thread holds in its temporary stash before it
+    /// stops picking up new data, and instead only reads as much as it can write back out. This
+    /// bounds peak memory on skewed data, at some cost to throughput. Default: `usize::MAX`
+    /// (unbounded)
+    pub max_stashed: usize,
+    /// The number of elements each worker thread reads between calls to `Tuner::scanning_yield()`,
+    /// which by default lets the thread pool run other pending work. The scanning sort otherwise
+    /// holds every thread in the pool until it completes, which can starve latency-sensitive work
+    /// sharing the pool. Smaller intervals interleave more often, but any work run by a yield
+    /// delays the sort. Default: `usize::MAX` (never yield)
+    pub yield_interval: usize,
+}
+
+impl Default for ScanningParams {
+    fn default() -> Self {
+        Self {
+            max_stashed: usize::MAX,
+            yield_interval: usize::MAX,
+        }
+    }
+}
+
+/// `TileParams` controls how large buckets are split into tiles for the multi-threaded sorts
+/// (`Algorithm::MtLsb`, `Algorithm::MtOop`, `Algorithm::Recombinating` and `Algorithm::Regions`).
+/// Each tile is counted and scattered as a separate task, so the tile size trades load-balancing
+/// against overhead. Smaller tiles give the thread pool more, finer-grained tasks to spread
+/// around, which helps when some tiles take longer than others, such as on skewed data. Larger
+/// tiles mean fewer tasks and fewer sets of counts to combine, which is cheaper when every tile
+/// takes about as long, as on uniform data.
+///
+/// The tile size is `max(min_tile_size, ceil(len / (threads * tiles_per_thread)))`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TileParams {
+    /// The smallest tile to split a bucket into. Default: `30_000`
+    pub min_tile_size: usize,
+    /// The number of tiles each thread receives. Default: `1`
+    pub tiles_per_thread: usize,
+}
+
+impl Default for TileParams {
+    fn default() -> Self {
+        Self {
+            min_tile_size: 30_000,
+            tiles_per_thread: 1,
+        }
+    }
 }
 
 pub trait Tuner {
     fn pick_algorithm(&self, p: &TuningParams, counts: &[usize]) -> Algorithm;
+
+    /// `counting_params` returns the thresholds used when counting buckets. Override this if
+    /// benchmarking shows different values are better on your machine.
+    #[inline]
+    fn counting_params(&self) -> CountingParams {
+        CountingParams::default()
+    }
+
+    /// `scanning_params` returns the parameters used by the scanning sort. Override this to cap
+    /// the memory used by the scanning sort's per-thread stash.
+    #[inline]
+    fn scanning_params(&self) -> ScanningParams {
+        ScanningParams::default()
+    }
+
+    /// `tile_params` returns the parameters used to split buckets into tiles for the
+    /// multi-threaded sorts. Override this to use smaller or larger tiles.
+    #[inline]
+    fn tile_params(&self) -> TileParams {
+        TileParams::default()
+    }
+
+    /// `min_task_size` returns the size of the smallest bucket which the multi-threaded sorts
+    /// spawn as a separate task. Smaller buckets, and every bucket below them on the next levels,
+    /// are sorted serially by the task which found them, so that keys with many levels don't
+    /// spawn a task for every tiny bucket. Override this to spawn more, finer-grained tasks, or
+    /// fewer, larger ones. Default: `4_096`
+    #[inline]
+    fn min_task_size(&self) -> usize {
+        4_096
+    }
+
+    /// `insertion_sort_threshold` returns the size of the largest bucket which is sorted with an
+    /// insertion sort, rather than `sort_unstable_by()`, once a bucket is small enough to be
+    /// sorted by comparison. This only applies to keys of up to 16 levels. Override this if the
+    /// `tune_insertion_sort_threshold` bench shows a different crossover is faster on your
+    /// machine. Default: `20`
+    #[inline]
+    fn insertion_sort_threshold(&self) -> usize {
+        20
+    }
+
+    /// `counting_sort_threshold` returns the size of the smallest input of `u16` or `i16` keys
+    /// that is sorted by counting every key into 65536 buckets and writing the keys back in order,
+    /// rather than with a pass over 256 buckets for each level. This is single-threaded, so it only
+    /// applies to sorts with `with_parallel(false)`, or without the `multi-threaded` feature.
+    /// Override this if the `tune_counting_sort_threshold` bench shows a different crossover is
+    /// faster on your machine, or return `usize::MAX` to never use it. Default: `500_000`
+    #[inline]
+    fn counting_sort_threshold(&self) -> usize {
+        500_000
+    }
+
+    /// `scanning_yield` is called by each of the scanning sort's worker threads after reading
+    /// `ScanningParams::yield_interval` elements, while it holds no locks. Yields are skipped until
+    /// every worker has started. By default, this runs any other work pending in the current rayon
+    /// thread pool via `rayon::yield_now()`. Override this to hand control to your own scheduler
+    /// instead.
+    #[inline]
+    fn scanning_yield(&self) {
+        #[cfg(feature = "multi-threaded")]
+        rayon::yield_now();
+    }
 }
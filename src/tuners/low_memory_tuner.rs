@@ -9,6 +9,7 @@
 //!  * dynamic msb / lsb
 
 use crate::tuner::{Algorithm, Tuner, TuningParams};
+use crate::utils::two_value_buckets;
 
 pub struct LowMemoryTuner;
 impl Tuner for LowMemoryTuner {
@@ -18,6 +19,10 @@ impl Tuner for LowMemoryTuner {
             return Algorithm::Comparative;
         }
 
+        if (p.threads == 1 || p.input_len <= 1_000_000) && two_value_buckets(counts).is_some() {
+            return Algorithm::Partition;
+        }
+
         if p.input_len >= 5_000 {
             let distribution_threshold = (p.input_len / 256) * 2;
 
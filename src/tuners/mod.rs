@@ -1,11 +1,47 @@
+//! The tuners used by rdst. These can be used directly with `radix_sort_builder().with_tuner()`,
+//! or wrapped by your own `Tuner` to override only some of their decisions.
+//!
+//! ```
+//! use rdst::RadixSort;
+//! use rdst::tuner::{Algorithm, Tuner, TuningParams};
+//! use rdst::tuners::DefaultTuner;
+//!
+//! struct MyTuner;
+//!
+//! impl Tuner for MyTuner {
+//!     fn pick_algorithm(&self, p: &TuningParams, counts: &[usize]) -> Algorithm {
+//!         if (5_000_000..50_000_000).contains(&p.input_len) {
+//!             Algorithm::Lsb
+//!         } else {
+//!             DefaultTuner {}.pick_algorithm(p, counts)
+//!         }
+//!     }
+//! }
+//!
+//! let mut my_vec: Vec<usize> = vec![10, 25, 9, 22, 6];
+//! my_vec
+//!     .radix_sort_builder()
+//!     .with_tuner(&MyTuner {})
+//!     .sort();
+//! ```
+
 #[cfg(feature = "multi-threaded")]
 mod low_memory_tuner;
+mod single_algorithm_tuner;
 mod single_threaded_tuner;
 #[cfg(feature = "multi-threaded")]
 mod standard_tuner;
 
 #[cfg(feature = "multi-threaded")]
 pub use low_memory_tuner::LowMemoryTuner;
+pub use single_algorithm_tuner::SingleAlgorithmTuner;
 pub use single_threaded_tuner::SingleThreadedTuner;
 #[cfg(feature = "multi-threaded")]
 pub use standard_tuner::StandardTuner;
+
+/// `DefaultTuner` is the tuner used when no other tuner is selected. This is `StandardTuner` when
+/// the `multi-threaded` feature is enabled, and `SingleThreadedTuner` otherwise.
+#[cfg(feature = "multi-threaded")]
+pub type DefaultTuner = StandardTuner;
+#[cfg(not(feature = "multi-threaded"))]
+pub type DefaultTuner = SingleThreadedTuner;
@@ -0,0 +1,16 @@
+//! `SingleAlgorithmTuner` is a tuner which always picks the same algorithm, at every level and for
+//! every bucket size. This is mostly useful for benchmarking or isolating issues with a particular
+//! algorithm, via `radix_sort_builder().with_algorithm()`.
+
+use crate::tuner::{Algorithm, Tuner, TuningParams};
+
+pub struct SingleAlgorithmTuner {
+    pub algorithm: Algorithm,
+}
+
+impl Tuner for SingleAlgorithmTuner {
+    #[inline]
+    fn pick_algorithm(&self, _p: &TuningParams, _counts: &[usize]) -> Algorithm {
+        self.algorithm
+    }
+}
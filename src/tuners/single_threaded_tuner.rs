@@ -9,6 +9,7 @@
 //!  * dynamic msb / lsb
 
 use crate::tuner::{Algorithm, Tuner, TuningParams};
+use crate::utils::two_value_buckets;
 
 pub struct SingleThreadedTuner;
 impl Tuner for SingleThreadedTuner {
@@ -18,6 +19,10 @@ impl Tuner for SingleThreadedTuner {
             return Algorithm::Comparative;
         }
 
+        if two_value_buckets(counts).is_some() {
+            return Algorithm::Partition;
+        }
+
         let depth = p.total_levels - p.level - 1;
 
         if p.input_len >= 5_000 {
@@ -6,6 +6,7 @@
 //!  * dynamic msb / lsb
 
 use crate::tuner::{Algorithm, Tuner, TuningParams};
+use crate::utils::two_value_buckets;
 
 pub struct StandardTuner;
 impl Tuner for StandardTuner {
@@ -15,6 +16,12 @@ impl Tuner for StandardTuner {
             return Algorithm::Comparative;
         }
 
+        // Partitioning is a single serial pass, which beats the other single-threaded sorts, but
+        // not the multi-threaded ones on larger buckets
+        if (p.threads == 1 || p.input_len <= 350_000) && two_value_buckets(counts).is_some() {
+            return Algorithm::Partition;
+        }
+
         let depth = p.total_levels - p.level - 1;
 
         if p.input_len >= 5_000 {
@@ -1,3 +1,5 @@
+#![allow(clippy::type_complexity)]
+
 use crate::utils::test_utils::{gen_inputs, NumericTest};
 use criterion::{AxisScale, BatchSize, BenchmarkId, Criterion, PlotConfiguration, Throughput};
 use std::time::Duration;
@@ -48,7 +50,7 @@ where
 
         out.push(inputs[start..end].to_vec());
 
-        len = len / 2;
+        len /= 2;
         if len == 0 {
             break;
         }
@@ -78,7 +80,7 @@ pub fn bench_common<T>(
         group.throughput(Throughput::Elements(l as u64));
 
         for t in tests.iter() {
-            group.bench_with_input(BenchmarkId::new((*t).0, l), set, |bench, set| {
+            group.bench_with_input(BenchmarkId::new(t.0, l), set, |bench, set| {
                 bench.iter_batched(|| set.clone(), &*t.1, BatchSize::SmallInput);
             });
         }
@@ -105,12 +107,12 @@ pub fn bench_medley<T>(
     group.throughput(Throughput::Elements(len));
 
     for t in tests.iter() {
-        group.bench_with_input(BenchmarkId::new((*t).0, len), &0u32, |bench, _set| {
+        group.bench_with_input(BenchmarkId::new(t.0, len), &0u32, |bench, _set| {
             bench.iter_batched(
                 || input_sets.clone(),
                 |input| {
                     for set in input {
-                        (*t).1(set);
+                        (t.1)(set);
                     }
                 },
                 BatchSize::SmallInput,
@@ -139,19 +141,15 @@ pub fn bench_single<T>(
     group.throughput(Throughput::Elements(input.len() as u64));
 
     for t in tests.iter() {
-        group.bench_with_input(
-            BenchmarkId::new((*t).0, input.len()),
-            &0u32,
-            |bench, _set| {
-                bench.iter_batched(
-                    || input.clone(),
-                    |input| {
-                        (*t).1(input);
-                    },
-                    BatchSize::SmallInput,
-                );
-            },
-        );
+        group.bench_with_input(BenchmarkId::new(t.0, input.len()), &0u32, |bench, _set| {
+            bench.iter_batched(
+                || input.clone(),
+                |input| {
+                    (t.1)(input);
+                },
+                BatchSize::SmallInput,
+            );
+        });
     }
 
     group.finish();
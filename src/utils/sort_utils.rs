@@ -1,9 +1,99 @@
+use crate::tuner::CountingParams;
 use crate::RadixKey;
 #[cfg(feature = "multi-threaded")]
 use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::collections::TryReserveError;
 #[cfg(feature = "multi-threaded")]
 use std::sync::mpsc::channel;
 
+/// `checked_inc!(cursor, limit, level, bucket)` moves a bucket's front write cursor forward by
+/// one, after an item has been written at it. `limit` is the first slot past the bucket's free
+/// space: its end, or its back write cursor when the bucket is filled from both ends. With the
+/// `debug-checks` feature, passing `limit` panics with the level and bucket, as it means more
+/// items were distributed into the bucket than it was counted to hold. Without the feature, this
+/// is a plain wrapping addition.
+macro_rules! checked_inc {
+    ($cursor:expr, $limit:expr, $level:expr, $bucket:expr) => {{
+        let cursor = $cursor.wrapping_add(1);
+
+        #[cfg(feature = "debug-checks")]
+        if cursor > $limit {
+            panic!("bucket overflow: level {}, bucket {}", $level, $bucket);
+        }
+
+        cursor
+    }};
+}
+
+/// `checked_dec!(cursor, floor, level, bucket)` moves a bucket's back write cursor back by one,
+/// before an item is written at it. `floor` is the bucket's front write cursor. With the
+/// `debug-checks` feature, reaching `floor` panics as for `checked_inc!`. Without the feature,
+/// this is a plain wrapping subtraction.
+macro_rules! checked_dec {
+    ($cursor:expr, $floor:expr, $level:expr, $bucket:expr) => {{
+        #[cfg(feature = "debug-checks")]
+        if $cursor <= $floor {
+            panic!("bucket overflow: level {}, bucket {}", $level, $bucket);
+        }
+
+        $cursor.wrapping_sub(1)
+    }};
+}
+
+pub(crate) use checked_dec;
+pub(crate) use checked_inc;
+
+/// `LevelSamples` records the byte at `level` of a few evenly spaced items while counting, to be
+/// re-checked as the scatter starts, before any item has moved. A mismatch means `get_level`
+/// returned different values for the same item, such as from interior mutability, which would
+/// otherwise silently mis-sort. The check only runs with `debug_assertions`; in release builds
+/// this is empty and both steps compile to nothing.
+pub struct LevelSamples {
+    #[cfg(debug_assertions)]
+    level: usize,
+    #[cfg(debug_assertions)]
+    samples: [(usize, u8); 8],
+}
+
+impl LevelSamples {
+    #[inline]
+    #[allow(unused_variables)]
+    pub fn record<T: RadixKey>(bucket: &[T], level: usize) -> Self {
+        #[cfg(debug_assertions)]
+        {
+            let step = bucket.len() / 8;
+            let mut samples = [(0, 0); 8];
+
+            for (i, s) in samples.iter_mut().enumerate() {
+                let index = i * step + step / 2;
+                *s = (index, bucket[index].get_level(level));
+            }
+
+            Self { level, samples }
+        }
+
+        #[cfg(not(debug_assertions))]
+        Self {}
+    }
+
+    #[inline]
+    #[allow(unused_variables)]
+    pub fn check<T: RadixKey>(&self, bucket: &[T]) {
+        #[cfg(debug_assertions)]
+        for &(index, counted) in self.samples.iter() {
+            let scattered = bucket[index].get_level(self.level);
+
+            assert_eq!(
+                counted, scattered,
+                "RadixKey::get_level is not deterministic: item {} returned {} at level {} while \
+                 counting, but {} when scattering",
+                index, counted, self.level, scattered
+            );
+        }
+    }
+}
+
 #[inline]
 pub fn get_prefix_sums(counts: &[usize; 256]) -> [usize; 256] {
     let mut sums = [0usize; 256];
@@ -29,33 +119,41 @@ pub fn get_end_offsets(counts: &[usize; 256], prefix_sums: &[usize; 256]) -> [us
 
 #[inline]
 #[cfg(any(test, bench, tuning))]
-pub fn par_get_counts<T>(bucket: &[T], level: usize) -> ([usize; 256], bool)
+pub fn par_get_counts<T>(
+    bucket: &[T],
+    level: usize,
+    params: &CountingParams,
+) -> ([usize; 256], bool)
 where
     T: RadixKey + Sized + Send + Sync,
 {
-    if bucket.len() == 0 {
+    if bucket.is_empty() {
         return ([0usize; 256], true);
     }
 
-    let (counts, sorted, _, _) = par_get_counts_with_ends(bucket, level);
+    let (counts, sorted, _, _) = par_get_counts_with_ends(bucket, level, params);
     (counts, sorted)
 }
 
 #[inline]
 #[cfg(feature = "multi-threaded")]
-pub fn par_get_counts_with_ends<T>(bucket: &[T], level: usize) -> ([usize; 256], bool, u8, u8)
+pub fn par_get_counts_with_ends<T>(
+    bucket: &[T],
+    level: usize,
+    params: &CountingParams,
+) -> ([usize; 256], bool, u8, u8)
 where
     T: RadixKey + Sized + Send + Sync,
 {
     #[cfg(feature = "work_profiles")]
     println!("({}) PAR_COUNT", level);
 
-    if bucket.len() < 400_000 {
+    if bucket.len() < params.par_count_threshold_for::<T>() {
         return get_counts_with_ends(bucket, level);
     }
 
     let threads = rayon::current_num_threads();
-    let chunk_divisor = 8;
+    let chunk_divisor = params.par_count_chunk_divisor;
     let chunk_size = (bucket.len() / threads / chunk_divisor) + 1;
     let chunks = bucket.par_chunks(chunk_size);
     let len = chunks.len();
@@ -189,28 +287,101 @@ where
     (counts, sorted)
 }
 
-#[allow(clippy::uninit_vec)]
+/// `get_all_counts` counts every level of every item in a single pass, using
+/// `RadixKey::get_all_levels` to extract all of the bytes of each key at once.
+/// The returned Vec is indexed by level.
+///
+/// This allocates one `[usize; 256]` per level, so 32 KiB on 64-bit targets for a 16 level key
+/// such as `u128`. Keys without `BATCHED_LEVELS` never allocate this, as they are counted one
+/// level at a time.
+#[inline]
+pub fn get_all_counts<T>(bucket: &[T]) -> Vec<[usize; 256]>
+where
+    T: RadixKey,
+{
+    let mut counts = vec![[0usize; 256]; T::LEVELS];
+    let mut levels = vec![0u8; T::LEVELS];
+
+    for item in bucket {
+        item.get_all_levels(&mut levels);
+
+        for (c, b) in counts.iter_mut().zip(levels.iter()) {
+            c[*b as usize] += 1;
+        }
+    }
+
+    counts
+}
+
+// Returns a temporary buffer the same length as `bucket`, for sorting it out-of-place. Every value
+// is overwritten before it is read, so its contents don't matter.
+#[cfg(not(feature = "safe-only"))]
+#[allow(clippy::uninit_vec, unsafe_code)]
 #[inline]
-pub fn get_tmp_bucket<T>(len: usize) -> Vec<T> {
-    let mut tmp_bucket = Vec::with_capacity(len);
+pub fn get_tmp_bucket<T>(bucket: &[T]) -> Vec<T> {
+    let mut tmp_bucket = Vec::with_capacity(bucket.len());
     unsafe {
         // Safety: This will leave the vec with potentially uninitialized data
         // however as we account for every value when placing things
         // into tmp_bucket, this is "safe". This is used because it provides a
         // very significant speed improvement over resize, to_vec etc.
-        tmp_bucket.set_len(len);
+        tmp_bucket.set_len(bucket.len());
     }
 
     tmp_bucket
 }
 
+// With `safe-only`, the buffer is initialized by copying `bucket`, as `T` is `Copy` but not
+// necessarily `Default`.
+#[cfg(feature = "safe-only")]
+#[inline]
+pub fn get_tmp_bucket<T: Copy>(bucket: &[T]) -> Vec<T> {
+    bucket.to_vec()
+}
+
+// As `get_tmp_bucket`, but returns an error rather than aborting if the allocation fails.
+#[cfg(not(feature = "safe-only"))]
+#[allow(clippy::uninit_vec, unsafe_code)]
+#[inline]
+pub fn try_get_tmp_bucket<T>(bucket: &[T]) -> Result<Vec<T>, TryReserveError> {
+    let mut tmp_bucket = try_reserve_tmp_bucket(bucket.len())?;
+    unsafe {
+        // Safety: As with `get_tmp_bucket`
+        tmp_bucket.set_len(bucket.len());
+    }
+
+    Ok(tmp_bucket)
+}
+
+#[cfg(feature = "safe-only")]
+#[inline]
+pub fn try_get_tmp_bucket<T: Copy>(bucket: &[T]) -> Result<Vec<T>, TryReserveError> {
+    let mut tmp_bucket = try_reserve_tmp_bucket(bucket.len())?;
+    tmp_bucket.extend_from_slice(bucket);
+
+    Ok(tmp_bucket)
+}
+
+#[inline]
+fn try_reserve_tmp_bucket<T>(len: usize) -> Result<Vec<T>, TryReserveError> {
+    let mut tmp_bucket = Vec::new();
+    tmp_bucket.try_reserve_exact(len)?;
+
+    Ok(tmp_bucket)
+}
+
 #[inline]
 pub const fn cdiv(a: usize, b: usize) -> usize {
-    (a + b - 1) / b
+    a.div_ceil(b)
 }
 
 #[inline]
-pub fn get_tile_counts<T>(bucket: &[T], tile_size: usize, level: usize) -> (Vec<[usize; 256]>, bool)
+pub fn get_tile_counts<T>(
+    bucket: &[T],
+    tile_size: usize,
+    level: usize,
+    #[allow(unused)] params: &CountingParams,
+) -> (Vec<[usize; 256]>, bool)
 where
     T: RadixKey + Copy + Sized + Send + Sync,
 {
@@ -220,7 +391,7 @@ where
     #[cfg(feature = "multi-threaded")]
     let tiles: Vec<([usize; 256], bool, u8, u8)> = bucket
         .par_chunks(tile_size)
-        .map(|chunk| par_get_counts_with_ends(chunk, level))
+        .map(|chunk| par_get_counts_with_ends(chunk, level, params))
         .collect();
 
     #[cfg(not(feature = "multi-threaded"))]
@@ -275,33 +446,230 @@ pub fn is_homogenous_bucket(counts: &[usize; 256]) -> bool {
     true
 }
 
+/// `get_zero_levels` returns whether each level from `start_level` to `end_level` (inclusive) is
+/// zero for every item in the bucket, in a single pass. The pass stops early once every level has
+/// been found to hold a non-zero byte.
+#[inline]
+pub fn get_zero_levels<T>(bucket: &[T], start_level: usize, end_level: usize) -> Vec<bool>
+where
+    T: RadixKey,
+{
+    let mut seen = vec![0u8; end_level + 1 - start_level];
+
+    for chunk in bucket.chunks(256) {
+        for item in chunk {
+            for (s, level) in seen.iter_mut().zip(start_level..=end_level) {
+                *s |= item.get_level(level);
+            }
+        }
+
+        if seen.iter().all(|s| *s != 0) {
+            break;
+        }
+    }
+
+    seen.into_iter().map(|s| s == 0).collect()
+}
+
+/// `two_value_buckets` returns the two buckets in use if exactly two of the counts are non-zero.
+#[inline]
+pub fn two_value_buckets(counts: &[usize]) -> Option<(u8, u8)> {
+    let mut buckets = counts
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| **c > 0)
+        .map(|(b, _)| b as u8);
+
+    match (buckets.next(), buckets.next(), buckets.next()) {
+        (Some(low), Some(high), None) => Some((low, high)),
+        _ => None,
+    }
+}
+
+/// `cmp_by_levels` compares two items by their first `levels` levels, from the most significant
+/// level down, which is the order the radix sorts put them in.
+#[inline]
+pub fn cmp_by_levels<T>(a: &T, b: &T, levels: usize) -> Ordering
+where
+    T: RadixKey,
+{
+    for level in (0..levels).rev() {
+        let ord = a.get_level(level).cmp(&b.get_level(level));
+
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// `levels_as_u128` packs every level of a key of up to 16 levels into a `u128`, level `0` as the
+/// least significant byte, so that comparing the results compares the keys the way the radix
+/// sorts order them. This is several times faster than comparing level by level.
+#[inline]
+pub fn levels_as_u128<T: RadixKey>(v: &T) -> u128 {
+    let mut bytes = [0u8; 16];
+    v.get_all_levels(&mut bytes[..T::LEVELS]);
+
+    u128::from_le_bytes(bytes)
+}
+
+/// `is_sorted_by_levels` checks whether the bucket is already sorted by its first `levels` levels,
+/// comparing each adjacent pair from the most significant level down. It stops at the first pair
+/// found out of order, so it is cheap for unsorted data and a single pass for sorted data.
+#[inline]
+pub fn is_sorted_by_levels<T>(bucket: &[T], levels: usize) -> bool
+where
+    T: RadixKey,
+{
+    bucket.windows(2).all(|w| {
+        for level in (0..levels).rev() {
+            let a = w[0].get_level(level);
+            let b = w[1].get_level(level);
+
+            if a != b {
+                return a < b;
+            }
+        }
+
+        true
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::utils::get_tile_counts;
+    use super::try_reserve_tmp_bucket;
+    use crate::tuner::CountingParams;
+    use crate::utils::{
+        get_all_counts, get_counts, get_tile_counts, get_zero_levels, is_sorted_by_levels,
+        par_get_counts, try_get_tmp_bucket, two_value_buckets,
+    };
+    use crate::RadixKey;
+    use block_pseudorand::block_rand;
 
     #[test]
     pub fn test_get_tile_counts_correctly_marks_already_sorted_single_tile() {
-        let mut data: Vec<u8> = vec![0, 5, 2, 3, 1];
+        let data: Vec<u8> = vec![0, 5, 2, 3, 1];
 
-        let (_counts, already_sorted) = get_tile_counts(&mut data, 5, 0);
-        assert_eq!(already_sorted, false);
+        let (_counts, already_sorted) = get_tile_counts(&data, 5, 0, &CountingParams::default());
+        assert!(!already_sorted);
 
-        let mut data: Vec<u8> = vec![0, 0, 1, 1, 2];
+        let data: Vec<u8> = vec![0, 0, 1, 1, 2];
 
-        let (_counts, already_sorted) = get_tile_counts(&mut data, 5, 0);
-        assert_eq!(already_sorted, true);
+        let (_counts, already_sorted) = get_tile_counts(&data, 5, 0, &CountingParams::default());
+        assert!(already_sorted);
     }
 
     #[test]
     pub fn test_get_tile_counts_correctly_marks_already_sorted_multiple_tiles() {
-        let mut data: Vec<u8> = vec![0, 5, 2, 3, 1];
+        let data: Vec<u8> = vec![0, 5, 2, 3, 1];
+
+        let (_counts, already_sorted) = get_tile_counts(&data, 2, 0, &CountingParams::default());
+        assert!(!already_sorted);
 
-        let (_counts, already_sorted) = get_tile_counts(&mut data, 2, 0);
-        assert_eq!(already_sorted, false);
+        let data: Vec<u8> = vec![0, 0, 1, 1, 2];
 
-        let mut data: Vec<u8> = vec![0, 0, 1, 1, 2];
+        let (_counts, already_sorted) = get_tile_counts(&data, 2, 0, &CountingParams::default());
+        assert!(already_sorted);
+    }
 
-        let (_counts, already_sorted) = get_tile_counts(&mut data, 2, 0);
-        assert_eq!(already_sorted, true);
+    fn validate_get_all_counts<T>(data: &[T])
+    where
+        T: RadixKey,
+    {
+        let all_counts = get_all_counts(data);
+
+        for (level, counts) in all_counts.iter().enumerate() {
+            assert_eq!(*counts, get_counts(data, level).0);
+        }
+    }
+
+    #[test]
+    pub fn test_get_all_counts_matches_get_counts() {
+        validate_get_all_counts(&block_rand::<u8>(10_000));
+        validate_get_all_counts(&block_rand::<u32>(10_000));
+        validate_get_all_counts(&block_rand::<u128>(10_000));
+        validate_get_all_counts(&block_rand::<i64>(10_000));
+        validate_get_all_counts(&block_rand::<f32>(10_000));
+        validate_get_all_counts(&block_rand::<f64>(10_000));
+        validate_get_all_counts(&block_rand::<[u8; 3]>(10_000));
+    }
+
+    #[test]
+    pub fn test_is_sorted_by_levels() {
+        assert!(is_sorted_by_levels::<u32>(&[], 4));
+        assert!(is_sorted_by_levels(&[7u32], 4));
+        assert!(is_sorted_by_levels(&[1u32, 1, 256, 257, 65536], 4));
+        assert!(!is_sorted_by_levels(&[1u32, 256, 2], 4));
+
+        // Only the lowest level is considered, as with a partial RadixKey
+        assert!(is_sorted_by_levels(&[0x0100u16, 0x0200, 0x0101], 1));
+        assert!(!is_sorted_by_levels(&[0x0100u16, 0x0200, 0x0101], 2));
+
+        let mut data = block_rand::<u64>(10_000);
+        assert!(!is_sorted_by_levels(&data, u64::LEVELS));
+        data.sort_unstable();
+        assert!(is_sorted_by_levels(&data, u64::LEVELS));
+    }
+
+    #[test]
+    pub fn test_two_value_buckets() {
+        let mut counts = [0usize; 256];
+        assert_eq!(two_value_buckets(&counts), None);
+
+        counts[7] = 3;
+        assert_eq!(two_value_buckets(&counts), None);
+
+        counts[200] = 1;
+        assert_eq!(two_value_buckets(&counts), Some((7, 200)));
+
+        counts[255] = 1;
+        assert_eq!(two_value_buckets(&counts), None);
+    }
+
+    #[test]
+    pub fn test_try_get_tmp_bucket() {
+        assert_eq!(try_get_tmp_bucket(&[0u32; 1000]).unwrap().len(), 1000);
+        assert!(try_reserve_tmp_bucket::<u64>(usize::MAX / 4).is_err());
+    }
+
+    #[test]
+    pub fn test_get_zero_levels() {
+        let data: Vec<u64> = block_rand::<u64>(10_000)
+            .into_iter()
+            .map(|v| v & 0x00FF_0000_FF00_00FF)
+            .collect();
+
+        assert_eq!(
+            get_zero_levels(&data, 1, 7),
+            [true, true, false, true, true, false, true]
+        );
+        assert_eq!(get_zero_levels(&data, 0, 0), [false]);
+        assert_eq!(get_zero_levels::<u64>(&[], 0, 1), [true, true]);
+    }
+
+    #[test]
+    pub fn test_par_count_threshold_for() {
+        let params = CountingParams::default();
+
+        assert_eq!(params.par_count_threshold_for::<u32>(), 400_000);
+        assert_eq!(params.par_count_threshold_for::<u64>(), 200_000);
+        assert_eq!(params.par_count_threshold_for::<u8>(), 1_600_000);
+        assert_eq!(params.par_count_threshold_for::<[u8; 64]>(), 25_000);
+        assert_eq!(params.par_count_threshold_for::<[u8; 256]>(), 25_000);
+        assert_eq!(params.par_count_threshold_for::<()>(), 1_600_000);
+    }
+
+    #[test]
+    #[cfg(feature = "multi-threaded")]
+    pub fn test_par_get_counts_large_items() {
+        // Above the threshold for 256-byte items, but far below the one for 4-byte items
+        let data = block_rand::<[u8; 256]>(50_000);
+
+        assert_eq!(
+            par_get_counts(&data, 255, &CountingParams::default()),
+            get_counts(&data, 255)
+        );
     }
 }